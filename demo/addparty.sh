@@ -13,14 +13,14 @@ echo "~~~~~        NEW PARTY PROTOCOL         ~~~~"
 echo "~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~"
 echo ""
 
-killall gg18_keygen_client gg18_sign_client gg18_addparty_client 2> /dev/null
+killall mpecdsa 2> /dev/null
 
 sleep 2
 
 for i in $(seq 1 $((n + 1)))
 do
   echo "Creating key-gen client #$i out of $n"
-  ./target/$target/examples/gg18_addparty_client http://127.0.0.1:8001 keys$i.store &
+  ./target/$target/examples/mpecdsa add-party --parties $n --threshold $t --key keys$i.store &
   sleep 2
 done
 
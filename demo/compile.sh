@@ -1 +1 @@
-cargo build --examples --release
+cargo build --examples --release --features http
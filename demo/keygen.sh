@@ -21,7 +21,7 @@ echo "~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~"
 echo ""
 
 rm keys?.store
-killall sm_manager gg18_keygen_client gg18_sign_client 2> /dev/null
+killall sm_manager mpecdsa 2> /dev/null
 
 ./target/release/examples/sm_manager &
 
@@ -30,6 +30,6 @@ sleep 2
 for i in $(seq 1 $n)
 do
   echo "Creating key-gen client #$i out of $n"
-  ./target/$target/examples/gg18_keygen_client http://127.0.0.1:8001 keys$i.store &
+  ./target/$target/examples/mpecdsa keygen --parties $n --threshold $t --key keys$i.store &
   sleep 2
 done
@@ -25,18 +25,20 @@ for party in "$@"; do
   fi
 done
 
+signers=`echo "$@" | tr " " ","`
+
 echo "Multi-party ECDSA parties: $n, threshold: $t"
 echo "~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~"
 echo "~~~~~     ECDSA SIGNATURE PROTOCOL      ~~~~"
 echo "~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~"
 echo ""
 
-killall gg18_keygen_client gg18_sign_client 2> /dev/null
+killall mpecdsa 2> /dev/null
 
 sleep 2
 
 for party in "$@"; do
   echo "Signing for client #$party..."
-  ./target/$target/examples/gg18_sign_client http://127.0.0.1:8001 keys$party.store "PORUKA" &
+  ./target/$target/examples/mpecdsa sign --key keys$party.store --message "PORUKA" --signers $signers &
   sleep 2
 done
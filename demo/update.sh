@@ -37,7 +37,7 @@ echo "~~~~~        ECDSA SHARES UPDATE        ~~~~"
 echo "~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~"
 echo ""
 
-killall gg18_keygen_client gg18_sign_client gg18_addparty_client gg18_update_client 2> /dev/null
+killall mpecdsa 2> /dev/null
 
 sleep 2
 
@@ -45,6 +45,6 @@ echo "$#" > update_params.json
 
 for party in "$@"; do
   echo "Signing for client #$party..."
-  ./target/$target/examples/gg18_update_client http://127.0.0.1:8001 keys$party.store &
+  ./target/$target/examples/mpecdsa reshare --parties $# --key keys$party.store &
   sleep 2
 done
@@ -1,26 +1,18 @@
-use std::{env, iter::repeat, thread, time, time::Duration};
+use std::{env, thread, time, time::Duration};
 
-use crypto::{
-    aead::{AeadDecryptor, AeadEncryptor},
-    aes::KeySize::KeySize256,
-    aes_gcm::AesGcm,
-};
 use curv::{
     arithmetic::traits::Converter,
     elliptic::curves::traits::{ECPoint, ECScalar},
     BigInt, FE, GE,
 };
-use reqwest::Client;
+use multi_party_ecdsa::transport::echo::{compare, digest, echo_round, Echo};
+use multi_party_ecdsa::transport::http::HttpTransport;
+use multi_party_ecdsa::transport::identity::{IdentityKey, SignedMessage};
+use multi_party_ecdsa::transport::{Backoff, Transport, TransportError};
 use serde::{Deserialize, Serialize};
 
 pub type Key = String;
 
-#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
-pub struct AEAD {
-    pub ciphertext: Vec<u8>,
-    pub tag: Vec<u8>,
-}
-
 #[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub struct PartySignup {
     pub number: u16,
@@ -42,44 +34,130 @@ pub struct Entry {
 pub struct Params {
     pub parties: String,
     pub threshold: String,
+    // run an echo round after the commitment broadcasts, "echo": true in params.json
+    #[serde(default)]
+    pub echo: bool,
 }
 
-#[allow(dead_code)]
-pub fn aes_encrypt(key: &[u8], plaintext: &[u8]) -> AEAD {
-    let nonce: Vec<u8> = repeat(3).take(12).collect();
-    let aad: [u8; 0] = [];
-    let mut gcm = AesGcm::new(KeySize256, key, &nonce[..], &aad);
-    let mut out: Vec<u8> = repeat(0).take(plaintext.len()).collect();
-    let mut out_tag: Vec<u8> = repeat(0).take(16).collect();
-    gcm.encrypt(&plaintext[..], &mut out[..], &mut out_tag[..]);
-    AEAD {
-        ciphertext: out.to_vec(),
-        tag: out_tag.to_vec(),
+// a long poll for the messages to receiver in round from senders
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Wait {
+    pub round: String,
+    pub receiver: u16,
+    pub senders: Vec<u16>,
+    pub timeout_ms: u64,
+}
+
+// how long a party waits for the messages of one round before it aborts, unless ROUND_TIMEOUT
+// (seconds) says otherwise
+pub const ROUND_TIMEOUT_SECS: u64 = 300;
+
+pub fn round_timeout() -> Duration {
+    let secs = env::var("ROUND_TIMEOUT")
+        .ok()
+        .and_then(|secs| secs.parse().ok())
+        .unwrap_or(ROUND_TIMEOUT_SECS);
+    Duration::from_secs(secs)
+}
+
+// a ceremony created through POST /session on sm_manager
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct SessionParams {
+    pub protocol: String, // keygen, sign, addparty or update
+    pub parties: u16,
+    pub threshold: u16,
+    #[serde(default)]
+    pub signers: Option<Vec<u16>>, // party ids, sign only
+    #[serde(default)]
+    pub echo: bool,
+}
+
+// talks to the relay at address, e.g. http://127.0.0.1:8001. Without a session the clients
+// join the ceremony sm_manager runs with params.json.
+pub struct Client {
+    http: reqwest::Client,
+    address: String,
+    session: Option<String>,
+}
+
+impl Client {
+    #[allow(dead_code)]
+    pub fn new(address: &str, session: Option<String>) -> Self {
+        Client {
+            http: reqwest::Client::new(),
+            address: address.trim_end_matches('/').to_string(),
+            session,
+        }
     }
 }
 
+// parameters of the session to join, if there is one
+#[allow(dead_code)]
+pub fn read_params(client: &Client) -> Option<SessionParams> {
+    let id = client.session.as_ref()?;
+    let res_body = postb(client, &format!("session/{}", id), ()).unwrap();
+    let session: Result<SessionParams, ()> = serde_json::from_str(&res_body).unwrap();
+    Some(session.unwrap_or_else(|_| panic!("unknown session {}", id)))
+}
+
+// signs up to the session to join, or through path to the ceremony run with params.json
 #[allow(dead_code)]
-pub fn aes_decrypt(key: &[u8], aead_pack: AEAD) -> Vec<u8> {
-    let mut out: Vec<u8> = repeat(0).take(aead_pack.ciphertext.len()).collect();
-    let nonce: Vec<u8> = repeat(3).take(12).collect();
-    let aad: [u8; 0] = [];
-    let mut gcm = AesGcm::new(KeySize256, key, &nonce[..], &aad);
-    gcm.decrypt(&aead_pack.ciphertext[..], &mut out, &aead_pack.tag[..]);
-    out
+pub fn signup_to(client: &Client, path: &str) -> Result<PartySignup, ()> {
+    let res_body = match &client.session {
+        Some(id) => postb(client, &format!("session/{}/signup", id), ()).unwrap(),
+        None => postb(client, path, ()).unwrap(),
+    };
+    serde_json::from_str(&res_body).unwrap()
 }
 
+// data signed by party_num for round of session uuid
+#[allow(dead_code)]
+pub fn signed(
+    identity: &IdentityKey,
+    party_num: u16,
+    round: &str,
+    data: String,
+    uuid: &str,
+) -> String {
+    serde_json::to_string(&SignedMessage::new(identity, uuid, round, party_num, &data)).unwrap()
+}
+
+// the payload of msg, which party_num must have signed with public for round of session uuid
+#[allow(dead_code)]
+pub fn verified(public: &GE, party_num: u16, round: &str, msg: &str, uuid: &str) -> String {
+    let signed: SignedMessage = serde_json::from_str(msg).unwrap();
+    signed
+        .verify(public, uuid, round, party_num)
+        .unwrap_or_else(|_| panic!("bad signature from party {} in {}", party_num, round))
+}
+
+// verified payloads of what a poll returned, roster holds the identity keys by party_num - 1
+#[allow(dead_code)]
+pub fn verified_all(
+    roster: &[GE],
+    party_num: u16,
+    round: &str,
+    answers: Vec<String>,
+    uuid: &str,
+) -> Vec<String> {
+    (1..=roster.len() as u16)
+        .filter(|i| *i != party_num)
+        .zip(answers)
+        .map(|(i, msg)| verified(&roster[(i - 1) as usize], i, round, &msg, uuid))
+        .collect()
+}
+
+// the sm_manager endpoints that are not part of a round, e.g. signup
 pub fn postb<T>(client: &Client, path: &str, body: T) -> Option<String>
 where
     T: serde::ser::Serialize,
 {
-    let addr = env::args()
-        .nth(1)
-        .unwrap_or_else(|| "http://127.0.0.1:8001".to_string());
     let retries = 3;
     let retry_delay = time::Duration::from_millis(250);
     for _i in 1..retries {
         let res = client
-            .post(&format!("{}/{}", addr, path))
+            .http
+            .post(&format!("{}/{}", client.address, path))
             .json(&body)
             .send();
 
@@ -91,6 +169,26 @@ where
     None
 }
 
+// party_num's connection to the relay, pausing for delay whenever the relay is busy and giving
+// up on a round once the round timeout is over
+fn relay(client: &Client, party_num: u16, delay: Duration) -> HttpTransport {
+    let backoff = Backoff {
+        initial: delay,
+        timeout: Some(round_timeout()),
+        ..Backoff::default()
+    };
+    HttpTransport::new(&client.address, party_num, backoff)
+}
+
+// Err if the relay already holds a message under the same key
+fn sent(result: Result<(), TransportError>) -> Result<(), ()> {
+    match result {
+        Ok(()) => Ok(()),
+        Err(TransportError::Rejected(_)) => Err(()),
+        Err(e) => panic!("{:?}", e),
+    }
+}
+
 pub fn broadcast(
     client: &Client,
     party_num: u16,
@@ -98,14 +196,8 @@ pub fn broadcast(
     data: String,
     sender_uuid: String,
 ) -> Result<(), ()> {
-    let key = format!("{}-{}-{}", party_num, round, sender_uuid);
-    let entry = Entry {
-        key: key.clone(),
-        value: data,
-    };
-
-    let res_body = postb(&client, "set", entry).unwrap();
-    serde_json::from_str(&res_body).unwrap()
+    let mut relay = relay(client, party_num, Backoff::default().initial);
+    sent(relay.broadcast(&sender_uuid, round, &data))
 }
 
 pub fn sendp2p(
@@ -116,15 +208,8 @@ pub fn sendp2p(
     data: String,
     sender_uuid: String,
 ) -> Result<(), ()> {
-    let key = format!("{}-{}-{}-{}", party_from, party_to, round, sender_uuid);
-
-    let entry = Entry {
-        key: key.clone(),
-        value: data,
-    };
-
-    let res_body = postb(&client, "set", entry).unwrap();
-    serde_json::from_str(&res_body).unwrap()
+    let mut relay = relay(client, party_from, Backoff::default().initial);
+    sent(relay.send(&sender_uuid, round, party_to, &data))
 }
 
 pub fn poll_for_broadcasts(
@@ -135,25 +220,8 @@ pub fn poll_for_broadcasts(
     round: &str,
     sender_uuid: String,
 ) -> Vec<String> {
-    let mut ans_vec = Vec::new();
-    for i in 1..=n {
-        if i != party_num {
-            let key = format!("{}-{}-{}", i, round, sender_uuid);
-            let index = Index { key };
-            loop {
-                // add delay to allow the server to process request:
-                thread::sleep(delay);
-                let res_body = postb(client, "get", index.clone()).unwrap();
-                let answer: Result<Entry, ()> = serde_json::from_str(&res_body).unwrap();
-                if let Ok(answer) = answer {
-                    ans_vec.push(answer.value);
-                    println!("[{:?}] party {:?} => party {:?}", round, i, party_num);
-                    break;
-                }
-            }
-        }
-    }
-    ans_vec
+    let senders = (1..=n).filter(|i| *i != party_num).collect::<Vec<u16>>();
+    wait_for(client, party_num, &senders, delay, round, &sender_uuid)
 }
 
 pub fn poll_for_p2p(
@@ -164,25 +232,45 @@ pub fn poll_for_p2p(
     round: &str,
     sender_uuid: String,
 ) -> Vec<String> {
-    let mut ans_vec = Vec::new();
-    for i in 1..=n {
-        if i != party_num {
-            let key = format!("{}-{}-{}-{}", i, party_num, round, sender_uuid);
-            let index = Index { key };
-            loop {
-                // add delay to allow the server to process request:
-                thread::sleep(delay);
-                let res_body = postb(client, "get", index.clone()).unwrap();
-                let answer: Result<Entry, ()> = serde_json::from_str(&res_body).unwrap();
-                if let Ok(answer) = answer {
-                    ans_vec.push(answer.value);
-                    println!("[{:?}] party {:?} => party {:?}", round, i, party_num);
-                    break;
-                }
+    let senders = (1..=n).filter(|i| *i != party_num).collect::<Vec<u16>>();
+    wait_for(client, party_num, &senders, delay, round, &sender_uuid)
+}
+
+// the messages of every sender to party_num in round. Stops the party when another one aborts,
+// and aborts the session itself once the round timeout is over.
+pub fn wait_for(
+    client: &Client,
+    party_num: u16,
+    senders: &[u16],
+    delay: Duration,
+    round: &str,
+    sender_uuid: &str,
+) -> Vec<String> {
+    let mut relay = relay(client, party_num, delay);
+    match relay.receive_all(sender_uuid, round, senders) {
+        Ok(answer) => {
+            for envelope in &answer {
+                eprintln!(
+                    "[{:?}] party {:?} => party {:?}",
+                    round, envelope.sender, party_num
+                );
             }
+            answer.into_iter().map(|envelope| envelope.body).collect()
+        }
+        Err(TransportError::Aborted {
+            party,
+            round,
+            reason,
+        }) => panic!("party {} aborted in round {}: {}", party, round, reason),
+        Err(TransportError::Timeout { sender, .. }) => {
+            let reason = format!("no message from party {}", sender);
+            let _ = relay.abort(sender_uuid, round, &reason);
+            panic!("party {} timed out in round {}", sender, round);
         }
+        // the relay only fails waits on sessions it does not know, e.g. closed ones
+        Err(TransportError::Rejected(_)) => panic!("session {} is closed", sender_uuid),
+        Err(e) => panic!("{:?}", e),
     }
-    ans_vec
 }
 
 pub fn simple_send(
@@ -193,15 +281,7 @@ pub fn simple_send(
     data: String,
     sender_uuid: String,
 ) -> Result<(), ()> {
-    let key = format!("{}-{}-{}-{}", party_from, party_to, msg_label, sender_uuid);
-
-    let entry = Entry {
-        key: key.clone(),
-        value: data,
-    };
-
-    let res_body = postb(&client, "set", entry).unwrap();
-    serde_json::from_str(&res_body).unwrap()
+    sendp2p(client, party_from, party_to, msg_label, data, sender_uuid)
 }
 
 pub fn simple_poll(
@@ -212,47 +292,117 @@ pub fn simple_poll(
     msg_label: &str,
     sender_uuid: String,
 ) -> String {
-    let key = format!("{}-{}-{}-{}", party_from, party_to, msg_label, sender_uuid);
-    let index = Index { key };
-    loop {
-        // add delay to allow the server to process request:
-        thread::sleep(delay);
-        let res_body = postb(client, "get", index.clone()).unwrap();
-        let answer: Result<Entry, ()> = serde_json::from_str(&res_body).unwrap();
-        if let Ok(answer) = answer {
-            println!("[{:?}] party {:?} => party {:?}", msg_label, party_from, party_to);
-            return answer.value
+    wait_for(
+        client,
+        party_to,
+        &[party_from],
+        delay,
+        msg_label,
+        &sender_uuid,
+    )
+    .remove(0)
+}
+
+// echo round for the broadcasts of round, own being what party_num broadcast itself and
+// answers what poll_for_broadcasts returned. The echoes are signed, so the relay cannot forge
+// one to have an honest sender named, roster holds the identity keys by party_num - 1.
+// Panics on a differing digest naming the sender and the echoer, one of which lied.
+#[allow(dead_code, clippy::too_many_arguments)]
+pub fn check_echo(
+    client: &Client,
+    party_num: u16,
+    identity: &IdentityKey,
+    roster: &[GE],
+    delay: Duration,
+    round: &str,
+    own: &str,
+    answers: &[String],
+    sender_uuid: String,
+) {
+    let n = roster.len() as u16;
+    let others = (1..=n).filter(|i| *i != party_num).zip(answers);
+    let mut mine: Echo = others.map(|(i, msg)| (i, digest(msg))).collect();
+    mine.insert(party_num, digest(own));
+    let echo_round = echo_round(round);
+    assert!(broadcast(
+        client,
+        party_num,
+        &echo_round,
+        signed(
+            identity,
+            party_num,
+            &echo_round,
+            serde_json::to_string(&mine).unwrap(),
+            &sender_uuid,
+        ),
+        sender_uuid.clone()
+    )
+    .is_ok());
+    let echoes = poll_for_broadcasts(
+        client,
+        party_num,
+        n,
+        delay,
+        &echo_round,
+        sender_uuid.clone(),
+    );
+    let echoes = verified_all(roster, party_num, &echo_round, echoes, &sender_uuid);
+    let echoers = (1..=n).filter(|i| *i != party_num);
+    for (echoer, echo) in echoers.zip(echoes) {
+        let theirs: Echo = serde_json::from_str(&echo).unwrap();
+        if let Err(e) = compare(round, echoer, &mine, &theirs) {
+            panic!("{:?}", e);
         }
     }
 }
 
 #[allow(dead_code)]
 pub fn check_sig(r: &FE, s: &FE, msg: &BigInt, pk: &GE) {
-    use secp256k1::{verify, Message, PublicKey, PublicKeyFormat, Signature};
+    let is_correct = verify_sig(
+        &r.get_element()[..],
+        &s.get_element()[..],
+        msg,
+        &public_key(pk),
+    );
+    assert!(is_correct);
+}
+
+// whether (r, s) signs msg under pk, a serialized secp256k1 public key in any of its formats
+#[allow(dead_code)]
+pub fn verify_sig(r: &[u8], s: &[u8], msg: &BigInt, pk: &[u8]) -> bool {
+    use secp256k1::{verify, Message, PublicKey, Signature};
 
     let raw_msg = BigInt::to_vec(&msg);
+    if raw_msg.len() > 32 || r.len() > 32 || s.len() > 32 {
+        return false;
+    }
     let mut msg: Vec<u8> = Vec::new(); // padding
     msg.extend(vec![0u8; 32 - raw_msg.len()]);
     msg.extend(raw_msg.iter());
-
     let msg = Message::parse_slice(msg.as_slice()).unwrap();
+
+    let pk = match PublicKey::parse_slice(pk, None) {
+        Ok(pk) => pk,
+        Err(_) => return false,
+    };
+
+    let mut compact: Vec<u8> = Vec::new();
+    compact.extend(vec![0u8; 32 - r.len()]);
+    compact.extend(r.iter());
+    compact.extend(vec![0u8; 32 - s.len()]);
+    compact.extend(s.iter());
+    match Signature::parse_slice(compact.as_slice()) {
+        Ok(secp_sig) => verify(&msg, &secp_sig, &pk),
+        Err(_) => false,
+    }
+}
+
+// pk serialized uncompressed, with its 0x04 prefix
+#[allow(dead_code)]
+pub fn public_key(pk: &GE) -> Vec<u8> {
     let mut raw_pk = pk.pk_to_key_slice();
     if raw_pk.len() == 64 {
         raw_pk.insert(0, 4u8);
     }
-    let pk = PublicKey::parse_slice(&raw_pk, Some(PublicKeyFormat::Full)).unwrap();
-
-    let mut compact: Vec<u8> = Vec::new();
-    let bytes_r = &r.get_element()[..];
-    compact.extend(vec![0u8; 32 - bytes_r.len()]);
-    compact.extend(bytes_r.iter());
-
-    let bytes_s = &s.get_element()[..];
-    compact.extend(vec![0u8; 32 - bytes_s.len()]);
-    compact.extend(bytes_s.iter());
-
-    let secp_sig = Signature::parse_slice(compact.as_slice()).unwrap();
-
-    let is_correct = verify(&msg, &secp_sig, &pk);
-    assert!(is_correct);
+    raw_pk
 }
@@ -0,0 +1,513 @@
+/// mpecdsa add-party: the parties of a key give a new party a share of it. PARTIES of them take
+/// part, signed up in any order, and announce their ids in a signed round0; the first t + 1 to
+/// sign up hand the new party its share. The new party signs up last and is given the next id
+/// of the key, PartyId::next, the ids of parties that left are not reused. Parties of the key
+/// that do not take part keep their key files as they were and do not know the new party.
+use curv::{
+  cryptographic_primitives::secret_sharing::feldman_vss::VerifiableSS,
+  elliptic::curves::traits::{ECPoint, ECScalar},
+  FE, GE,
+};
+use multi_party_ecdsa::protocols::multi_party_ecdsa::gg_2018::party_i::{Keys, SharedKeys};
+use multi_party_ecdsa::protocols::multi_party_ecdsa::gg_2018::party_id::{self, PartyId};
+use multi_party_ecdsa::protocols::multi_party_ecdsa::gg_2018::refresh;
+use multi_party_ecdsa::transport::identity::{IdentityKey, SignedMessage};
+use multi_party_ecdsa::transport::secure::{channel_run, run_nonce, Sealed, SecureChannel};
+use multi_party_ecdsa::Error::{DuplicatePartyId, InvalidPartyId};
+use paillier::EncryptionKey;
+use serde_json::{json, Value};
+use std::{fs, time};
+
+use crate::common::{
+  broadcast, poll_for_broadcasts, simple_poll, simple_send, Client, PartySignup, public_key,
+  signed, signup_to, verified,
+};
+use crate::load_key;
+
+// PARTIES is the number of parties of the key taking part, THRESHOLD that of the key, key_file
+// is where the new party saves its key and the file the others update
+pub fn run(client: &Client, PARTIES: u16, THRESHOLD: u16, key_file: &str) -> Value {
+  assert!(
+    PARTIES > THRESHOLD,
+    "add-party needs {} parties of the key, not {}", THRESHOLD + 1, PARTIES
+  );
+  // signup:
+  let (party_num_int, uuid) = match signup(&client).unwrap() {
+      PartySignup { number, uuid } => (number, uuid),
+  };
+  eprintln!("number: {:?}, uuid: {:?}", party_num_int, uuid);
+
+  if party_num_int > PARTIES {
+    call_new_party(PARTIES, THRESHOLD, &client, party_num_int, uuid.clone(), key_file)
+  } else {
+    call_existing_party(PARTIES, THRESHOLD, &client, party_num_int, uuid.clone(), key_file)
+  }
+}
+
+
+pub fn call_new_party(
+  PARTIES: u16,
+  THRESHOLD: u16,
+  client: &Client,
+  party_num_int: u16,
+  uuid: String,
+  key_file: &str,
+) -> Value {
+  // delay:
+  let delay = time::Duration::from_millis(25);
+
+  let identity = IdentityKey::new();
+  // the nonces the p2p channels of this run are keyed with
+  let nonce = run_nonce();
+
+  // Posalji public key svima...
+  for i in 1..=PARTIES {
+    assert!(simple_send(
+      &client,
+      party_num_int,
+      i,
+      "np_pub_key",  // np stands for "new party"
+      signed(
+        &identity,
+        party_num_int,
+        "np_pub_key",
+        serde_json::to_string(&(&nonce, identity.public)).unwrap(),
+        &uuid,
+      ),
+      uuid.clone(),
+    )
+    .is_ok());
+  }
+
+  // the parties of the key say which ones they are, and hand out the identity keys and the
+  // commitments of the key. Those are taken on first use, every party has to send the same.
+  let round0_ans_vec = poll_for_broadcasts(
+    &client,
+    party_num_int,
+    PARTIES + 1,
+    delay,
+    "round0",
+    uuid.clone(),
+  );
+  let mut orig_pub_key_ans_vec: Vec<String> = Vec::new();
+  let mut key: Option<(Vec<GE>, VerifiableSS)> = None;
+  for i in 1..=PARTIES {
+    let orig_pub_key_ans = simple_poll(
+      &client,
+      i,
+      party_num_int,
+      delay,
+      "orig_pub_key",
+      uuid.clone(),
+    );
+    let signed: SignedMessage = serde_json::from_str(&orig_pub_key_ans).unwrap();
+    let key_i: (Vec<GE>, VerifiableSS) = serde_json::from_str(&signed.payload).unwrap();
+    match &key {
+      None => key = Some(key_i),
+      Some(key) => assert!(*key == key_i, "party {} has another key", i),
+    }
+    orig_pub_key_ans_vec.push(orig_pub_key_ans);
+  }
+  let (mut key_roster, vss_scheme) = key.unwrap();
+  assert!(key_roster.len() == vss_scheme.parameters.share_count);
+
+  let mut ids: Vec<PartyId> = Vec::new();
+  for round0_ans in &round0_ans_vec {
+    let signed: SignedMessage = serde_json::from_str(round0_ans).unwrap();
+    let (id_i, _): (PartyId, Vec<u8>) = serde_json::from_str(&signed.payload).unwrap();
+    ids.push(id_i);
+  }
+  match party_id::indices(&ids, &vss_scheme) {
+    Err(InvalidPartyId { party }) => panic!("there is no party {}", party),
+    Err(DuplicatePartyId { party }) => panic!("party {} takes part twice", party),
+    _ => (),
+  }
+  // identity keys of the parties taking part and the nonces of their channels, by signup number
+  let mut roster: Vec<GE> = Vec::new();
+  let mut nonces: Vec<Vec<u8>> = Vec::new();
+  for i in 1..=PARTIES {
+    let public = key_roster[ids[(i - 1) as usize].index()];
+    let payload = verified(&public, i, "round0", &round0_ans_vec[(i - 1) as usize], &uuid);
+    let (_, nonce_i): (PartyId, Vec<u8>) = serde_json::from_str(&payload).unwrap();
+    verified(&public, i, "orig_pub_key", &orig_pub_key_ans_vec[(i - 1) as usize], &uuid);
+    roster.push(public);
+    nonces.push(nonce_i);
+  }
+  let party_id = PartyId::next(&vss_scheme).expect("no party ids left for the key");
+  let party_keys = Keys::create(party_id.id() as usize);
+
+  ///////////////////////////////////////////////////////////////////////////////
+  for i in 1..=PARTIES {
+    assert!(simple_send(
+      &client,
+      party_num_int,
+      i,
+      "np_paillier_key",
+      signed(&identity, party_num_int, "np_paillier_key", serde_json::to_string(&party_keys.ek).unwrap(), &uuid),
+      uuid.clone(),
+    )
+    .is_ok());
+  }
+
+  // Pribavi javni kljuc od svih ostalih
+  let mut paillier_key_vec: Vec<EncryptionKey> = Vec::new();
+  for i in 1..=PARTIES {
+    let orig_paillier_key_ans = simple_poll(
+      &client,
+      i,
+      party_num_int,
+      delay,
+      "orig_paillier_key",
+      uuid.clone(),
+    );
+    let orig_paillier_key_ans = verified(&roster[(i - 1) as usize], i, "orig_paillier_key", &orig_paillier_key_ans, &uuid);
+    let paillier_keys: Vec<EncryptionKey> = serde_json::from_str(&orig_paillier_key_ans).unwrap();
+    if i == 1 {
+      paillier_key_vec = paillier_keys;
+    } else {
+      assert!(paillier_keys == paillier_key_vec, "party {} has other paillier keys", i);
+    }
+  }
+  assert!(paillier_key_vec.len() == vss_scheme.parameters.share_count);
+
+  let y_sum_ans = simple_poll(
+    &client,
+    1,
+    party_num_int,
+    delay,
+    "y_sum",
+    uuid.clone(),
+  );
+  let y_sum_ans = verified(&roster[0], 1, "y_sum", &y_sum_ans, &uuid);
+  let y_sum: GE = serde_json::from_str(&y_sum_ans).unwrap();
+  for i in 2..=PARTIES {
+    let y_ans = simple_poll(
+      &client,
+      i,
+      party_num_int,
+      delay,
+      "y_sum",
+      uuid.clone(),
+    );
+    let y_ans = verified(&roster[(i - 1) as usize], i, "y_sum", &y_ans, &uuid);
+    let y: GE = serde_json::from_str(&y_ans).unwrap();
+    assert!(y_sum == y);
+  }
+
+  /////////////////////////////////////////////////////////
+  let mut x_i: FE = FE::zero();
+  for i in 1..=(THRESHOLD+1) {
+    let share_part_ans = simple_poll(
+      &client,
+      i,
+      party_num_int,
+      delay,
+      "share_part",
+      uuid.clone(),
+    );
+    let share_part_ans = verified(&roster[(i - 1) as usize], i, "share_part", &share_part_ans, &uuid);
+    let sealed: Sealed = serde_json::from_str(&share_part_ans).unwrap();
+    let mut channel = SecureChannel::new(
+      &uuid,
+      &channel_run(party_num_int, &nonce, i, &nonces[(i - 1) as usize]),
+      party_num_int,
+      &identity.secret,
+      i,
+      &roster[(i - 1) as usize],
+    );
+    let share_part = channel
+      .open("share_part", &sealed)
+      .expect("invalid share_part ciphertext");
+    let share_part: FE = serde_json::from_slice(&share_part).unwrap();
+    x_i = x_i + share_part;
+  }
+
+  let shared_keys: SharedKeys = SharedKeys {y: y_sum, x_i};
+
+  // Add myself to public vectors
+  paillier_key_vec.push(party_keys.ek.clone());
+  key_roster.push(identity.public);
+
+  let keygen_json = serde_json::to_string(&(
+    party_keys,
+    shared_keys,
+    party_id,
+    refresh::add_party_vss_scheme(&vss_scheme),
+    paillier_key_vec,
+    y_sum,
+    identity,
+    key_roster,
+  ))
+  .unwrap();
+
+  let comm: GE = party_id.public_share(&vss_scheme);
+  let g: GE = ECPoint::generator();
+  let comm_expect: GE = g * x_i;
+  assert!(comm == comm_expect);
+
+  fs::write(key_file, keygen_json).expect("Unable to save !");
+
+  json!({
+    "party_id": party_id,
+    "parties": vss_scheme.parameters.share_count + 1,
+    "threshold": THRESHOLD,
+    "public_key": hex::encode(public_key(&y_sum)),
+    "key_file": key_file,
+  })
+}
+
+
+///////////////////////////////////////////////////////
+/////// ~~~~~~~ EXISTING PARTY ~~~~~~~~~ //////////////
+///////////////////////////////////////////////////////
+pub fn call_existing_party(
+  PARTIES: u16,
+  THRESHOLD: u16,
+  client: &Client,
+  party_num_int: u16,
+  uuid: String,
+  key_file: &str,
+) -> Value {
+  // delay:
+  let delay = time::Duration::from_millis(25);
+
+  // Ucitaj keys{party_id}.store
+  let (party_keys, shared_keys, party_id, vss_scheme, paillier_key_vector, y_sum, identity, mut key_roster) =
+    load_key(key_file);
+
+  // round 0: say which party of the key this is, with the nonce of its channel to the new party
+  let nonce = run_nonce();
+  assert!(broadcast(
+    &client,
+    party_num_int,
+    "round0",
+    signed(
+      &identity,
+      party_num_int,
+      "round0",
+      serde_json::to_string(&(party_id, &nonce)).unwrap(),
+      &uuid,
+    ),
+    uuid.clone()
+  )
+  .is_ok());
+
+  let round0_ans_vec = poll_for_broadcasts(
+      &client,
+      party_num_int,
+      PARTIES,
+      delay,
+      "round0",
+      uuid.clone(),
+  );
+
+  let mut j = 0;
+  let mut ids: Vec<PartyId> = Vec::new();
+  // identity keys of the parties of the key taking part and the nonces of their channels, by
+  // signup number
+  let mut roster: Vec<GE> = Vec::new();
+  let mut nonces: Vec<Vec<u8>> = Vec::new();
+  for i in 1..=PARTIES {
+      if i == party_num_int {
+          ids.push(party_id);
+          roster.push(identity.public);
+          nonces.push(nonce.clone());
+      } else {
+          // the party id is only trusted once the message verifies under its keygen identity
+          let signed: SignedMessage = serde_json::from_str(&round0_ans_vec[j]).unwrap();
+          let (id_j, nonce_j): (PartyId, Vec<u8>) = serde_json::from_str(&signed.payload).unwrap();
+          assert!(
+            party_id::indices(&[id_j], &vss_scheme).is_ok(),
+            "there is no party {}", id_j
+          );
+          let public = key_roster[id_j.index()];
+          verified(&public, i, "round0", &round0_ans_vec[j], &uuid);
+          ids.push(id_j);
+          roster.push(public);
+          nonces.push(nonce_j);
+          j += 1;
+      }
+  }
+  if let Err(DuplicatePartyId { party }) = party_id::indices(&ids, &vss_scheme) {
+    panic!("party {} takes part twice", party);
+  }
+  // the first t + 1 to sign up hand the new party its share
+  let helpers = &ids[..=(THRESHOLD as usize)];
+
+  // Pribavi javni kljuc od novog korisnika
+  let np_pub_key_ans = simple_poll(
+    &client,
+    PARTIES + 1,
+    party_num_int,
+    delay,
+    "np_pub_key",
+    uuid.clone(),
+  );
+  // the new party has no identity yet, it is taken on first use and kept in the roster
+  let signed_np: SignedMessage = serde_json::from_str(&np_pub_key_ans).unwrap();
+  let (_, np_identity): (Vec<u8>, GE) = serde_json::from_str(&signed_np.payload).unwrap();
+  let np_payload = verified(&np_identity, PARTIES + 1, "np_pub_key", &np_pub_key_ans, &uuid);
+  let (np_nonce, _): (Vec<u8>, GE) = serde_json::from_str(&np_payload).unwrap();
+
+  // the identity keys and commitments of the key, the new party has neither yet
+  assert!(simple_send(
+    &client,
+    party_num_int,
+    PARTIES + 1,
+    "orig_pub_key",
+    signed(
+      &identity,
+      party_num_int,
+      "orig_pub_key",
+      serde_json::to_string(&(&key_roster, &vss_scheme)).unwrap(),
+      &uuid,
+    ),
+    uuid.clone(),
+  )
+  .is_ok());
+
+  ///////////////////////////////////////////////////////////////////////////////
+  let np_paillier_key_ans = simple_poll(
+    &client,
+    PARTIES + 1,
+    party_num_int,
+    delay,
+    "np_paillier_key",
+    uuid.clone(),
+  );
+  let np_paillier_key_ans = verified(&np_identity, PARTIES + 1, "np_paillier_key", &np_paillier_key_ans, &uuid);
+  let np_ek: EncryptionKey = serde_json::from_str(&np_paillier_key_ans).unwrap();
+
+  // Posalji paillier javne kljuceve svih stranaka
+  assert!(simple_send(
+    &client,
+    party_num_int,
+    PARTIES + 1,
+    "orig_paillier_key",
+    signed(&identity, party_num_int, "orig_paillier_key", serde_json::to_string(&paillier_key_vector).unwrap(), &uuid),
+    uuid.clone(),
+  )
+  .is_ok());
+
+  assert!(simple_send(
+    &client,
+    party_num_int,
+    PARTIES + 1,
+    "y_sum",
+    signed(&identity, party_num_int, "y_sum", serde_json::to_string(&y_sum).unwrap(), &uuid),
+    uuid.clone(),
+  )
+  .is_ok());
+
+  ////////////////////////////////////////////////////////////////////
+  if party_num_int <= THRESHOLD + 1 {
+    let np_x_part: FE = refresh::add_party_share_part(party_id, &shared_keys.x_i, helpers, &vss_scheme)
+      .expect("invalid helper set");
+    // the masks are sealed too, the relay seeing them could unmask the share parts
+    let helper_channel = |i: u16| {
+      SecureChannel::new(
+        &uuid,
+        &channel_run(party_num_int, &nonce, i, &nonces[(i - 1) as usize]),
+        party_num_int,
+        &identity.secret,
+        i,
+        &roster[(i - 1) as usize],
+      )
+    };
+    let mut random_sum: FE = FE::zero();
+    for i in 1..=(THRESHOLD + 1) {
+      if i != party_num_int {
+        let random_value: FE = ECScalar::new_random();
+        random_sum = random_sum + random_value;
+        let sealed = helper_channel(i).seal("random_value", &serde_json::to_vec(&random_value).unwrap());
+        assert!(simple_send(
+          &client,
+          party_num_int,
+          i,
+          "random_value",
+          signed(&identity, party_num_int, "random_value", serde_json::to_string(&sealed).unwrap(), &uuid),
+          uuid.clone(),
+        )
+        .is_ok());
+      }
+    }
+    let mut my_part: FE = np_x_part.sub(&random_sum.get_element());
+    assert!(my_part + random_sum == np_x_part);
+    for i in 1..=(THRESHOLD + 1) {
+      if i != party_num_int {
+        let recv_value_ans = simple_poll(
+          &client,
+          i,
+          party_num_int,
+          delay,
+          "random_value",
+          uuid.clone(),
+        );
+        let recv_value_ans = verified(&roster[(i - 1) as usize], i, "random_value", &recv_value_ans, &uuid);
+        let sealed: Sealed = serde_json::from_str(&recv_value_ans).unwrap();
+        let recv_value = helper_channel(i)
+          .open("random_value", &sealed)
+          .expect("invalid random_value ciphertext");
+        let recv_value: FE = serde_json::from_slice(&recv_value).unwrap();
+        my_part = my_part + recv_value;
+      }
+    }
+
+    // sealed, anyone seeing all the share parts could add up the new party's share
+    let mut channel = SecureChannel::new(
+      &uuid,
+      &channel_run(party_num_int, &nonce, PARTIES + 1, &np_nonce),
+      party_num_int,
+      &identity.secret,
+      PARTIES + 1,
+      &np_identity,
+    );
+    let sealed = channel.seal("share_part", &serde_json::to_vec(&my_part).unwrap());
+    assert!(simple_send(
+      &client,
+      party_num_int,
+      PARTIES + 1,
+      "share_part",
+      signed(&identity, party_num_int, "share_part", serde_json::to_string(&sealed).unwrap(), &uuid),
+      uuid.clone(),
+    )
+    .is_ok());
+  }
+
+  // update data...
+  // Write new party's Paillier encryption key
+  let mut paillier_key_vec: Vec<EncryptionKey> = paillier_key_vector.clone();
+  paillier_key_vec.push(np_ek);
+  key_roster.push(np_identity);
+
+  let keygen_json = serde_json::to_string(&(
+    party_keys,
+    shared_keys,
+    party_id,
+    refresh::add_party_vss_scheme(&vss_scheme),
+    paillier_key_vec,
+    y_sum,
+    identity,
+    key_roster,
+  ))
+  .unwrap();
+
+  fs::write(key_file, keygen_json).expect("Unable to save !");
+
+  json!({
+    "party_id": party_id,
+    "parties": vss_scheme.parameters.share_count + 1,
+    "threshold": THRESHOLD,
+    "public_key": hex::encode(public_key(&y_sum)),
+    "key_file": key_file,
+  })
+}
+
+pub fn signup(client: &Client) -> Result<PartySignup, ()> {
+  signup_to(client, "signupaddparty")
+}
+
+pub fn do_vecs_match<T: PartialEq>(a: &Vec<T>, b: &Vec<T>) -> bool {
+  let matching = a.iter().zip(b.iter()).filter(|&(a, b)| a == b).count();
+  matching == a.len() && matching == b.len()
+}
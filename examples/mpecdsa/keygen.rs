@@ -1,44 +1,28 @@
-#![allow(non_snake_case)]
-/// to run:
-/// 1: go to rocket_server -> cargo run
-/// 2: cargo run from PARTIES number of terminals
+/// mpecdsa keygen: every party creates its share of a new key and saves it to its key file.
 use curv::{
     arithmetic::traits::Converter,
     cryptographic_primitives::{
         proofs::sigma_dlog::DLogProof, secret_sharing::feldman_vss::VerifiableSS,
     },
-    elliptic::curves::traits::{ECPoint, ECScalar},
+    elliptic::curves::traits::ECScalar,
     BigInt, FE, GE,
 };
 use multi_party_ecdsa::protocols::multi_party_ecdsa::gg_2018::party_i::{
     KeyGenBroadcastMessage1, KeyGenDecommitMessage1, Keys, Parameters,
 };
+use multi_party_ecdsa::protocols::multi_party_ecdsa::gg_2018::party_id::PartyId;
+use multi_party_ecdsa::transport::identity::{IdentityKey, SignedMessage};
+use multi_party_ecdsa::transport::secure::{channel_run, run_nonce, Sealed, SecureChannel};
 use paillier::EncryptionKey;
-use reqwest::Client;
-use std::{env, fs, time};
+use serde_json::{json, Value};
+use std::{fs, time};
 
-mod common;
-use common::{
-    aes_decrypt, aes_encrypt, broadcast, poll_for_broadcasts, poll_for_p2p, postb, sendp2p, Params,
-    PartySignup, AEAD,
+use crate::common::{
+    broadcast, check_echo, poll_for_broadcasts, poll_for_p2p, public_key, sendp2p, signed,
+    signup_to, verified, verified_all, Client, PartySignup,
 };
 
-fn main() {
-    if env::args().nth(3).is_some() {
-        panic!("too many arguments")
-    }
-    if env::args().nth(2).is_none() {
-        panic!("too few arguments")
-    }
-    //read parameters:
-    let data = fs::read_to_string("params.json")
-        .expect("Unable to read params, make sure config file is present in the same folder ");
-    let params: Params = serde_json::from_str(&data).unwrap();
-    let PARTIES: u16 = params.parties.parse::<u16>().unwrap();
-    let THRESHOLD: u16 = params.threshold.parse::<u16>().unwrap();
-
-    let client = Client::new();
-
+pub fn run(client: &Client, PARTIES: u16, THRESHOLD: u16, echo: bool, key_file: &str) -> Value {
     // delay:
     let delay = time::Duration::from_millis(25);
     let params = Parameters {
@@ -50,17 +34,30 @@ fn main() {
     let (party_num_int, uuid) = match signup(&client).unwrap() {
         PartySignup { number, uuid } => (number, uuid),
     };
-    println!("number: {:?}, uuid: {:?}", party_num_int, uuid);
+    eprintln!("number: {:?}, uuid: {:?}", party_num_int, uuid);
+    // the signup number is the party's id for the life of the key
+    let party_id = PartyId::new(party_num_int).expect("signup number 0");
 
     let party_keys = Keys::create(party_num_int as usize);
+    let identity = IdentityKey::new();
+    let nonce = run_nonce();
     let (bc_i, decom_i) = party_keys.phase1_broadcast_phase3_proof_of_correct_key();
 
-    // send commitment to ephemeral public keys, get round 1 commitments of other parties
+    // send commitment to ephemeral public keys, get round 1 commitments of other parties.
+    // the identity keys signing every later message are fixed here, each one signing its own,
+    // with the nonces the p2p channels of this run are keyed with
+    let round1_msg = signed(
+        &identity,
+        party_num_int,
+        "round1",
+        serde_json::to_string(&(bc_i.clone(), identity.public, &nonce)).unwrap(),
+        &uuid,
+    );
     assert!(broadcast(
         &client,
         party_num_int,
         "round1",
-        serde_json::to_string(&bc_i).unwrap(),
+        round1_msg.clone(),
         uuid.clone()
     )
     .is_ok());
@@ -72,20 +69,52 @@ fn main() {
         "round1",
         uuid.clone(),
     );
-
-    let mut bc1_vec = round1_ans_vec
-        .iter()
-        .map(|m| serde_json::from_str::<KeyGenBroadcastMessage1>(m).unwrap())
-        .collect::<Vec<_>>();
+    let mut bc1_vec: Vec<KeyGenBroadcastMessage1> = Vec::new();
+    let mut roster: Vec<GE> = Vec::new();
+    let mut nonces: Vec<Vec<u8>> = Vec::new();
+    let senders = (1..=PARTIES).filter(|i| *i != party_num_int);
+    for (i, msg) in senders.zip(&round1_ans_vec) {
+        let signed_msg: SignedMessage = serde_json::from_str(msg).unwrap();
+        let (_, identity_j, _): (KeyGenBroadcastMessage1, GE, Vec<u8>) =
+            serde_json::from_str(&signed_msg.payload).unwrap();
+        let payload = verified(&identity_j, i, "round1", msg, &uuid);
+        let (bc1_j, _, nonce_j): (KeyGenBroadcastMessage1, GE, Vec<u8>) =
+            serde_json::from_str(&payload).unwrap();
+        bc1_vec.push(bc1_j);
+        roster.push(identity_j);
+        nonces.push(nonce_j);
+    }
 
     bc1_vec.insert(party_num_int as usize - 1, bc_i);
+    roster.insert(party_num_int as usize - 1, identity.public);
+    nonces.insert(party_num_int as usize - 1, nonce.clone());
+
+    if echo {
+        check_echo(
+            &client,
+            party_num_int,
+            &identity,
+            &roster,
+            delay,
+            "round1",
+            &round1_msg,
+            &round1_ans_vec,
+            uuid.clone(),
+        );
+    }
 
     // send ephemeral public keys and check commitments correctness
     assert!(broadcast(
         &client,
         party_num_int,
         "round2",
-        serde_json::to_string(&decom_i).unwrap(),
+        signed(
+            &identity,
+            party_num_int,
+            "round2",
+            serde_json::to_string(&decom_i).unwrap(),
+            &uuid,
+        ),
         uuid.clone()
     )
     .is_ok());
@@ -97,11 +126,12 @@ fn main() {
         "round2",
         uuid.clone(),
     );
+    let round2_ans_vec = verified_all(&roster, party_num_int, "round2", round2_ans_vec, &uuid);
 
     let mut j = 0;
     let mut point_vec: Vec<GE> = Vec::new();
     let mut decom_vec: Vec<KeyGenDecommitMessage1> = Vec::new();
-    let mut enc_keys: Vec<BigInt> = Vec::new();
+    let mut channels: Vec<SecureChannel> = Vec::new();
     for i in 1..=PARTIES {
         if i == party_num_int {
             point_vec.push(decom_i.y_i);
@@ -110,7 +140,14 @@ fn main() {
             let decom_j: KeyGenDecommitMessage1 = serde_json::from_str(&round2_ans_vec[j]).unwrap();
             point_vec.push(decom_j.y_i);
             decom_vec.push(decom_j.clone());
-            enc_keys.push((decom_j.y_i.clone() * party_keys.u_i).x_coor().unwrap());
+            channels.push(SecureChannel::new(
+                &uuid,
+                &channel_run(party_num_int, &nonce, i, &nonces[(i - 1) as usize]),
+                party_num_int,
+                &identity.secret,
+                i,
+                &roster[(i - 1) as usize],
+            ));
             j = j + 1;
         }
     }
@@ -130,15 +167,20 @@ fn main() {
     for (k, i) in (1..=PARTIES).enumerate() {
         if i != party_num_int {
             // prepare encrypted ss for party i:
-            let key_i = BigInt::to_vec(&enc_keys[j]);
             let plaintext = BigInt::to_vec(&secret_shares[k].to_big_int());
-            let aead_pack_i = aes_encrypt(&key_i, &plaintext);
+            let sealed = channels[j].seal("round3", &plaintext);
             assert!(sendp2p(
                 &client,
                 party_num_int,
                 i,
                 "round3",
-                serde_json::to_string(&aead_pack_i).unwrap(),
+                signed(
+                    &identity,
+                    party_num_int,
+                    "round3",
+                    serde_json::to_string(&sealed).unwrap(),
+                    &uuid,
+                ),
                 uuid.clone()
             )
             .is_ok());
@@ -154,6 +196,7 @@ fn main() {
         "round3",
         uuid.clone(),
     );
+    let round3_ans_vec = verified_all(&roster, party_num_int, "round3", round3_ans_vec, &uuid);
 
     let mut j = 0;
     let mut party_shares: Vec<FE> = Vec::new();
@@ -161,9 +204,10 @@ fn main() {
         if i == party_num_int {
             party_shares.push(secret_shares[(i - 1) as usize]);
         } else {
-            let aead_pack: AEAD = serde_json::from_str(&round3_ans_vec[j]).unwrap();
-            let key_i = BigInt::to_vec(&enc_keys[j]);
-            let out = aes_decrypt(&key_i, aead_pack);
+            let sealed: Sealed = serde_json::from_str(&round3_ans_vec[j]).unwrap();
+            let out = channels[j]
+                .open("round3", &sealed)
+                .expect("invalid share ciphertext");
             let out_bn = BigInt::from(&out[..]);
             let out_fe = ECScalar::from(&out_bn);
             party_shares.push(out_fe);
@@ -177,7 +221,13 @@ fn main() {
         &client,
         party_num_int,
         "round4",
-        serde_json::to_string(&vss_scheme).unwrap(),
+        signed(
+            &identity,
+            party_num_int,
+            "round4",
+            serde_json::to_string(&vss_scheme).unwrap(),
+            &uuid,
+        ),
         uuid.clone()
     )
     .is_ok());
@@ -189,6 +239,7 @@ fn main() {
         "round4",
         uuid.clone(),
     );
+    let round4_ans_vec = verified_all(&roster, party_num_int, "round4", round4_ans_vec, &uuid);
 
     let mut j = 0;
     let mut vss_scheme_vec: Vec<VerifiableSS> = Vec::new();
@@ -217,7 +268,13 @@ fn main() {
         &client,
         party_num_int,
         "round5",
-        serde_json::to_string(&dlog_proof).unwrap(),
+        signed(
+            &identity,
+            party_num_int,
+            "round5",
+            serde_json::to_string(&dlog_proof).unwrap(),
+            &uuid,
+        ),
         uuid.clone()
     )
     .is_ok());
@@ -229,6 +286,7 @@ fn main() {
         "round5",
         uuid.clone(),
     );
+    let round5_ans_vec = verified_all(&roster, party_num_int, "round5", round5_ans_vec, &uuid);
 
     let mut j = 0;
     let mut dlog_proof_vec: Vec<DLogProof> = Vec::new();
@@ -264,18 +322,25 @@ fn main() {
     let keygen_json = serde_json::to_string(&(
         party_keys,
         shared_keys,
-        party_num_int,
+        party_id,
         one_vss_scheme,
         paillier_key_vec,
         y_sum,
+        identity,
+        roster,
     ))
     .unwrap();
-    fs::write(env::args().nth(2).unwrap(), keygen_json).expect("Unable to save !");
+    fs::write(key_file, keygen_json).expect("Unable to save !");
+
+    json!({
+        "party_id": party_id,
+        "parties": PARTIES,
+        "threshold": THRESHOLD,
+        "public_key": hex::encode(public_key(&y_sum)),
+        "key_file": key_file,
+    })
 }
 
 pub fn signup(client: &Client) -> Result<PartySignup, ()> {
-    let key = "signup-keygen".to_string();
-
-    let res_body = postb(&client, "signupkeygen", key).unwrap();
-    serde_json::from_str(&res_body).unwrap()
+    signup_to(client, "signupkeygen")
 }
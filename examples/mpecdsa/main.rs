@@ -0,0 +1,347 @@
+#![allow(non_snake_case)]
+/// mpecdsa: runs the gg18 ceremonies through sm_manager and looks into the key files they save.
+/// Every command prints one json object on stdout, failures included, progress goes to stderr.
+///
+///     mpecdsa keygen --parties 3 --threshold 1 --key keys1.store
+///     mpecdsa sign --key keys1.store --message "KZen Networks" --signers 1,3
+///     mpecdsa verify --key keys1.store --message "KZen Networks" --r <hex> --s <hex>
+///
+/// Without --session the ceremonies join the one sm_manager runs with its params.json, whose
+/// parameters have to match the flags.
+use std::{fs, panic, process};
+
+use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
+use curv::{
+    cryptographic_primitives::secret_sharing::feldman_vss::VerifiableSS,
+    elliptic::curves::traits::ECPoint, GE,
+};
+use multi_party_ecdsa::protocols::multi_party_ecdsa::gg_2018::party_i::{Keys, SharedKeys};
+use multi_party_ecdsa::protocols::multi_party_ecdsa::gg_2018::party_id::PartyId;
+use multi_party_ecdsa::transport::identity::IdentityKey;
+use paillier::EncryptionKey;
+use serde_json::{json, Value};
+
+mod addparty;
+#[path = "../common.rs"]
+mod common;
+mod keygen;
+mod refresh;
+mod sign;
+
+use common::{public_key, read_params, verify_sig, Client, SessionParams};
+
+// what keygen, add-party and refresh save: the party's keys, its share, its id, the summed vss
+// commitments, everyone's paillier keys, the public key, its identity key and everyone's
+pub type KeyFile = (
+    Keys,
+    SharedKeys,
+    PartyId,
+    VerifiableSS,
+    Vec<EncryptionKey>,
+    GE,
+    IdentityKey,
+    Vec<GE>,
+);
+
+pub fn load_key(path: &str) -> KeyFile {
+    let data = fs::read_to_string(path)
+        .unwrap_or_else(|_| panic!("Unable to load keys from {}, did you run keygen?", path));
+    serde_json::from_str(&data).unwrap_or_else(|_| panic!("{} is not a key file", path))
+}
+
+fn main() {
+    panic::set_hook(Box::new(|info| {
+        let payload = info.payload();
+        let error = match (
+            payload.downcast_ref::<&str>(),
+            payload.downcast_ref::<String>(),
+        ) {
+            (Some(error), _) => error.to_string(),
+            (_, Some(error)) => error.clone(),
+            _ => "unknown error".to_string(),
+        };
+        println!("{}", json!({ "error": error }));
+    }));
+
+    let matches = app().get_matches();
+    let result = match matches.subcommand() {
+        ("keygen", Some(m)) => {
+            let (client, session) = join(m, "keygen");
+            let (parties, threshold) = parameters(m, &session, None);
+            keygen::run(&client, parties, threshold, echo(m, &session), key(m))
+        }
+        ("sign", Some(m)) => {
+            let (client, session) = join(m, "sign");
+            let echo = echo(m, &session);
+            let signers: Vec<u16> = match m.values_of("signers") {
+                Some(values) => values.map(|v| number(v, "signers")).collect(),
+                None => session
+                    .and_then(|s| s.signers)
+                    .expect("--signers is required"),
+            };
+            sign::run(&client, key(m), &message(m), &signers, echo)
+        }
+        ("refresh", Some(m)) => {
+            let (client, session) = join(m, "update");
+            refresh::run(&client, key(m), session.map(|s| s.parties))
+        }
+        ("reshare", Some(m)) => {
+            let (client, session) = join(m, "update");
+            let updating = match session {
+                Some(session) => session.parties,
+                None => number(
+                    m.value_of("parties").expect("--parties is required"),
+                    "parties",
+                ),
+            };
+            let vss_scheme = load_key(key(m)).3;
+            let (n, t) = (
+                vss_scheme.parameters.share_count as u16,
+                vss_scheme.parameters.threshold as u16,
+            );
+            // the updated shares must be able to sign, the old ones of the rest must not
+            assert!(
+                updating > t && updating <= n,
+                "between {} and {} parties take part in a reshare",
+                t + 1,
+                n
+            );
+            assert!(
+                n - updating <= t,
+                "at least {} parties take part in a reshare, {} left out could still sign",
+                n - t,
+                n - updating
+            );
+            refresh::run(&client, key(m), Some(updating))
+        }
+        ("add-party", Some(m)) => {
+            let (client, session) = join(m, "addparty");
+            // the parties of the key can take the parameters from their key files
+            let existing = fs::metadata(key(m)).ok().map(|_| load_key(key(m)));
+            let (parties, threshold) = parameters(m, &session, existing.as_ref());
+            addparty::run(&client, parties, threshold, key(m))
+        }
+        ("pubkey", Some(m)) => {
+            let (_, _, party_id, _, _, y_sum, _, _) = load_key(key(m));
+            json!({
+                "party_id": party_id,
+                "public_key": hex::encode(public_key(&y_sum)),
+            })
+        }
+        ("verify", Some(m)) => {
+            let pk = match m.value_of("public-key") {
+                Some(pk) => hex::decode(pk).expect("--public-key is not hex"),
+                None => public_key(&load_key(key(m)).5),
+            };
+            let r = hex::decode(m.value_of("r").unwrap()).expect("--r is not hex");
+            let s = hex::decode(m.value_of("s").unwrap()).expect("--s is not hex");
+            let valid = verify_sig(&r, &s, &sign::digest(&message(m)), &pk);
+            println!("{}", json!({ "valid": valid }));
+            process::exit(if valid { 0 } else { 1 });
+        }
+        ("inspect", Some(m)) => inspect(load_key(key(m))),
+        _ => unreachable!(),
+    };
+    println!("{}", result);
+}
+
+fn app() -> App<'static, 'static> {
+    let key = Arg::with_name("key")
+        .long("key")
+        .takes_value(true)
+        .value_name("FILE")
+        .help("key file");
+    let parties = Arg::with_name("parties")
+        .long("parties")
+        .takes_value(true)
+        .value_name("N");
+    let threshold = Arg::with_name("threshold")
+        .long("threshold")
+        .takes_value(true)
+        .value_name("T");
+    let echo = Arg::with_name("echo")
+        .long("echo")
+        .help("run an echo round after the commitment broadcasts");
+    let message = Arg::with_name("message")
+        .long("message")
+        .takes_value(true)
+        .required(true)
+        .help("the message, the parties sign its SHA-256");
+    let encoding = Arg::with_name("encoding")
+        .long("encoding")
+        .takes_value(true)
+        .possible_values(&["utf8", "hex"])
+        .default_value("utf8")
+        .help("how the message is given");
+    let ceremony = |name: &str, about: &'static str| {
+        SubCommand::with_name(name)
+            .about(about)
+            .arg(
+                Arg::with_name("relay")
+                    .long("relay")
+                    .takes_value(true)
+                    .value_name("URL")
+                    .default_value("http://127.0.0.1:8001")
+                    .help("sm_manager to run the ceremony through"),
+            )
+            .arg(
+                Arg::with_name("session")
+                    .long("session")
+                    .takes_value(true)
+                    .value_name("ID")
+                    .help("session created on sm_manager, its parameters are used"),
+            )
+            .arg(key.clone().required(true))
+    };
+
+    App::new("mpecdsa")
+        .about("Multi-party ECDSA (gg18) ceremonies and key files")
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .subcommand(
+            ceremony("keygen", "Generate a key shared by the parties")
+                .arg(parties.clone().help("number of parties"))
+                .arg(threshold.clone().help("threshold, t + 1 parties can sign"))
+                .arg(echo.clone()),
+        )
+        .subcommand(
+            ceremony("sign", "Sign a message with t + 1 parties")
+                .arg(message.clone())
+                .arg(encoding.clone())
+                .arg(
+                    Arg::with_name("signers")
+                        .long("signers")
+                        .takes_value(true)
+                        .use_delimiter(true)
+                        .value_name("IDS")
+                        .help("party ids of the t + 1 signers, e.g. 1,3"),
+                )
+                .arg(echo),
+        )
+        .subcommand(ceremony("refresh", "Refresh the shares of all parties"))
+        .subcommand(
+            ceremony(
+                "reshare",
+                "Refresh the shares of the parties taking part, at least n - t of them",
+            )
+            .arg(parties.clone().help("number of parties taking part")),
+        )
+        .subcommand(
+            ceremony("add-party", "Give a new party a share of the key")
+                .arg(parties.help("number of parties of the key taking part"))
+                .arg(threshold.help("threshold of the key")),
+        )
+        .subcommand(
+            SubCommand::with_name("pubkey")
+                .about("Print the public key of a key file")
+                .arg(key.clone().required(true)),
+        )
+        .subcommand(
+            SubCommand::with_name("verify")
+                .about("Verify a signature")
+                .arg(key.clone().required_unless("public-key"))
+                .arg(
+                    Arg::with_name("public-key")
+                        .long("public-key")
+                        .takes_value(true)
+                        .value_name("HEX")
+                        .help("public key, compressed or not"),
+                )
+                .arg(message)
+                .arg(encoding)
+                .arg(
+                    Arg::with_name("r")
+                        .long("r")
+                        .takes_value(true)
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("s")
+                        .long("s")
+                        .takes_value(true)
+                        .required(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("inspect")
+                .about("Print what a key file holds, without its secrets")
+                .arg(key.required(true)),
+        )
+}
+
+// the relay client of a ceremony and the parameters of its session, if it runs in one
+fn join(m: &ArgMatches, protocol: &str) -> (Client, Option<SessionParams>) {
+    let client = Client::new(
+        m.value_of("relay").unwrap(),
+        m.value_of("session").map(String::from),
+    );
+    let session = read_params(&client);
+    if let Some(session) = &session {
+        assert!(
+            session.protocol == protocol,
+            "session {} runs {}",
+            m.value_of("session").unwrap(),
+            session.protocol
+        );
+    }
+    (client, session)
+}
+
+// parties and threshold from the session, the flags or the key file, in that order
+fn parameters(
+    m: &ArgMatches,
+    session: &Option<SessionParams>,
+    key: Option<&KeyFile>,
+) -> (u16, u16) {
+    if let Some(session) = session {
+        return (session.parties, session.threshold);
+    }
+    let flag = |name| m.value_of(name).map(|v| number(v, name));
+    let vss = key.map(|key| &key.3.parameters);
+    let parties = flag("parties").or_else(|| vss.map(|vss| vss.share_count as u16));
+    let threshold = flag("threshold").or_else(|| vss.map(|vss| vss.threshold as u16));
+    (
+        parties.expect("--parties is required"),
+        threshold.expect("--threshold is required"),
+    )
+}
+
+fn echo(m: &ArgMatches, session: &Option<SessionParams>) -> bool {
+    m.is_present("echo") || session.as_ref().map_or(false, |s| s.echo)
+}
+
+fn key<'a>(m: &'a ArgMatches) -> &'a str {
+    m.value_of("key").unwrap()
+}
+
+fn number(value: &str, name: &str) -> u16 {
+    value
+        .parse()
+        .unwrap_or_else(|_| panic!("--{} takes numbers, not {}", name, value))
+}
+
+fn message(m: &ArgMatches) -> Vec<u8> {
+    let message = m.value_of("message").unwrap();
+    match m.value_of("encoding") {
+        Some("hex") => hex::decode(message).expect("--message is not hex"),
+        _ => message.as_bytes().to_vec(),
+    }
+}
+
+fn inspect(key: KeyFile) -> Value {
+    let (party_keys, shared_keys, party_id, vss_scheme, paillier_key_vec, y_sum, identity, roster) =
+        key;
+    let g: GE = ECPoint::generator();
+    let public_share = party_id.public_share(&vss_scheme);
+    json!({
+        "party_id": party_id,
+        "parties": vss_scheme.parameters.share_count,
+        "threshold": vss_scheme.parameters.threshold,
+        "public_key": hex::encode(public_key(&y_sum)),
+        "public_share": hex::encode(public_key(&public_share)),
+        "share_matches_commitments": g * shared_keys.x_i == public_share,
+        "paillier_keys": paillier_key_vec.len(),
+        "party_key": hex::encode(public_key(&party_keys.y_i)),
+        "identity": hex::encode(public_key(&identity.public)),
+        "roster": roster.iter().map(|p| hex::encode(public_key(p))).collect::<Vec<_>>(),
+    })
+}
@@ -0,0 +1,206 @@
+/// mpecdsa refresh and reshare: the updating parties add shares of zero to their shares, so the
+/// key stays the same while the old shares stop combining with the new ones.
+use curv::{FE, GE};
+use multi_party_ecdsa::protocols::multi_party_ecdsa::gg_2018::party_i::SharedKeys;
+use multi_party_ecdsa::protocols::multi_party_ecdsa::gg_2018::party_id::{self, PartyId};
+use multi_party_ecdsa::protocols::multi_party_ecdsa::gg_2018::refresh;
+use multi_party_ecdsa::transport::identity::SignedMessage;
+use multi_party_ecdsa::transport::secure::{channel_run, run_nonce, Sealed, SecureChannel};
+use multi_party_ecdsa::Error::{DuplicatePartyId, InvalidShare};
+use serde_json::{json, Value};
+use std::{fs, time};
+
+use crate::common::{
+  simple_poll, simple_send, Client, PartySignup, signup_to, poll_for_broadcasts, broadcast,
+  public_key, signed, verified
+};
+use crate::load_key;
+
+// updating is the number of parties taking part, all of them when None
+pub fn run(client: &Client, key_file: &str, updating: Option<u16>) -> Value {
+  // delay:
+  let delay = time::Duration::from_millis(25);
+
+  let (party_keys, shared_keys, party_id, vss_scheme, paillier_key_vector, y_sum, identity, key_roster) =
+    load_key(key_file);
+  let UPDATING = updating.unwrap_or(vss_scheme.parameters.share_count as u16);
+
+  // signup:
+  let (party_num_int, uuid) = match signup(&client).unwrap() {
+      PartySignup { number, uuid } => (number, uuid),
+  };
+  eprintln!("number: {:?}, uuid: {:?}", party_num_int, uuid);
+
+  // the nonces the p2p channels of this run are keyed with
+  let nonce = run_nonce();
+  assert!(broadcast(
+    &client,
+    party_num_int,
+    "round0",
+    signed(
+      &identity,
+      party_num_int,
+      "round0",
+      serde_json::to_string(&(party_id, &nonce)).unwrap(),
+      &uuid,
+    ),
+    uuid.clone()
+  )
+  .is_ok());
+
+  let round0_ans_vec = poll_for_broadcasts(
+      &client,
+      party_num_int,
+      UPDATING,
+      delay,
+      "round0",
+      uuid.clone(),
+  );
+
+  
+  let mut j = 0;
+  let mut updaters_vec: Vec<PartyId> = Vec::new();
+  // channels[i - 1] seals the subshares exchanged with party i
+  let mut channels: Vec<Option<SecureChannel>> = Vec::new();
+  // identity keys of this session's updaters, by signup number
+  let mut roster: Vec<GE> = Vec::new();
+  for i in 1..=UPDATING {
+      if i == party_num_int {
+          updaters_vec.push(party_id);
+          channels.push(None);
+          roster.push(identity.public);
+      } else {
+          // the party id is only trusted once the message verifies under its keygen identity
+          let signed: SignedMessage = serde_json::from_str(&round0_ans_vec[j]).unwrap();
+          let (updater_j, _): (PartyId, Vec<u8>) = serde_json::from_str(&signed.payload).unwrap();
+          assert!(
+            party_id::indices(&[updater_j], &vss_scheme).is_ok(),
+            "there is no party {}", updater_j
+          );
+          let public = key_roster[updater_j.index()];
+          let payload = verified(&public, i, "round0", &round0_ans_vec[j], &uuid);
+          let (updater_j, nonce_j): (PartyId, Vec<u8>) = serde_json::from_str(&payload).unwrap();
+          updaters_vec.push(updater_j);
+          roster.push(public);
+          channels.push(Some(SecureChannel::new(
+            &uuid,
+            &channel_run(party_num_int, &nonce, i, &nonce_j),
+            party_num_int,
+            &identity.secret,
+            i,
+            &public,
+          )));
+          j += 1;
+      }
+  }
+
+  // two updaters with the same id would both get the subshares meant for it
+  if let Err(DuplicatePartyId { party }) = party_id::indices(&updaters_vec, &vss_scheme) {
+    panic!("party {} takes part twice", party);
+  }
+
+  // sve osim slobodnog clana!
+  let (subshares, commitments) = refresh::deal_refresh(&vss_scheme, &updaters_vec);
+
+  for i in 1..=UPDATING {
+    if i != party_num_int {
+      let channel = channels[(i - 1) as usize].as_mut().unwrap();
+      let sealed = channel.seal(
+        "subshare",
+        &serde_json::to_vec(&subshares[(i - 1) as usize]).unwrap(),
+      );
+      assert!(simple_send(
+        &client,
+        party_num_int,
+        i,
+        "subshare",
+        signed(&identity, party_num_int, "subshare", serde_json::to_string(&sealed).unwrap(), &uuid),
+        uuid.clone(),
+      )
+      .is_ok());
+
+      assert!(simple_send(
+        &client,
+        party_num_int,
+        i,
+        "feldmans",
+        signed(&identity, party_num_int, "feldmans", serde_json::to_string(&commitments).unwrap(), &uuid),
+        uuid.clone(),
+      )
+      .is_ok());
+    }
+  }
+
+  // what every updater dealt to this party, by signup number
+  let mut received: Vec<(FE, Vec<GE>)> = Vec::new();
+  for i in 1..=UPDATING {
+    if i == party_num_int {
+      received.push((subshares[(i - 1) as usize], commitments.clone()));
+    } else {
+      let subshare_ans = simple_poll(
+        &client,
+        i,
+        party_num_int,
+        delay,
+        "subshare",
+        uuid.clone(),
+      );
+      let subshare_ans = verified(&roster[(i - 1) as usize], i, "subshare", &subshare_ans, &uuid);
+      let sealed: Sealed = serde_json::from_str(&subshare_ans).unwrap();
+      let subshare = channels[(i - 1) as usize]
+        .as_mut()
+        .unwrap()
+        .open("subshare", &sealed)
+        .expect("invalid subshare ciphertext");
+      let subshare: FE = serde_json::from_slice(&subshare).unwrap();
+      let feldmans_ans = simple_poll(
+        &client,
+        i,
+        party_num_int,
+        delay,
+        "feldmans",
+        uuid.clone(),
+      );
+      let feldmans_ans = verified(&roster[(i - 1) as usize], i, "feldmans", &feldmans_ans, &uuid);
+      // sve osim slobodnog clana
+      let feldmans: Vec<GE> = serde_json::from_str(&feldmans_ans).unwrap();
+      received.push((subshare, feldmans));
+    }
+  }
+
+  let (updated_x_i, updated_vss_scheme) =
+    match refresh::apply_refresh(party_id, &shared_keys.x_i, &vss_scheme, &received) {
+      Ok(updated) => updated,
+      Err(InvalidShare { parties }) => {
+        let parties = parties.iter().map(|k| k + 1).collect::<Vec<usize>>();
+        panic!("bad subshares from parties {:?}", parties)
+      }
+      Err(e) => panic!("refresh failed: {:?}", e),
+    };
+
+  let update_json = serde_json::to_string(&(
+    party_keys,
+    SharedKeys { y: shared_keys.y, x_i: updated_x_i },
+    party_id,
+    updated_vss_scheme,
+    paillier_key_vector,
+    y_sum,
+    identity,
+    key_roster,
+  ))
+  .unwrap();
+
+  fs::write(key_file, update_json).expect("Unable to save !");
+
+  json!({
+    "party_id": party_id,
+    "updating": updaters_vec,
+    "public_key": hex::encode(public_key(&y_sum)),
+    "key_file": key_file,
+  })
+}
+
+
+pub fn signup(client: &Client) -> Result<PartySignup, ()> {
+  signup_to(client, "signupupdate")
+}
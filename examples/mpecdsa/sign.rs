@@ -1,5 +1,6 @@
-#![allow(non_snake_case)]
-
+/// mpecdsa sign: t + 1 parties sign a message with their shares of a key.
+use crypto::digest::Digest;
+use crypto::sha2::Sha256;
 use curv::{
     cryptographic_primitives::{
         proofs::sigma_correct_homomorphic_elgamal_enc::HomoELGamalProof,
@@ -9,67 +10,72 @@ use curv::{
     BigInt, FE, GE,
 };
 use multi_party_ecdsa::protocols::multi_party_ecdsa::gg_2018::party_i::{
-    Keys, LocalSignature, PartyPrivate, Phase5ADecom1, Phase5Com1, Phase5Com2, Phase5DDecom2,
-    SharedKeys, SignBroadcastPhase1, SignDecommitPhase1, SignKeys,
+    LocalSignature, PartyPrivate, Phase5ADecom1, Phase5Com1, Phase5Com2, Phase5DDecom2,
+    SignBroadcastPhase1, SignContext, SignDecommitPhase1, SignKeys,
 };
+use multi_party_ecdsa::protocols::multi_party_ecdsa::gg_2018::party_id::{self, PartyId};
+use multi_party_ecdsa::transport::identity::SignedMessage;
+use multi_party_ecdsa::transport::secure::{channel_run, run_nonce, Sealed, SecureChannel};
 use multi_party_ecdsa::utilities::mta::*;
+use multi_party_ecdsa::Error::{DuplicatePartyId, MessageMismatch, PublicShareMismatch};
 
-use paillier::EncryptionKey;
-use reqwest::Client;
-use std::{env, fs, time};
+use serde_json::{json, Value};
+use std::time;
 
-mod common;
-use common::{
-    broadcast, check_sig, poll_for_broadcasts, poll_for_p2p, postb, sendp2p, Params, PartySignup,
+use crate::common::{
+    broadcast, check_echo, check_sig, poll_for_broadcasts, poll_for_p2p, public_key, sendp2p,
+    signed, signup_to, verified, verified_all, Client, PartySignup,
 };
+use crate::load_key;
 
+// signers are the party ids of the t + 1 signers, this party's included. Each signer posts at
+// its place in the sorted list, whatever number the signup handed out.
 #[allow(clippy::cognitive_complexity)]
-fn main() {
-    if env::args().nth(4).is_some() {
-        panic!("too many arguments")
-    }
-    if env::args().nth(3).is_none() {
-        panic!("too few arguments")
-    }
-    let message_str = env::args().nth(3).unwrap_or_else(|| "".to_string());
-    let message = match hex::decode(message_str.clone()) {
-        Ok(x) => x,
-        Err(_e) => message_str.as_bytes().to_vec(),
-    };
-    let message = &message[..];
-    let client = Client::new();
+pub fn run(client: &Client, key_file: &str, message: &[u8], signers: &[u16], echo: bool) -> Value {
     // delay:
     let delay = time::Duration::from_millis(25);
     // read key file
-    let data = fs::read_to_string(env::args().nth(2).unwrap())
-        .expect("Unable to load keys, did you run keygen first? ");
-    let (party_keys, shared_keys, party_id, vss_scheme, paillier_key_vector, y_sum): (
-        Keys,
-        SharedKeys,
-        u16,
-        VerifiableSS,
-        Vec<EncryptionKey>,
-        GE,
-    ) = serde_json::from_str(&data).unwrap();
-
-    //read parameters:
-    let data = fs::read_to_string("params.json")
-        .expect("Unable to read params, make sure config file is present in the same folder ");
-    let params: Params = serde_json::from_str(&data).unwrap();
-    let THRESHOLD = params.threshold.parse::<u16>().unwrap();
+    let (
+        party_keys,
+        shared_keys,
+        party_id,
+        vss_scheme,
+        paillier_key_vector,
+        y_sum,
+        identity,
+        key_roster,
+    ) = load_key(key_file);
+    let THRESHOLD = vss_scheme.parameters.threshold as u16;
+    let (signers, signers_vec) = signer_set(signers, THRESHOLD, &vss_scheme);
+    let party_num_int = match signers.iter().position(|&s| s == party_id) {
+        Some(position) => position as u16 + 1,
+        None => panic!("party {} is not among the signers {:?}", party_id, signers),
+    };
+    // what gets signed is the SHA-256 of the message, the signers have to agree on it, on
+    // themselves and the key
+    let message_bn = digest(message);
+    let context = SignContext::new(&message_bn, &signers_vec, &y_sum);
 
     //signup:
-    let (party_num_int, uuid) = match signup(&client).unwrap() {
+    let (number, uuid) = match signup(&client).unwrap() {
         PartySignup { number, uuid } => (number, uuid),
     };
-    println!("number: {:?}, uuid: {:?}", party_num_int, uuid);
+    eprintln!("number: {:?}, uuid: {:?}", number, uuid);
 
-    // round 0: collect signers IDs
+    // round 0: agree on the signers and the message, collect the nonces the p2p channels of
+    // this run are keyed with. Nothing secret is sent before everyone checked this round.
+    let nonce = run_nonce();
     assert!(broadcast(
         &client,
         party_num_int,
         "round0",
-        serde_json::to_string(&party_id).unwrap(),
+        signed(
+            &identity,
+            party_num_int,
+            "round0",
+            serde_json::to_string(&(party_id, &nonce, context.commitment())).unwrap(),
+            &uuid,
+        ),
         uuid.clone()
     )
     .is_ok());
@@ -83,40 +89,84 @@ fn main() {
     );
 
     let mut j = 0;
-    let mut signers_vec: Vec<usize> = Vec::new();
+    let mut context_coms: Vec<BigInt> = Vec::new();
+    let mut channels: Vec<SecureChannel> = Vec::new();
+    // identity keys of this session's signers, by place in signers
+    let roster = signers
+        .iter()
+        .map(|s| key_roster[s.index()])
+        .collect::<Vec<GE>>();
     for i in 1..=THRESHOLD + 1 {
-        if i == party_num_int {
-            signers_vec.push((party_id - 1) as usize);
-        } else {
-            let signer_j: u16 = serde_json::from_str(&round0_ans_vec[j]).unwrap();
-            signers_vec.push((signer_j - 1) as usize);
+        if i != party_num_int {
+            let expected = signers[(i - 1) as usize];
+            let signed: SignedMessage = serde_json::from_str(&round0_ans_vec[j]).unwrap();
+            let (signer_j, _, _): (PartyId, Vec<u8>, BigInt) =
+                serde_json::from_str(&signed.payload).unwrap();
+            assert!(
+                signer_j == expected,
+                "party {} took the place of signer {}",
+                signer_j,
+                expected
+            );
+            let payload = verified(
+                &roster[(i - 1) as usize],
+                i,
+                "round0",
+                &round0_ans_vec[j],
+                &uuid,
+            );
+            let (_, nonce_j, context_com): (PartyId, Vec<u8>, BigInt) =
+                serde_json::from_str(&payload).unwrap();
+            context_coms.push(context_com);
+            channels.push(SecureChannel::new(
+                &uuid,
+                &channel_run(party_num_int, &nonce, i, &nonce_j),
+                party_num_int,
+                &identity.secret,
+                i,
+                &roster[(i - 1) as usize],
+            ));
             j += 1;
+        } else {
+            context_coms.push(context.commitment());
         }
     }
+    if let Err(MessageMismatch { party }) = context.verify_commitments(&context_coms) {
+        panic!(
+            "party {} signs another message, with other signers or another key",
+            party + 1
+        );
+    }
 
     let private = PartyPrivate::set_private(party_keys.clone(), shared_keys);
 
-    let sign_keys = SignKeys::create(
-        &private,
-        &vss_scheme,
-        signers_vec[(party_num_int - 1) as usize],
-        &signers_vec,
-    );
+    let sign_keys = match SignKeys::create_for_party(&private, &vss_scheme, party_id, &signers) {
+        Ok(sign_keys) => sign_keys,
+        Err(PublicShareMismatch { .. }) => panic!(
+            "the share in {} does not match the key's commitments, is it from before a refresh?",
+            key_file
+        ),
+        Err(_) => panic!("invalid signers"),
+    };
 
-    let xi_com_vec = Keys::get_commitments_to_xi(&vss_scheme);
-    println!("Client #{} XI_COM_LEN {}", party_id, xi_com_vec.len());
     //////////////////////////////////////////////////////////////////////////////
     let (com, decommit) = sign_keys.phase1_broadcast();
     let (m_a_k, _) = MessageA::a(&sign_keys.k_i, &party_keys.ek);
+    let round1_msg = signed(
+        &identity,
+        party_num_int,
+        "round1",
+        serde_json::to_string(&(com.clone(), m_a_k.clone())).unwrap(),
+        &uuid,
+    );
     assert!(broadcast(
         &client,
         party_num_int,
         "round1",
-        serde_json::to_string(&(com.clone(), m_a_k.clone())).unwrap(),
+        round1_msg.clone(),
         uuid.clone()
     )
     .is_ok());
-    println!("Client #{} poslao!", party_id);
     let round1_ans_vec = poll_for_broadcasts(
         &client,
         party_num_int,
@@ -125,8 +175,20 @@ fn main() {
         "round1",
         uuid.clone(),
     );
-
-    println!("Client #{} after r1", party_id);
+    if echo {
+        check_echo(
+            &client,
+            party_num_int,
+            &identity,
+            &roster,
+            delay,
+            "round1",
+            &round1_msg,
+            &round1_ans_vec,
+            uuid.clone(),
+        );
+    }
+    let round1_ans_vec = verified_all(&roster, party_num_int, "round1", round1_ans_vec, &uuid);
 
     let mut j = 0;
     let mut bc1_vec: Vec<SignBroadcastPhase1> = Vec::new();
@@ -178,13 +240,22 @@ fn main() {
     let mut j = 0;
     for i in 1..THRESHOLD + 2 {
         if i != party_num_int {
+            let plaintext =
+                serde_json::to_vec(&(m_b_gamma_send_vec[j].clone(), m_b_w_send_vec[j].clone()))
+                    .unwrap();
+            let sealed = channels[j].seal("round2", &plaintext);
             assert!(sendp2p(
                 &client,
                 party_num_int,
                 i,
                 "round2",
-                serde_json::to_string(&(m_b_gamma_send_vec[j].clone(), m_b_w_send_vec[j].clone()))
-                    .unwrap(),
+                signed(
+                    &identity,
+                    party_num_int,
+                    "round2",
+                    serde_json::to_string(&sealed).unwrap(),
+                    &uuid,
+                ),
                 uuid.clone()
             )
             .is_ok());
@@ -200,14 +271,19 @@ fn main() {
         "round2",
         uuid.clone(),
     );
+    let round2_ans_vec = verified_all(&roster, party_num_int, "round2", round2_ans_vec, &uuid);
 
     let mut m_b_gamma_rec_vec: Vec<MessageB> = Vec::new();
     let mut m_b_w_rec_vec: Vec<MessageB> = Vec::new();
 
     for i in 0..THRESHOLD {
         //  if signers_vec.contains(&(i as usize)) {
+        let sealed: Sealed = serde_json::from_str(&round2_ans_vec[i as usize]).unwrap();
+        let plaintext = channels[i as usize]
+            .open("round2", &sealed)
+            .expect("invalid round2 ciphertext");
         let (m_b_gamma_i, m_b_w_i): (MessageB, MessageB) =
-            serde_json::from_str(&round2_ans_vec[i as usize]).unwrap();
+            serde_json::from_slice(&plaintext).unwrap();
         m_b_gamma_rec_vec.push(m_b_gamma_i);
         m_b_w_rec_vec.push(m_b_w_i);
         //     }
@@ -225,18 +301,21 @@ fn main() {
                 .verify_proofs_get_alpha(&party_keys.dk, &sign_keys.k_i)
                 .expect("wrong dlog or m_b");
             let m_b = m_b_w_rec_vec[j].clone();
-            let alpha_ij_wi = m_b
-                .verify_proofs_get_alpha(&party_keys.dk, &sign_keys.k_i)
-                .expect("wrong dlog or m_b");
-            alpha_vec.push(alpha_ij_gamma);
-            miu_vec.push(alpha_ij_wi);
-            let g_w_i = Keys::update_commitments_to_xi(
-                &xi_com_vec[signers_vec[(i - 1) as usize]],
+            let alpha_ij_wi = match m_b.verify_proofs_get_alpha_wc(
+                &party_keys.dk,
+                &sign_keys.k_i,
                 &vss_scheme,
                 signers_vec[(i - 1) as usize],
                 &signers_vec,
-            );
-            assert_eq!(m_b.b_proof.pk, g_w_i);
+            ) {
+                Ok(alpha_ij_wi) => alpha_ij_wi,
+                Err(PublicShareMismatch { party }) => {
+                    panic!("party {} signs with a wrong or stale share", party + 1)
+                }
+                Err(_) => panic!("wrong dlog or m_b"),
+            };
+            alpha_vec.push(alpha_ij_gamma);
+            miu_vec.push(alpha_ij_wi);
             j += 1;
         }
     }
@@ -248,7 +327,13 @@ fn main() {
         &client,
         party_num_int,
         "round3",
-        serde_json::to_string(&delta_i).unwrap(),
+        signed(
+            &identity,
+            party_num_int,
+            "round3",
+            serde_json::to_string(&delta_i).unwrap(),
+            &uuid,
+        ),
         uuid.clone()
     )
     .is_ok());
@@ -260,6 +345,7 @@ fn main() {
         "round3",
         uuid.clone(),
     );
+    let round3_ans_vec = verified_all(&roster, party_num_int, "round3", round3_ans_vec, &uuid);
     let mut delta_vec: Vec<FE> = Vec::new();
     format_vec_from_reads(
         &round3_ans_vec,
@@ -275,7 +361,13 @@ fn main() {
         &client,
         party_num_int,
         "round4",
-        serde_json::to_string(&decommit).unwrap(),
+        signed(
+            &identity,
+            party_num_int,
+            "round4",
+            serde_json::to_string(&decommit).unwrap(),
+            &uuid,
+        ),
         uuid.clone()
     )
     .is_ok());
@@ -287,6 +379,7 @@ fn main() {
         "round4",
         uuid.clone(),
     );
+    let round4_ans_vec = verified_all(&roster, party_num_int, "round4", round4_ans_vec, &uuid);
 
     let mut decommit_vec: Vec<SignDecommitPhase1> = Vec::new();
     format_vec_from_reads(
@@ -306,8 +399,6 @@ fn main() {
     // adding local g_gamma_i
     let R = R + decomm_i.g_gamma_i * delta_inv;
 
-    // we assume the message is already hashed (by the signer).
-    let message_bn = BigInt::from(message);
     let local_sig =
         LocalSignature::phase5_local_sig(&sign_keys.k_i, &message_bn, &R, &sigma, &y_sum);
 
@@ -319,7 +410,13 @@ fn main() {
         &client,
         party_num_int,
         "round5",
-        serde_json::to_string(&phase5_com).unwrap(),
+        signed(
+            &identity,
+            party_num_int,
+            "round5",
+            serde_json::to_string(&phase5_com).unwrap(),
+            &uuid,
+        ),
         uuid.clone()
     )
     .is_ok());
@@ -331,6 +428,7 @@ fn main() {
         "round5",
         uuid.clone(),
     );
+    let round5_ans_vec = verified_all(&roster, party_num_int, "round5", round5_ans_vec, &uuid);
 
     let mut commit5a_vec: Vec<Phase5Com1> = Vec::new();
     format_vec_from_reads(
@@ -345,12 +443,18 @@ fn main() {
         &client,
         party_num_int,
         "round6",
-        serde_json::to_string(&(
-            phase_5a_decom.clone(),
-            helgamal_proof.clone(),
-            dlog_proof_rho.clone()
-        ))
-        .unwrap(),
+        signed(
+            &identity,
+            party_num_int,
+            "round6",
+            serde_json::to_string(&(
+                phase_5a_decom.clone(),
+                helgamal_proof.clone(),
+                dlog_proof_rho.clone()
+            ))
+            .unwrap(),
+            &uuid,
+        ),
         uuid.clone()
     )
     .is_ok());
@@ -362,6 +466,7 @@ fn main() {
         "round6",
         uuid.clone(),
     );
+    let round6_ans_vec = verified_all(&roster, party_num_int, "round6", round6_ans_vec, &uuid);
 
     let mut decommit5a_and_elgamal_and_dlog_vec: Vec<(Phase5ADecom1, HomoELGamalProof, DLogProof)> =
         Vec::new();
@@ -404,7 +509,13 @@ fn main() {
         &client,
         party_num_int,
         "round7",
-        serde_json::to_string(&phase5_com2).unwrap(),
+        signed(
+            &identity,
+            party_num_int,
+            "round7",
+            serde_json::to_string(&phase5_com2).unwrap(),
+            &uuid,
+        ),
         uuid.clone()
     )
     .is_ok());
@@ -416,6 +527,7 @@ fn main() {
         "round7",
         uuid.clone(),
     );
+    let round7_ans_vec = verified_all(&roster, party_num_int, "round7", round7_ans_vec, &uuid);
 
     let mut commit5c_vec: Vec<Phase5Com2> = Vec::new();
     format_vec_from_reads(
@@ -430,7 +542,13 @@ fn main() {
         &client,
         party_num_int,
         "round8",
-        serde_json::to_string(&phase_5d_decom2).unwrap(),
+        signed(
+            &identity,
+            party_num_int,
+            "round8",
+            serde_json::to_string(&phase_5d_decom2).unwrap(),
+            &uuid,
+        ),
         uuid.clone()
     )
     .is_ok());
@@ -442,6 +560,7 @@ fn main() {
         "round8",
         uuid.clone(),
     );
+    let round8_ans_vec = verified_all(&roster, party_num_int, "round8", round8_ans_vec, &uuid);
 
     let mut decommit5d_vec: Vec<Phase5DDecom2> = Vec::new();
     format_vec_from_reads(
@@ -471,7 +590,13 @@ fn main() {
         &client,
         party_num_int,
         "round9",
-        serde_json::to_string(&s_i).unwrap(),
+        signed(
+            &identity,
+            party_num_int,
+            "round9",
+            serde_json::to_string(&s_i).unwrap(),
+            &uuid,
+        ),
         uuid.clone()
     )
     .is_ok());
@@ -483,6 +608,7 @@ fn main() {
         "round9",
         uuid.clone(),
     );
+    let round9_ans_vec = verified_all(&roster, party_num_int, "round9", round9_ans_vec, &uuid);
 
     let mut s_i_vec: Vec<FE> = Vec::new();
     format_vec_from_reads(&round9_ans_vec, party_num_int as usize, s_i, &mut s_i_vec);
@@ -491,23 +617,61 @@ fn main() {
     let sig = local_sig
         .output_signature(&s_i_vec)
         .expect("verification failed");
-    println!("party {:?} Output Signature: \n", party_num_int);
-    println!("R: {:?}", sig.r.get_element());
-    println!("s: {:?} \n", sig.s.get_element());
-    println!("recid: {:?} \n", sig.recid.clone());
-
-    let sign_json = serde_json::to_string(&(
-        "r",
-        (BigInt::from(&(sig.r.get_element())[..])).to_str_radix(16),
-        "s",
-        (BigInt::from(&(sig.s.get_element())[..])).to_str_radix(16),
-    ))
-    .unwrap();
-
     // check sig against secp256k1
     check_sig(&sig.r, &sig.s, &message_bn, &y_sum);
 
-    fs::write("signature".to_string(), sign_json).expect("Unable to save !");
+    json!({
+        "party_id": party_id,
+        "signers": signers,
+        "message": hex::encode(message),
+        "public_key": hex::encode(public_key(&y_sum)),
+        "r": hex::encode(&sig.r.get_element()[..]),
+        "s": hex::encode(&sig.s.get_element()[..]),
+        "recid": sig.recid,
+    })
+}
+
+// the SHA-256 of message as a number, which is what the parties sign and verify checks
+pub fn digest(message: &[u8]) -> BigInt {
+    let mut hasher = Sha256::new();
+    hasher.input(message);
+    let mut hash = [0u8; 32];
+    hasher.result(&mut hash);
+    BigInt::from(&hash[..])
+}
+
+// signers sorted, so that every signer sees the same places, after checking there are t + 1
+// distinct party ids of the key of vss_scheme. Also returns their indices into vss_scheme.
+fn signer_set(
+    signers: &[u16],
+    THRESHOLD: u16,
+    vss_scheme: &VerifiableSS,
+) -> (Vec<PartyId>, Vec<usize>) {
+    let mut set = Vec::new();
+    for &signer in signers {
+        match PartyId::new(signer) {
+            Ok(id) => set.push(id),
+            Err(_) => panic!("there is no party {}", signer),
+        }
+    }
+    set.sort();
+    let indices = match party_id::indices(&set, vss_scheme) {
+        Ok(indices) => indices,
+        Err(DuplicatePartyId { party }) => {
+            panic!("party {} is named twice among the signers", party)
+        }
+        Err(_) => panic!(
+            "the signers {:?} are not all parties of the key, it has {}",
+            signers, vss_scheme.parameters.share_count
+        ),
+    };
+    assert!(
+        set.len() == THRESHOLD as usize + 1,
+        "need {} signers, not {:?}",
+        THRESHOLD + 1,
+        signers
+    );
+    (set, indices)
 }
 
 fn format_vec_from_reads<'a, T: serde::Deserialize<'a> + Clone>(
@@ -529,8 +693,5 @@ fn format_vec_from_reads<'a, T: serde::Deserialize<'a> + Clone>(
 }
 
 pub fn signup(client: &Client) -> Result<PartySignup, ()> {
-    let key = "signup-sign".to_string();
-
-    let res_body = postb(&client, "signupsign", key).unwrap();
-    serde_json::from_str(&res_body).unwrap()
+    signup_to(client, "signupsign")
 }
@@ -0,0 +1,51 @@
+/// Runs GG18 key generation, signing and refresh in one process, no sm_manager needed.
+/// to run: cargo run --example simulation -- [threshold] [parties]
+use curv::elliptic::curves::traits::{ECPoint, ECScalar};
+use curv::BigInt;
+use multi_party_ecdsa::protocols::multi_party_ecdsa::gg_2018::party_i::verify;
+use multi_party_ecdsa::simulation::{gg18, Transcript};
+use std::env;
+
+fn main() {
+    let threshold = env::args()
+        .nth(1)
+        .map(|t| t.parse::<u16>().unwrap())
+        .unwrap_or(1);
+    let parties = env::args()
+        .nth(2)
+        .map(|n| n.parse::<u16>().unwrap())
+        .unwrap_or(3);
+    let signers = (0..=threshold as usize).collect::<Vec<usize>>();
+    let message = BigInt::from(&b"multi-party-ecdsa"[..]);
+
+    let keygen = gg18::keygen(threshold, parties).expect("keygen failed");
+    print_rounds("keygen", &keygen.transcript);
+    let y = keygen.outputs[0].y_sum;
+    println!("public key: {:?}\n", y.get_element());
+
+    let signing = gg18::sign(&keygen.outputs, &signers, &message).expect("signing failed");
+    print_rounds("sign", &signing.transcript);
+    let sig = &signing.outputs[0];
+    verify(sig, &y, &message).expect("invalid signature");
+    println!("r: {:?}", sig.r.get_element());
+    println!("s: {:?}\n", sig.s.get_element());
+
+    let refresh = gg18::refresh(&keygen.outputs).expect("refresh failed");
+    print_rounds("refresh", &refresh.transcript);
+    let signing = gg18::sign(&refresh.outputs, &signers, &message).expect("signing failed");
+    verify(&signing.outputs[0], &y, &message).expect("invalid signature");
+    println!("signed again with the refreshed shares");
+}
+
+fn print_rounds(name: &str, transcript: &Transcript) {
+    println!("{}:", name);
+    for round in &transcript.rounds {
+        let bytes: usize = round.messages.iter().map(|m| m.body.len()).sum();
+        println!(
+            "  {:<8} {:>3} messages {:>8} bytes",
+            round.name,
+            round.messages.len(),
+            bytes
+        );
+    }
+}
@@ -1,24 +1,358 @@
 #![feature(proc_macro_hygiene, decl_macro)]
 
-use std::collections::HashMap;
-use std::fs;
-use std::sync::RwLock;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::env;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Condvar, Mutex, RwLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-use rocket::{post, routes, State};
+use multi_party_ecdsa::transport::Envelope;
+
+use rocket::http::Status;
+use rocket::request::{self, FromRequest, Request};
+use rocket::{get, post, routes, Outcome, State};
 use rocket_contrib::json::Json;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 mod common;
-use common::{Entry, Index, Key, Params, PartySignup};
+use common::{Entry, Index, Key, Params, PartySignup, SessionParams, Wait};
 
-#[post("/get", format = "json", data = "<request>")]
-fn get(
-    db_mtx: State<RwLock<HashMap<Key, String>>>,
-    request: Json<Index>,
-) -> Json<Result<Entry, ()>> {
+// longest a wait is held, each one keeps a rocket worker busy
+const MAX_WAIT_MS: u64 = 20_000;
+// rocket workers that never hold a wait, so set and get go through however many parties wait
+const RESERVED_WORKERS: usize = 8;
+
+// one ceremony: its parameters, how many parties signed up and the messages they posted
+struct Session {
+    params: SessionParams,
+    signups: u16,
+    messages: HashMap<Key, String>,
+    last_active: u64, // seconds since the epoch
+}
+
+impl Session {
+    fn new(params: SessionParams, at: u64) -> Self {
+        Session {
+            params,
+            signups: 0,
+            messages: HashMap::new(),
+            last_active: at,
+        }
+    }
+
+    // nothing happened for retention seconds, finished or abandoned
+    fn expired(&self, now: u64, retention: u64) -> bool {
+        now.saturating_sub(self.last_active) >= retention
+    }
+
+    // number of parties that sign up to the session
+    fn size(&self) -> u16 {
+        match self.params.protocol.as_str() {
+            "sign" => self.params.threshold + 1,
+            "addparty" => self.params.parties + 1,
+            _ => self.params.parties,
+        }
+    }
+}
+
+type Sessions = RwLock<HashMap<String, Session>>;
+
+// counts the messages set, waits sleep until it changes
+#[derive(Default)]
+struct Posted {
+    count: Mutex<u64>,
+    cond: Condvar,
+}
+
+// the waits being held, any beyond max are turned away with 503
+struct Waiters {
+    active: AtomicUsize,
+    max: usize,
+}
+
+// a wait slot, given back when dropped
+struct Waiting<'a>(&'a Waiters);
+
+impl Waiters {
+    fn new(max: usize) -> Self {
+        Waiters {
+            active: AtomicUsize::new(0),
+            max,
+        }
+    }
+
+    fn enter(&self) -> Option<Waiting> {
+        if self.active.fetch_add(1, Ordering::SeqCst) >= self.max {
+            self.active.fetch_sub(1, Ordering::SeqCst);
+            return None;
+        }
+        Some(Waiting(self))
+    }
+}
+
+impl<'a> Drop for Waiting<'a> {
+    fn drop(&mut self) {
+        self.0.active.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+// every change to the sessions and the fixed signups, one json record per line
+#[derive(Serialize, Deserialize)]
+enum Record {
+    Created {
+        id: String,
+        params: SessionParams,
+        signups: u16,
+        at: u64,
+    },
+    Signup {
+        id: String,
+        at: u64,
+    },
+    Set {
+        id: String,
+        key: Key,
+        value: String,
+        at: u64,
+    },
+    Expired {
+        id: String,
+    },
+    Closed {
+        id: String,
+    },
+    Counter {
+        key: Key,
+        value: String,
+    },
+}
+
+// the journal file, replayed on start so a restart does not lose ceremonies in flight
+struct Journal {
+    file: Mutex<File>,
+    retention: u64,
+}
+
+impl Journal {
+    // replays the journal at path, drops expired sessions and rewrites it with what is left
+    fn open(
+        path: &str,
+        retention: u64,
+    ) -> (Journal, HashMap<String, Session>, HashMap<Key, String>) {
+        let mut sessions: HashMap<String, Session> = HashMap::new();
+        let mut counters: HashMap<Key, String> = HashMap::new();
+        if let Ok(file) = File::open(path) {
+            // a crash can leave the last record half written, even cut in the middle of a
+            // character, it was never acknowledged
+            let lines = BufReader::new(file).lines().filter_map(|line| line.ok());
+            for record in lines.filter_map(|line| serde_json::from_str(&line).ok()) {
+                match record {
+                    Record::Created {
+                        id,
+                        params,
+                        signups,
+                        at,
+                    } => {
+                        let mut session = Session::new(params, at);
+                        session.signups = signups;
+                        sessions.insert(id, session);
+                    }
+                    Record::Signup { id, at } => {
+                        if let Some(session) = sessions.get_mut(&id) {
+                            session.signups += 1;
+                            session.last_active = at;
+                        }
+                    }
+                    Record::Set { id, key, value, at } => {
+                        if let Some(session) = sessions.get_mut(&id) {
+                            session.messages.insert(key, value);
+                            session.last_active = at;
+                        }
+                    }
+                    Record::Expired { id } | Record::Closed { id } => {
+                        sessions.remove(&id);
+                    }
+                    Record::Counter { key, value } => {
+                        counters.insert(key, value);
+                    }
+                }
+            }
+        }
+        let now = now();
+        sessions.retain(|_, session| !session.expired(now, retention));
+
+        let compacted = format!("{}.tmp", path);
+        {
+            let mut file = File::create(&compacted).expect("Unable to write journal");
+            let mut records = Vec::new();
+            for (id, session) in &sessions {
+                records.push(Record::Created {
+                    id: id.clone(),
+                    params: session.params.clone(),
+                    signups: session.signups,
+                    at: session.last_active,
+                });
+                for (key, value) in &session.messages {
+                    records.push(Record::Set {
+                        id: id.clone(),
+                        key: key.clone(),
+                        value: value.clone(),
+                        at: session.last_active,
+                    });
+                }
+            }
+            for (key, value) in &counters {
+                records.push(Record::Counter {
+                    key: key.clone(),
+                    value: value.clone(),
+                });
+            }
+            for record in records {
+                writeln!(file, "{}", serde_json::to_string(&record).unwrap()).unwrap();
+            }
+            file.sync_all().unwrap();
+        }
+        fs::rename(&compacted, path).expect("Unable to write journal");
+
+        let file = OpenOptions::new()
+            .append(true)
+            .open(path)
+            .expect("Unable to open journal");
+        let journal = Journal {
+            file: Mutex::new(file),
+            retention,
+        };
+        (journal, sessions, counters)
+    }
+
+    // returns once the record is on disk
+    fn append(&self, record: &Record) {
+        let mut file = self.file.lock().unwrap();
+        writeln!(file, "{}", serde_json::to_string(record).unwrap()).unwrap();
+        file.sync_data().unwrap();
+    }
+
+    fn expire(&self, sessions: &mut HashMap<String, Session>) {
+        let now = now();
+        let expired = sessions
+            .iter()
+            .filter(|(_, session)| session.expired(now, self.retention))
+            .map(|(id, _)| id.clone())
+            .collect::<Vec<String>>();
+        for id in expired {
+            self.append(&Record::Expired { id: id.clone() });
+            sessions.remove(&id);
+        }
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+fn check_params(params: &SessionParams) -> Result<(), String> {
+    if !["keygen", "sign", "addparty", "update"].contains(&params.protocol.as_str()) {
+        return Err(format!("unknown protocol {}", params.protocol));
+    }
+    if params.threshold == 0 || params.threshold >= params.parties {
+        return Err(format!(
+            "threshold {} out of range for {} parties",
+            params.threshold, params.parties
+        ));
+    }
+    if let Some(signers) = &params.signers {
+        if params.protocol != "sign" {
+            return Err("signers are only given for sign".to_string());
+        }
+        if signers.len() != params.threshold as usize + 1 {
+            return Err(format!("need {} signers", params.threshold + 1));
+        }
+        for (i, signer) in signers.iter().enumerate() {
+            if *signer == 0 || *signer > params.parties || signers[..i].contains(signer) {
+                return Err(format!("bad signer {}", signer));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[post("/session", format = "json", data = "<request>")]
+fn create_session(
+    sessions: State<Sessions>,
+    journal: State<Journal>,
+    request: Json<SessionParams>,
+) -> Json<Result<String, String>> {
+    let params: SessionParams = request.0;
+    if let Err(e) = check_params(&params) {
+        return Json(Err(e));
+    }
+    let id = Uuid::new_v4().to_string();
+    let mut sessions = sessions.write().unwrap();
+    journal.expire(&mut sessions);
+    create(&mut sessions, &journal, &id, params);
+    Json(Ok(id))
+}
+
+fn create(
+    sessions: &mut HashMap<String, Session>,
+    journal: &Journal,
+    id: &str,
+    params: SessionParams,
+) {
+    let at = now();
+    journal.append(&Record::Created {
+        id: id.to_string(),
+        params: params.clone(),
+        signups: 0,
+        at,
+    });
+    sessions.insert(id.to_string(), Session::new(params, at));
+}
+
+fn join(session: &mut Session, journal: &Journal, id: &str) -> u16 {
+    let at = now();
+    journal.append(&Record::Signup {
+        id: id.to_string(),
+        at,
+    });
+    session.signups += 1;
+    session.last_active = at;
+    session.signups
+}
+
+#[post("/session/<id>", format = "json")]
+fn session_params(id: String, sessions: State<Sessions>) -> Json<Result<SessionParams, ()>> {
+    let sessions = sessions.read().unwrap();
+    Json(sessions.get(&id).map(|s| s.params.clone()).ok_or(()))
+}
+
+#[post("/session/<id>/signup", format = "json")]
+fn signup_session(
+    id: String,
+    sessions: State<Sessions>,
+    journal: State<Journal>,
+) -> Json<Result<PartySignup, ()>> {
+    let mut sessions = sessions.write().unwrap();
+    match sessions.get_mut(&id) {
+        Some(session) if session.signups < session.size() => Json(Ok(PartySignup {
+            number: join(session, &journal, &id),
+            uuid: id,
+        })),
+        _ => Json(Err(())),
+    }
+}
+
+#[post("/session/<id>/get", format = "json", data = "<request>")]
+fn get(id: String, sessions: State<Sessions>, request: Json<Index>) -> Json<Result<Entry, ()>> {
     let index: Index = request.0;
-    let hm = db_mtx.read().unwrap();
-    match hm.get(&index.key) {
+    let sessions = sessions.read().unwrap();
+    match sessions.get(&id).and_then(|s| s.messages.get(&index.key)) {
         Some(v) => {
             let entry = Entry {
                 key: index.key,
@@ -30,16 +364,166 @@ fn get(
     }
 }
 
-#[post("/set", format = "json", data = "<request>")]
-fn set(db_mtx: State<RwLock<HashMap<Key, String>>>, request: Json<Entry>) -> Json<Result<(), ()>> {
+// write once: a key can not be set to another value once others may have read it, setting
+// the same value again is fine so that clients can retry
+#[post("/session/<id>/set", format = "json", data = "<request>")]
+fn set(
+    id: String,
+    sessions: State<Sessions>,
+    journal: State<Journal>,
+    posted: State<Posted>,
+    request: Json<Entry>,
+) -> Json<Result<(), ()>> {
     let entry: Entry = request.0;
-    let mut hm = db_mtx.write().unwrap();
-    hm.insert(entry.key.clone(), entry.value.clone());
-    Json(Ok(()))
+    let mut sessions = sessions.write().unwrap();
+    let session = match sessions.get_mut(&id) {
+        Some(session) => session,
+        None => return Json(Err(())),
+    };
+    let stored = put(session, &journal, &id, entry);
+    if stored == Ok(true) {
+        *posted.count.lock().unwrap() += 1;
+        posted.cond.notify_all();
+    }
+    Json(stored.map(|_| ()))
+}
+
+// Ok(true) if the entry is new, Ok(false) if the key already held the same value
+fn put(session: &mut Session, journal: &Journal, id: &str, entry: Entry) -> Result<bool, ()> {
+    match session.messages.get(&entry.key) {
+        Some(value) if *value == entry.value => Ok(false),
+        Some(_) => Err(()),
+        None => {
+            let at = now();
+            journal.append(&Record::Set {
+                id: id.to_string(),
+                key: entry.key.clone(),
+                value: entry.value.clone(),
+                at,
+            });
+            session.messages.insert(entry.key, entry.value);
+            session.last_active = at;
+            Ok(true)
+        }
+    }
+}
+
+// holds the request until there is a message for receiver in round from every sender, or
+// timeout_ms ran out, and returns what is there. A sender's p2p message is returned over its
+// broadcast. Answers 503 while all wait slots are taken, the client pauses and asks again.
+#[post("/session/<id>/wait", format = "json", data = "<request>")]
+fn wait(
+    id: String,
+    sessions: State<Sessions>,
+    posted: State<Posted>,
+    waiters: State<Waiters>,
+    request: Json<Wait>,
+) -> Result<Json<Result<Vec<Envelope>, ()>>, Status> {
+    let _slot = waiters.enter().ok_or(Status::ServiceUnavailable)?;
+    let wait: Wait = request.0;
+    let deadline = Instant::now() + Duration::from_millis(wait.timeout_ms.min(MAX_WAIT_MS));
+    loop {
+        let seen = *posted.count.lock().unwrap();
+        let found = match collect(&sessions.read().unwrap(), &id, &wait) {
+            Some(found) => found,
+            None => return Ok(Json(Err(()))),
+        };
+        let now = Instant::now();
+        let aborted = found.iter().any(|e| e.round == "abort");
+        if found.len() == wait.senders.len() || aborted || now >= deadline {
+            return Ok(Json(Ok(found)));
+        }
+        let count = posted.count.lock().unwrap();
+        // anything set since the look above is picked up right away
+        if *count == seen {
+            drop(posted.cond.wait_timeout(count, deadline - now).unwrap());
+        }
+    }
+}
+
+// the round's messages, or the aborts of the other parties if there are any
+fn collect(sessions: &HashMap<String, Session>, id: &str, wait: &Wait) -> Option<Vec<Envelope>> {
+    let session = sessions.get(id)?;
+    let aborts = (1..=session.size())
+        .filter(|&party| party != wait.receiver)
+        .filter_map(|party| {
+            let body = session.messages.get(&format!("{}-abort-{}", party, id))?;
+            Some(Envelope {
+                session: id.to_string(),
+                round: "abort".to_string(),
+                sender: party,
+                receiver: None,
+                body: body.clone(),
+            })
+        })
+        .collect::<Vec<_>>();
+    if !aborts.is_empty() {
+        return Some(aborts);
+    }
+    let found = wait.senders.iter().filter_map(|&sender| {
+        let p2p = format!("{}-{}-{}-{}", sender, wait.receiver, wait.round, id);
+        let broadcast = format!("{}-{}-{}", sender, wait.round, id);
+        let (receiver, body) = match session.messages.get(&p2p) {
+            Some(body) => (Some(wait.receiver), body),
+            None => (None, session.messages.get(&broadcast)?),
+        };
+        Some(Envelope {
+            session: id.to_string(),
+            round: wait.round.clone(),
+            sender,
+            receiver,
+            body: body.clone(),
+        })
+    });
+    Some(found.collect())
+}
+
+// the fixed signups below run one ceremony at a time with the parameters in params.json and
+// update_params.json, each uuid they hand out is registered as a session when first seen
+fn register(sessions: &Sessions, journal: &Journal, uuid: &str, protocol: &str) {
+    let mut sessions = sessions.write().unwrap();
+    if !sessions.contains_key(uuid) {
+        journal.expire(&mut sessions);
+        create(&mut sessions, journal, uuid, file_params(protocol));
+    }
+    join(sessions.get_mut(uuid).unwrap(), journal, uuid);
+}
+
+fn file_params(protocol: &str) -> SessionParams {
+    let data = fs::read_to_string("params.json")
+        .expect("Unable to read params, make sure config file is present in the same folder ");
+    let params: Params = serde_json::from_str(&data).unwrap();
+    let parties = if protocol == "update" {
+        let data =
+            fs::read_to_string("update_params.json").expect("Unable to read update_params.json");
+        data.trim().parse::<u16>().unwrap()
+    } else {
+        params.parties.parse::<u16>().unwrap()
+    };
+    SessionParams {
+        protocol: protocol.to_string(),
+        parties,
+        threshold: params.threshold.parse::<u16>().unwrap(),
+        signers: None,
+        echo: params.echo,
+    }
+}
+
+fn save_counter(db: &mut HashMap<Key, String>, journal: &Journal, key: Key, signup: &PartySignup) {
+    let value = serde_json::to_string(signup).unwrap();
+    journal.append(&Record::Counter {
+        key: key.clone(),
+        value: value.clone(),
+    });
+    db.insert(key, value);
 }
 
 #[post("/signupkeygen", format = "json")]
-fn signup_keygen(db_mtx: State<RwLock<HashMap<Key, String>>>) -> Json<Result<PartySignup, ()>> {
+fn signup_keygen(
+    db_mtx: State<RwLock<HashMap<Key, String>>>,
+    sessions: State<Sessions>,
+    journal: State<Journal>,
+) -> Json<Result<PartySignup, ()>> {
     let data = fs::read_to_string("params.json")
         .expect("Unable to read params, make sure config file is present in the same folder ");
     let params: Params = serde_json::from_str(&data).unwrap();
@@ -63,14 +547,19 @@ fn signup_keygen(db_mtx: State<RwLock<HashMap<Key, String>>>) -> Json<Result<Par
             }
         }
     };
+    register(&sessions, &journal, &party_signup.uuid, "keygen");
 
     let mut hm = db_mtx.write().unwrap();
-    hm.insert(key, serde_json::to_string(&party_signup).unwrap());
+    save_counter(&mut hm, &journal, key, &party_signup);
     Json(Ok(party_signup))
 }
 
 #[post("/signupsign", format = "json")]
-fn signup_sign(db_mtx: State<RwLock<HashMap<Key, String>>>) -> Json<Result<PartySignup, ()>> {
+fn signup_sign(
+    db_mtx: State<RwLock<HashMap<Key, String>>>,
+    sessions: State<Sessions>,
+    journal: State<Journal>,
+) -> Json<Result<PartySignup, ()>> {
     //read parameters:
     let data = fs::read_to_string("params.json")
         .expect("Unable to read params, make sure config file is present in the same folder ");
@@ -94,14 +583,19 @@ fn signup_sign(db_mtx: State<RwLock<HashMap<Key, String>>>) -> Json<Result<Party
             }
         }
     };
+    register(&sessions, &journal, &party_signup.uuid, "sign");
 
     let mut hm = db_mtx.write().unwrap();
-    hm.insert(key, serde_json::to_string(&party_signup).unwrap());
+    save_counter(&mut hm, &journal, key, &party_signup);
     Json(Ok(party_signup))
 }
 
 #[post("/signupaddparty", format = "json")]
-fn signup_addparty(db_mtx: State<RwLock<HashMap<Key, String>>>) -> Json<Result<PartySignup, ()>> {
+fn signup_addparty(
+    db_mtx: State<RwLock<HashMap<Key, String>>>,
+    sessions: State<Sessions>,
+    journal: State<Journal>,
+) -> Json<Result<PartySignup, ()>> {
     // read parameters:
     let data = fs::read_to_string("params.json")
         .expect("Unable to read params, make sure config file is present in the same folder ");
@@ -125,18 +619,22 @@ fn signup_addparty(db_mtx: State<RwLock<HashMap<Key, String>>>) -> Json<Result<P
             }
         }
     };
+    register(&sessions, &journal, &party_signup.uuid, "addparty");
 
     let mut hm = db_mtx.write().unwrap();
-    hm.insert(key, serde_json::to_string(&party_signup).unwrap());
+    save_counter(&mut hm, &journal, key, &party_signup);
     Json(Ok(party_signup))
 }
 
 #[post("/signupupdate", format = "json")]
-fn signup_update(db_mtx: State<RwLock<HashMap<Key, String>>>) -> Json<Result<PartySignup, ()>> {
+fn signup_update(
+    db_mtx: State<RwLock<HashMap<Key, String>>>,
+    sessions: State<Sessions>,
+    journal: State<Journal>,
+) -> Json<Result<PartySignup, ()>> {
     let data = fs::read_to_string("update_params.json")
         .expect("Unable to read update_params.json");
     let parties = data.trim().to_string().parse::<u16>().unwrap();
-    println!("hhh {:?}", parties);
 
     let key = "signup-update".to_string();
 
@@ -146,6 +644,7 @@ fn signup_update(db_mtx: State<RwLock<HashMap<Key, String>>>) -> Json<Result<Par
         let client_signup: PartySignup = serde_json::from_str(&value).unwrap();
         client_signup
     };
+    register(&sessions, &journal, &current_party_signup.uuid, "update");
 
     let cc = current_party_signup.clone();
 
@@ -164,17 +663,146 @@ fn signup_update(db_mtx: State<RwLock<HashMap<Key, String>>>) -> Json<Result<Par
     };
 
     let mut hm = db_mtx.write().unwrap();
-    hm.insert(key, serde_json::to_string(&next_party_signup).unwrap());
+    save_counter(&mut hm, &journal, key, &next_party_signup);
     Json(Ok(current_party_signup))
 }
 
+// what the admin endpoints report about a session
+#[derive(Serialize)]
+struct SessionStatus {
+    id: String,
+    params: SessionParams,
+    size: u16,
+    signed_up: Vec<u16>,
+    rounds: BTreeMap<u16, BTreeSet<String>>, // the rounds each party posted in
+    last_active: u64,
+}
+
+impl SessionStatus {
+    fn new(id: &str, session: &Session) -> Self {
+        let mut rounds: BTreeMap<u16, BTreeSet<String>> = BTreeMap::new();
+        for (sender, round) in session.messages.keys().filter_map(|key| parse_key(key, id)) {
+            rounds.entry(sender).or_default().insert(round);
+        }
+        SessionStatus {
+            id: id.to_string(),
+            params: session.params.clone(),
+            size: session.size(),
+            signed_up: (1..=session.signups).collect(),
+            rounds,
+            last_active: session.last_active,
+        }
+    }
+}
+
+// sender and round of a message key, "{from}-{round}-{id}" or "{from}-{to}-{round}-{id}"
+fn parse_key(key: &str, id: &str) -> Option<(u16, String)> {
+    let suffix = format!("-{}", id);
+    if !key.ends_with(&suffix) {
+        return None;
+    }
+    let mut parts = key[..key.len() - suffix.len()].splitn(2, '-');
+    let sender = parts.next()?.parse::<u16>().ok()?;
+    let rest = parts.next()?;
+    let round = match rest.splitn(2, '-').collect::<Vec<_>>()[..] {
+        [to, round] if to.parse::<u16>().is_ok() => round,
+        _ => rest,
+    };
+    Some((sender, round.to_string()))
+}
+
+// the token the admin endpoints want in an X-Admin-Token header, None turns them off
+struct AdminToken(Option<String>);
+
+// a request that carries the admin token
+struct Admin;
+
+impl<'a, 'r> FromRequest<'a, 'r> for Admin {
+    type Error = ();
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<Self, ()> {
+        let token = match request.guard::<State<AdminToken>>() {
+            Outcome::Success(token) => token,
+            _ => return Outcome::Failure((Status::InternalServerError, ())),
+        };
+        let expected = match &token.0 {
+            Some(expected) => expected,
+            None => return Outcome::Failure((Status::Forbidden, ())),
+        };
+        match request.headers().get_one("X-Admin-Token") {
+            Some(given) if same(given.as_bytes(), expected.as_bytes()) => Outcome::Success(Admin),
+            _ => Outcome::Failure((Status::Unauthorized, ())),
+        }
+    }
+}
+
+// compares without stopping at the first difference, so timing does not leak the token
+fn same(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[get("/admin/sessions")]
+fn admin_sessions(_admin: Admin, sessions: State<Sessions>) -> Json<Vec<SessionStatus>> {
+    let sessions = sessions.read().unwrap();
+    let mut status = sessions
+        .iter()
+        .map(|(id, session)| SessionStatus::new(id, session))
+        .collect::<Vec<_>>();
+    status.sort_by(|a, b| a.id.cmp(&b.id));
+    Json(status)
+}
+
+#[get("/admin/sessions/<id>")]
+fn admin_session(
+    _admin: Admin,
+    id: String,
+    sessions: State<Sessions>,
+) -> Json<Result<SessionStatus, ()>> {
+    let sessions = sessions.read().unwrap();
+    Json(
+        sessions
+            .get(&id)
+            .map(|session| SessionStatus::new(&id, session))
+            .ok_or(()),
+    )
+}
+
+// drops the session with its messages, waits on it return right away and further calls fail
+#[post("/admin/sessions/<id>/close")]
+fn admin_close(
+    _admin: Admin,
+    id: String,
+    sessions: State<Sessions>,
+    journal: State<Journal>,
+    posted: State<Posted>,
+) -> Json<Result<(), ()>> {
+    let mut sessions = sessions.write().unwrap();
+    if !sessions.contains_key(&id) {
+        return Json(Err(()));
+    }
+    journal.append(&Record::Closed { id: id.clone() });
+    sessions.remove(&id);
+    *posted.count.lock().unwrap() += 1;
+    posted.cond.notify_all();
+    Json(Ok(()))
+}
+
 //refcell, arc
 
 fn main() {
     // let mut my_config = Config::development();
     // my_config.set_port(18001);
-    let db: HashMap<Key, String> = HashMap::new();
+    // SM_JOURNAL is where the board is kept across restarts, SM_RETENTION how many seconds a
+    // session is kept after its last signup or message
+    let journal_path = env::var("SM_JOURNAL").unwrap_or_else(|_| "sm_manager.journal".to_string());
+    let retention = env::var("SM_RETENTION")
+        .map(|s| s.parse::<u64>().expect("SM_RETENTION is in seconds"))
+        .unwrap_or(24 * 60 * 60);
+    // SM_ADMIN_TOKEN is what the admin endpoints want in X-Admin-Token, without it they are off
+    let admin_token = AdminToken(env::var("SM_ADMIN_TOKEN").ok().filter(|t| !t.is_empty()));
+    let (journal, sessions, db) = Journal::open(&journal_path, retention);
     let db_mtx = RwLock::new(db);
+    let sessions: Sessions = RwLock::new(sessions);
     //rocket::custom(my_config).mount("/", routes![get, set]).manage(db_mtx).launch();
 
     /////////////////////////////////////////////////////////////////
@@ -208,27 +836,199 @@ fn main() {
         number: party1 + 1,
         uuid: uuid_update,
     };
+    // unless the journal already had them
     {
         let mut hm = db_mtx.write().unwrap();
-        hm.insert(
-            keygen_key,
-            serde_json::to_string(&party_signup_keygen).unwrap(),
-        );
-        hm.insert(sign_key, serde_json::to_string(&party_signup_sign).unwrap());
-        hm.insert(
-            addparty_key,
-            serde_json::to_string(&party_signup_addparty).unwrap(),
-        );
-        hm.insert(
-            update_key,
-            serde_json::to_string(&party_signup_update).unwrap(),
-        );
+        hm.entry(keygen_key)
+            .or_insert_with(|| serde_json::to_string(&party_signup_keygen).unwrap());
+        hm.entry(sign_key)
+            .or_insert_with(|| serde_json::to_string(&party_signup_sign).unwrap());
+        hm.entry(addparty_key)
+            .or_insert_with(|| serde_json::to_string(&party_signup_addparty).unwrap());
+        hm.entry(update_key)
+            .or_insert_with(|| serde_json::to_string(&party_signup_update).unwrap());
     }
     /////////////////////////////////////////////////////////////////
-    rocket::ignite()
+    let rocket = rocket::ignite();
+    // without a worker left for waits every client would be told 503 forever
+    let workers = rocket.config().workers as usize;
+    assert!(
+        workers > RESERVED_WORKERS,
+        "sm_manager needs more than {} rocket workers, {} are configured",
+        RESERVED_WORKERS,
+        workers
+    );
+    let waiters = Waiters::new(workers - RESERVED_WORKERS);
+    rocket
         .mount("/", routes![
             get, set, signup_keygen, signup_sign, signup_addparty,
-            signup_update])
+            signup_update, create_session, session_params, signup_session, wait,
+            admin_sessions, admin_session, admin_close])
         .manage(db_mtx)
+        .manage(sessions)
+        .manage(journal)
+        .manage(Posted::default())
+        .manage(waiters)
+        .manage(admin_token)
         .launch();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn journal_path() -> String {
+        env::temp_dir()
+            .join(format!("sm_manager-{}.journal", Uuid::new_v4()))
+            .to_str()
+            .unwrap()
+            .to_string()
+    }
+
+    fn params() -> SessionParams {
+        SessionParams {
+            protocol: "keygen".to_string(),
+            parties: 3,
+            threshold: 1,
+            signers: None,
+            echo: false,
+        }
+    }
+
+    fn entry(key: &str, value: &str) -> Entry {
+        Entry {
+            key: key.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    fn lines(path: &str) -> usize {
+        fs::read_to_string(path).unwrap().lines().count()
+    }
+
+    #[test]
+    fn test_same() {
+        assert!(same(b"token", b"token"));
+        assert!(!same(b"token", b"tokem"));
+        assert!(!same(b"token", b"token2"));
+        assert!(!same(b"", b"token"));
+    }
+
+    #[test]
+    fn test_replay() {
+        let path = journal_path();
+        {
+            let (journal, mut sessions, mut counters) = Journal::open(&path, 3600);
+            create(&mut sessions, &journal, "a", params());
+            let session = sessions.get_mut("a").unwrap();
+            assert_eq!(join(session, &journal, "a"), 1);
+            assert_eq!(join(session, &journal, "a"), 2);
+            assert_eq!(
+                put(session, &journal, "a", entry("1-round1-a", "x")),
+                Ok(true)
+            );
+            let signup = PartySignup {
+                number: 2,
+                uuid: "a".to_string(),
+            };
+            save_counter(
+                &mut counters,
+                &journal,
+                "signup-keygen".to_string(),
+                &signup,
+            );
+        }
+        let (_, sessions, counters) = Journal::open(&path, 3600);
+        let session = &sessions["a"];
+        assert_eq!(session.params, params());
+        assert_eq!(session.signups, 2);
+        assert_eq!(session.messages["1-round1-a"], "x");
+        let signup: PartySignup = serde_json::from_str(&counters["signup-keygen"]).unwrap();
+        assert_eq!(signup.number, 2);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_torn_record() {
+        let path = journal_path();
+        {
+            let (journal, mut sessions, _) = Journal::open(&path, 3600);
+            create(&mut sessions, &journal, "a", params());
+        }
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        // cut inside the three bytes of a euro sign
+        let torn = b"{\"Set\":{\"id\":\"a\",\"value\":\"\xe2\x82";
+        file.write_all(torn).unwrap();
+        drop(file);
+        let (_, sessions, _) = Journal::open(&path, 3600);
+        assert!(sessions["a"].messages.is_empty());
+        assert_eq!(lines(&path), 1);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_set_is_write_once() {
+        let path = journal_path();
+        let (journal, mut sessions, _) = Journal::open(&path, 3600);
+        create(&mut sessions, &journal, "a", params());
+        let session = sessions.get_mut("a").unwrap();
+        assert_eq!(
+            put(session, &journal, "a", entry("1-round1-a", "x")),
+            Ok(true)
+        );
+        // a retry with the same value is fine, another value is refused and not journaled
+        assert_eq!(
+            put(session, &journal, "a", entry("1-round1-a", "x")),
+            Ok(false)
+        );
+        assert_eq!(
+            put(session, &journal, "a", entry("1-round1-a", "y")),
+            Err(())
+        );
+        assert_eq!(session.messages["1-round1-a"], "x");
+        assert_eq!(lines(&path), 2);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_compaction_and_retention() {
+        let path = journal_path();
+        {
+            let (journal, mut sessions, _) = Journal::open(&path, 3600);
+            for id in &["active", "closed", "abandoned"] {
+                create(&mut sessions, &journal, id, params());
+            }
+            let session = sessions.get_mut("active").unwrap();
+            for value in &["x", "y", "z"] {
+                join(session, &journal, "active");
+                let key = format!("{}-round1-active", session.signups);
+                assert_eq!(
+                    put(session, &journal, "active", entry(&key, value)),
+                    Ok(true)
+                );
+            }
+            journal.append(&Record::Closed {
+                id: "closed".to_string(),
+            });
+            // nobody signed up to it in the last hour
+            journal.append(&Record::Created {
+                id: "abandoned".to_string(),
+                params: params(),
+                signups: 1,
+                at: now() - 3600,
+            });
+        }
+        let (journal, mut sessions, _) = Journal::open(&path, 3600);
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions["active"].messages.len(), 3);
+        // one record for the session, one per message
+        assert_eq!(lines(&path), 4);
+
+        sessions.get_mut("active").unwrap().last_active = now() - 3600;
+        journal.expire(&mut sessions);
+        assert!(sessions.is_empty());
+        let (_, sessions, _) = Journal::open(&path, 3600);
+        assert!(sessions.is_empty());
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,14 @@
+// writes the honest messages of the fixtures to corpus/<target>/, run before fuzzing:
+// cargo run --release --example seed_corpus [--features=cclst]
+use std::fs;
+use std::path::PathBuf;
+
+fn main() {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("corpus");
+    for (target, json) in multi_party_ecdsa_fuzz::seed_corpus() {
+        let dir = root.join(target);
+        fs::create_dir_all(&dir).expect("cannot create corpus directory");
+        fs::write(dir.join("honest.json"), json).expect("cannot write seed");
+        println!("{}", target);
+    }
+}
@@ -0,0 +1,11 @@
+#![no_main]
+use class_group::primitives::cl_dl_lcm::CLDLProofPublicSetup;
+use libfuzzer_sys::fuzz_target;
+use multi_party_ecdsa::protocols::two_party_ecdsa::cclst_2019::party_two;
+use multi_party_ecdsa_fuzz::from_json;
+
+fuzz_target!(|data: &[u8]| {
+    if let Some(proof) = from_json::<CLDLProofPublicSetup>(data) {
+        let _ = party_two::HSMCLPublic::verify_zkcldl_proof(proof);
+    }
+});
@@ -0,0 +1,14 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use multi_party_ecdsa::protocols::two_party_ecdsa::cclst_2019::{party_one, party_two};
+use multi_party_ecdsa_fuzz::cclst::CCLST;
+use multi_party_ecdsa_fuzz::from_json;
+
+fuzz_target!(|data: &[u8]| {
+    if let Some(first) = from_json::<party_one::EphKeyGenFirstMsg>(data) {
+        let _ = party_two::EphKeyGenSecondMsg::verify_and_decommit(
+            CCLST.eph_comm_witness.clone(),
+            &first,
+        );
+    }
+});
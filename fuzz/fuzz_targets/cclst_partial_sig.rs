@@ -0,0 +1,17 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use multi_party_ecdsa::protocols::two_party_ecdsa::cclst_2019::{party_one, party_two};
+use multi_party_ecdsa_fuzz::cclst::CCLST;
+use multi_party_ecdsa_fuzz::{from_json, message};
+
+fuzz_target!(|data: &[u8]| {
+    if let Some(partial_sig) = from_json::<party_two::PartialSig>(data) {
+        let signature = party_one::Signature::compute(
+            &CCLST.party_one_private,
+            partial_sig.c3,
+            &CCLST.eph_ec_key_pair_party1,
+            &CCLST.eph_party_two_second.comm_witness.public_share,
+        );
+        let _ = party_one::verify(&signature, &CCLST.pubkey, &message());
+    }
+});
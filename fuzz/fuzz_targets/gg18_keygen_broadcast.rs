@@ -0,0 +1,18 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use multi_party_ecdsa::protocols::multi_party_ecdsa::gg_2018::party_i::KeyGenBroadcastMessage1;
+use multi_party_ecdsa_fuzz::{from_json, rng, Gg18, GG18};
+
+fuzz_target!(|data: &[u8]| {
+    if let Some(bc1) = from_json::<KeyGenBroadcastMessage1>(data) {
+        let mut bc1_vec = GG18.keygen_bc1.clone();
+        bc1_vec[1] = bc1;
+        let _ = GG18.keys[0]
+            .phase1_verify_com_phase3_verify_correct_key_phase2_distribute_with_rng(
+                &Gg18::params(),
+                &GG18.keygen_decom,
+                &bc1_vec,
+                &mut rng(),
+            );
+    }
+});
@@ -0,0 +1,18 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use multi_party_ecdsa::protocols::multi_party_ecdsa::gg_2018::party_i::KeyGenDecommitMessage1;
+use multi_party_ecdsa_fuzz::{from_json, rng, Gg18, GG18};
+
+fuzz_target!(|data: &[u8]| {
+    if let Some(decom) = from_json::<KeyGenDecommitMessage1>(data) {
+        let mut decom_vec = GG18.keygen_decom.clone();
+        decom_vec[1] = decom;
+        let _ = GG18.keys[0]
+            .phase1_verify_com_phase3_verify_correct_key_phase2_distribute_with_rng(
+                &Gg18::params(),
+                &decom_vec,
+                &GG18.keygen_bc1,
+                &mut rng(),
+            );
+    }
+});
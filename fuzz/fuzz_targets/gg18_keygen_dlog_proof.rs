@@ -0,0 +1,13 @@
+#![no_main]
+use curv::cryptographic_primitives::proofs::sigma_dlog::DLogProof;
+use libfuzzer_sys::fuzz_target;
+use multi_party_ecdsa::protocols::multi_party_ecdsa::gg_2018::party_i::Keys;
+use multi_party_ecdsa_fuzz::{from_json, Gg18, GG18};
+
+fuzz_target!(|data: &[u8]| {
+    if let Some(dlog_proof) = from_json::<DLogProof>(data) {
+        let mut dlog_proofs = GG18.dlog_proofs.clone();
+        dlog_proofs[1] = dlog_proof;
+        let _ = Keys::verify_dlog_proofs(&Gg18::params(), &dlog_proofs, &GG18.y_vec);
+    }
+});
@@ -0,0 +1,22 @@
+#![no_main]
+use curv::cryptographic_primitives::secret_sharing::feldman_vss::VerifiableSS;
+use curv::FE;
+use libfuzzer_sys::fuzz_target;
+use multi_party_ecdsa_fuzz::{from_json, rng, Gg18, GG18};
+
+fuzz_target!(|data: &[u8]| {
+    if let Some((vss_scheme, share)) = from_json::<(VerifiableSS, FE)>(data) {
+        let mut vss_schemes = GG18.vss_schemes.clone();
+        let mut shares = GG18.shares.clone();
+        vss_schemes[1] = vss_scheme;
+        shares[1] = share;
+        let _ = GG18.keys[0].phase2_verify_vss_construct_keypair_phase3_pok_dlog_with_rng(
+            &Gg18::params(),
+            &GG18.y_vec,
+            &shares,
+            &vss_schemes,
+            1,
+            &mut rng(),
+        );
+    }
+});
@@ -0,0 +1,18 @@
+#![no_main]
+use curv::cryptographic_primitives::proofs::sigma_dlog::DLogProof;
+use libfuzzer_sys::fuzz_target;
+use multi_party_ecdsa::protocols::multi_party_ecdsa::gg_2018::party_i::{
+    SignBroadcastPhase1, SignDecommitPhase1, SignKeys,
+};
+use multi_party_ecdsa_fuzz::{from_json, GG18};
+
+fuzz_target!(|data: &[u8]| {
+    if let Some((bc1, decom)) = from_json::<(SignBroadcastPhase1, SignDecommitPhase1)>(data) {
+        let mut bc1_vec = GG18.sign_bc1.clone();
+        let mut decom_vec = GG18.sign_decom.clone();
+        bc1_vec[1] = bc1;
+        decom_vec[1] = decom;
+        let b_proofs = GG18.b_proofs.iter().collect::<Vec<&DLogProof>>();
+        let _ = SignKeys::phase4(&GG18.delta_inv, &b_proofs, decom_vec, &bc1_vec);
+    }
+});
@@ -0,0 +1,25 @@
+#![no_main]
+use curv::cryptographic_primitives::proofs::sigma_correct_homomorphic_elgamal_enc::HomoELGamalProof;
+use curv::cryptographic_primitives::proofs::sigma_dlog::DLogProof;
+use libfuzzer_sys::fuzz_target;
+use multi_party_ecdsa::protocols::multi_party_ecdsa::gg_2018::party_i::{
+    Phase5ADecom1, Phase5Com1,
+};
+use multi_party_ecdsa_fuzz::{from_json, rng, GG18};
+
+fuzz_target!(|data: &[u8]| {
+    if let Some((com, decom, elgamal_proof, dlog_proof_rho)) =
+        from_json::<(Phase5Com1, Phase5ADecom1, HomoELGamalProof, DLogProof)>(data)
+    {
+        let dlog_proofs_rho = vec![GG18.dlog_proofs_rho[0].clone(), dlog_proof_rho];
+        let _ = GG18.local_sig.phase5c_with_rng(
+            &[decom],
+            &[com],
+            &[elgamal_proof],
+            &dlog_proofs_rho,
+            &GG18.phase5a_decom[0].V_i,
+            &GG18.R,
+            &mut rng(),
+        );
+    }
+});
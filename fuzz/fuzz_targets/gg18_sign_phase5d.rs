@@ -0,0 +1,18 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use multi_party_ecdsa::protocols::multi_party_ecdsa::gg_2018::party_i::{
+    Phase5Com2, Phase5DDecom2,
+};
+use multi_party_ecdsa_fuzz::{from_json, GG18};
+
+fuzz_target!(|data: &[u8]| {
+    if let Some((com2, decom2)) = from_json::<(Phase5Com2, Phase5DDecom2)>(data) {
+        let mut com2_vec = GG18.phase5_com2.clone();
+        let mut decom2_vec = GG18.phase5d_decom2.clone();
+        com2_vec[1] = com2;
+        decom2_vec[1] = decom2;
+        let _ = GG18
+            .local_sig
+            .phase5d(&decom2_vec, &com2_vec, &GG18.phase5a_decom);
+    }
+});
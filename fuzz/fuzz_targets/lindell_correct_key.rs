@@ -0,0 +1,11 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use multi_party_ecdsa::protocols::two_party_ecdsa::lindell_2017::party_two;
+use multi_party_ecdsa_fuzz::{from_json, LINDELL};
+use zk_paillier::zkproofs::NICorrectKeyProof;
+
+fuzz_target!(|data: &[u8]| {
+    if let Some(proof) = from_json::<NICorrectKeyProof>(data) {
+        let _ = party_two::PaillierPublic::verify_ni_proof_correct_key(proof, &LINDELL.paillier_ek);
+    }
+});
@@ -0,0 +1,12 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use multi_party_ecdsa::protocols::two_party_ecdsa::lindell_2017::{party_one, party_two};
+use multi_party_ecdsa_fuzz::from_json;
+
+fuzz_target!(|data: &[u8]| {
+    if let Some((first, second)) =
+        from_json::<(party_two::EphKeyGenFirstMsg, party_two::EphKeyGenSecondMsg)>(data)
+    {
+        let _ = party_one::EphKeyGenSecondMsg::verify_commitments_and_dlog_proof(&first, &second);
+    }
+});
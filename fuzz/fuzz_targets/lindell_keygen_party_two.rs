@@ -0,0 +1,13 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use multi_party_ecdsa::protocols::two_party_ecdsa::lindell_2017::{party_one, party_two};
+use multi_party_ecdsa_fuzz::{from_json, LINDELL};
+
+fuzz_target!(|data: &[u8]| {
+    if let Some(first) = from_json::<party_two::KeyGenFirstMsg>(data) {
+        let _ = party_one::KeyGenSecondMsg::verify_and_decommit(
+            LINDELL.comm_witness.clone(),
+            &first.d_log_proof,
+        );
+    }
+});
@@ -0,0 +1,16 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use multi_party_ecdsa::protocols::two_party_ecdsa::lindell_2017::{party_one, party_two};
+use multi_party_ecdsa_fuzz::{from_json, message, LINDELL};
+
+fuzz_target!(|data: &[u8]| {
+    if let Some(partial_sig) = from_json::<party_two::PartialSig>(data) {
+        let signature = party_one::Signature::compute(
+            &LINDELL.party_one_private,
+            &partial_sig.c3,
+            &LINDELL.eph_ec_key_pair_party1,
+            &LINDELL.eph_party_two_second.comm_witness.public_share,
+        );
+        let _ = party_one::verify(&signature, &LINDELL.pubkey, &message());
+    }
+});
@@ -0,0 +1,10 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use multi_party_ecdsa::utilities::mta::{MessageA, MessageB};
+use multi_party_ecdsa_fuzz::{from_json, rng, MTA};
+
+fuzz_target!(|data: &[u8]| {
+    if let Some(m_a) = from_json::<MessageA>(data) {
+        let _ = MessageB::b_with_rng(&MTA.b, &MTA.ek, m_a, &mut rng());
+    }
+});
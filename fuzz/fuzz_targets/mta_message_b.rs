@@ -0,0 +1,10 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use multi_party_ecdsa::utilities::mta::MessageB;
+use multi_party_ecdsa_fuzz::{from_json, MTA};
+
+fuzz_target!(|data: &[u8]| {
+    if let Some(m_b) = from_json::<MessageB>(data) {
+        let _ = m_b.verify_proofs_get_alpha(&MTA.dk, &MTA.a);
+    }
+});
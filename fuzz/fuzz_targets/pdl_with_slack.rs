@@ -0,0 +1,10 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use multi_party_ecdsa::utilities::zk_pdl_with_slack::PDLwSlackProof;
+use multi_party_ecdsa_fuzz::{from_json, PDL_WITH_SLACK};
+
+fuzz_target!(|data: &[u8]| {
+    if let Some(proof) = from_json::<PDLwSlackProof>(data) {
+        let _ = proof.verify(&PDL_WITH_SLACK.statement);
+    }
+});
@@ -0,0 +1,13 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use multi_party_ecdsa::utilities::zk_pdl::{PDLProverFirstMessage, Verifier};
+use multi_party_ecdsa_fuzz::{from_json, LINDELL};
+
+fuzz_target!(|data: &[u8]| {
+    if let Some(prover_first) = from_json::<PDLProverFirstMessage>(data) {
+        let mut state = LINDELL.pdl_verifier_state.clone();
+        if Verifier::message2(&prover_first, &LINDELL.pdl_statement, &mut state).is_ok() {
+            let _ = Verifier::finalize(&prover_first, &LINDELL.pdl_prover_second, &state);
+        }
+    }
+});
@@ -0,0 +1,14 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use multi_party_ecdsa::utilities::zk_pdl::{PDLProverSecondMessage, Verifier};
+use multi_party_ecdsa_fuzz::{from_json, LINDELL};
+
+fuzz_target!(|data: &[u8]| {
+    if let Some(prover_second) = from_json::<PDLProverSecondMessage>(data) {
+        let _ = Verifier::finalize(
+            &LINDELL.pdl_prover_first,
+            &prover_second,
+            &LINDELL.pdl_verifier_state,
+        );
+    }
+});
@@ -0,0 +1,21 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use multi_party_ecdsa::utilities::zk_pdl::{PDLVerifierFirstMessage, Prover};
+use multi_party_ecdsa_fuzz::{from_json, rng, LINDELL};
+
+fuzz_target!(|data: &[u8]| {
+    if let Some(verifier_first) = from_json::<PDLVerifierFirstMessage>(data) {
+        let (_, state) = Prover::message1_with_rng(
+            &LINDELL.pdl_witness,
+            &LINDELL.pdl_statement,
+            &verifier_first,
+            &mut rng(),
+        );
+        let _ = Prover::message2(
+            &verifier_first,
+            &LINDELL.pdl_verifier_second,
+            &LINDELL.pdl_witness,
+            &state,
+        );
+    }
+});
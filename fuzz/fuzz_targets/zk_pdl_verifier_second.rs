@@ -0,0 +1,15 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use multi_party_ecdsa::utilities::zk_pdl::{PDLVerifierSecondMessage, Prover};
+use multi_party_ecdsa_fuzz::{from_json, LINDELL};
+
+fuzz_target!(|data: &[u8]| {
+    if let Some(verifier_second) = from_json::<PDLVerifierSecondMessage>(data) {
+        let _ = Prover::message2(
+            &LINDELL.pdl_verifier_first,
+            &verifier_second,
+            &LINDELL.pdl_witness,
+            &LINDELL.pdl_prover_state,
+        );
+    }
+});
@@ -0,0 +1,638 @@
+#![allow(non_snake_case)]
+
+/*
+    Multi-party ECDSA
+
+    Copyright 2018 by Kzen Networks
+
+    This file is part of Multi-party ECDSA library
+    (https://github.com/KZen-networks/multi-party-ecdsa)
+
+    Multi-party ECDSA is free software: you can redistribute
+    it and/or modify it under the terms of the GNU General Public
+    License as published by the Free Software Foundation, either
+    version 3 of the License, or (at your option) any later version.
+
+    @license GPL-3.0+ <https://github.com/KZen-networks/multi-party-ecdsa/blob/master/LICENSE>
+*/
+
+/// Honest protocol state shared by the fuzz targets.
+/// Every fixture is an honest run driven by a seeded RNG, computed once per process. A target
+/// deserializes the fuzz input into one message type, puts it in place of the message the
+/// counterparty sent, and runs the verification the receiving party would run.
+/// `seed_corpus` returns the honest messages, so the fuzzer starts from well formed JSON.
+use curv::cryptographic_primitives::hashing::hash_sha256::HSha256;
+use curv::cryptographic_primitives::hashing::traits::Hash;
+use curv::cryptographic_primitives::proofs::sigma_correct_homomorphic_elgamal_enc::HomoELGamalProof;
+use curv::cryptographic_primitives::proofs::sigma_dlog::DLogProof;
+use curv::cryptographic_primitives::secret_sharing::feldman_vss::VerifiableSS;
+use curv::elliptic::curves::traits::*;
+use curv::{BigInt, FE, GE};
+use lazy_static::lazy_static;
+use paillier::EncryptionKey;
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use multi_party_ecdsa::protocols::multi_party_ecdsa::gg_2018::party_i::{
+    KeyGenBroadcastMessage1, KeyGenDecommitMessage1, Keys, LocalSignature, Parameters,
+    PartyPrivate, Phase5ADecom1, Phase5Com1, Phase5Com2, Phase5DDecom2, SignBroadcastPhase1,
+    SignDecommitPhase1, SignKeys,
+};
+use multi_party_ecdsa::protocols::two_party_ecdsa::lindell_2017::{party_one, party_two};
+use multi_party_ecdsa::utilities::mta::{MessageA, MessageB};
+use multi_party_ecdsa::utilities::sampling;
+use multi_party_ecdsa::utilities::zk_pdl::{
+    PDLProverFirstMessage, PDLProverSecondMessage, PDLProverState, PDLStatement,
+    PDLVerifierFirstMessage, PDLVerifierSecondMessage, PDLVerifierState, PDLWitness,
+};
+use multi_party_ecdsa::utilities::zk_pdl_with_slack::{
+    PDLwSlackProof, PDLwSlackStatement, PDLwSlackWitness,
+};
+
+const SEED: u64 = 0x6d70_6563_6473_61;
+
+pub fn rng() -> ChaCha20Rng {
+    ChaCha20Rng::seed_from_u64(SEED)
+}
+
+pub fn from_json<T: DeserializeOwned>(data: &[u8]) -> Option<T> {
+    serde_json::from_slice(data).ok()
+}
+
+pub fn message() -> BigInt {
+    HSha256::create_hash(&[&BigInt::from(&[79u8, 77, 69, 82][..])])
+}
+
+lazy_static! {
+    pub static ref GG18: Gg18 = Gg18::new();
+    pub static ref MTA: Mta = Mta::new();
+    pub static ref LINDELL: Lindell = Lindell::new();
+    pub static ref PDL_WITH_SLACK: PdlWithSlack = PdlWithSlack::new();
+}
+
+// t = 1, n = 2, both parties sign. Party 0 is the receiver, party 1 the fuzzed sender.
+pub struct Gg18 {
+    pub keys: Vec<Keys>,
+    pub keygen_bc1: Vec<KeyGenBroadcastMessage1>,
+    pub keygen_decom: Vec<KeyGenDecommitMessage1>,
+    pub y_vec: Vec<GE>,
+    pub vss_schemes: Vec<VerifiableSS>,
+    // the shares party 0 received, shares[j] was dealt by party j
+    pub shares: Vec<FE>,
+    pub dlog_proofs: Vec<DLogProof>,
+    pub sign_keys: Vec<SignKeys>,
+    pub sign_bc1: Vec<SignBroadcastPhase1>,
+    pub sign_decom: Vec<SignDecommitPhase1>,
+    // b_proofs[j] is the proof of gamma_j from the MtA party j answered
+    pub b_proofs: Vec<DLogProof>,
+    pub delta_inv: FE,
+    pub R: GE,
+    pub local_sig: LocalSignature,
+    pub phase5_com: Vec<Phase5Com1>,
+    pub phase5a_decom: Vec<Phase5ADecom1>,
+    pub elgamal_proofs: Vec<HomoELGamalProof>,
+    pub dlog_proofs_rho: Vec<DLogProof>,
+    pub phase5_com2: Vec<Phase5Com2>,
+    pub phase5d_decom2: Vec<Phase5DDecom2>,
+}
+
+impl Gg18 {
+    pub fn params() -> Parameters {
+        Parameters {
+            threshold: 1,
+            share_count: 2,
+        }
+    }
+
+    fn new() -> Self {
+        let rng = &mut rng();
+        let params = Self::params();
+        let keys = (0..2)
+            .map(|i| Keys::create_with_rng(i, rng))
+            .collect::<Vec<Keys>>();
+        let (keygen_bc1, keygen_decom): (Vec<_>, Vec<_>) = keys
+            .iter()
+            .map(|k| k.phase1_broadcast_phase3_proof_of_correct_key_with_rng(rng))
+            .unzip();
+        let y_vec = keygen_decom.iter().map(|d| d.y_i).collect::<Vec<GE>>();
+        let (vss_schemes, secret_shares): (Vec<_>, Vec<_>) = keys
+            .iter()
+            .map(|k| {
+                let (vss_scheme, secret_shares, _) = k
+                    .phase1_verify_com_phase3_verify_correct_key_phase2_distribute_with_rng(
+                        &params,
+                        &keygen_decom,
+                        &keygen_bc1,
+                        rng,
+                    )
+                    .expect("invalid key");
+                (vss_scheme, secret_shares)
+            })
+            .unzip();
+        let received = |i: usize| secret_shares.iter().map(|s| s[i]).collect::<Vec<FE>>();
+        let (shared_keys, dlog_proofs): (Vec<_>, Vec<_>) = keys
+            .iter()
+            .enumerate()
+            .map(|(i, k)| {
+                k.phase2_verify_vss_construct_keypair_phase3_pok_dlog_with_rng(
+                    &params,
+                    &y_vec,
+                    &received(i),
+                    &vss_schemes,
+                    i + 1,
+                    rng,
+                )
+                .expect("invalid vss")
+            })
+            .unzip();
+
+        let s = [0, 1];
+        let sign_keys = (0..2)
+            .map(|i| {
+                let private = PartyPrivate::set_private(keys[i].clone(), shared_keys[i].clone());
+                SignKeys::create_with_rng(&private, &vss_schemes[0], i, &s, rng)
+            })
+            .collect::<Vec<SignKeys>>();
+        let (sign_bc1, sign_decom): (Vec<_>, Vec<_>) = sign_keys
+            .iter()
+            .map(|k| k.phase1_broadcast_with_rng(rng))
+            .unzip();
+        let m_a = (0..2)
+            .map(|i| MessageA::a_with_rng(&sign_keys[i].k_i, &keys[i].ek, rng).0)
+            .collect::<Vec<MessageA>>();
+        // party j answers the MessageA of the other party
+        let m_b_gamma = (0..2)
+            .map(|j| {
+                MessageB::b_with_rng(
+                    &sign_keys[j].gamma_i,
+                    &keys[1 - j].ek,
+                    m_a[1 - j].clone(),
+                    rng,
+                )
+            })
+            .collect::<Vec<_>>();
+        let m_b_w = (0..2)
+            .map(|j| {
+                MessageB::b_with_rng(&sign_keys[j].w_i, &keys[1 - j].ek, m_a[1 - j].clone(), rng)
+            })
+            .collect::<Vec<_>>();
+        let delta_sigma = (0..2)
+            .map(|i| {
+                let alpha = m_b_gamma[1 - i]
+                    .0
+                    .verify_proofs_get_alpha(&keys[i].dk, &sign_keys[i].k_i)
+                    .expect("wrong dlog or m_b");
+                let miu = m_b_w[1 - i]
+                    .0
+                    .verify_proofs_get_alpha(&keys[i].dk, &sign_keys[i].k_i)
+                    .expect("wrong dlog or m_b");
+                (
+                    sign_keys[i].phase2_delta_i(&[alpha], &[m_b_gamma[i].1]),
+                    sign_keys[i].phase2_sigma_i(&[miu], &[m_b_w[i].1]),
+                )
+            })
+            .collect::<Vec<(FE, FE)>>();
+        let delta_inv = SignKeys::phase3_reconstruct_delta(&[delta_sigma[0].0, delta_sigma[1].0]);
+        let b_proofs = m_b_gamma
+            .iter()
+            .map(|m_b| m_b.0.b_proof.clone())
+            .collect::<Vec<DLogProof>>();
+        let R = SignKeys::phase4(
+            &delta_inv,
+            &b_proofs.iter().collect::<Vec<&DLogProof>>(),
+            sign_decom.clone(),
+            &sign_bc1,
+        )
+        .expect("bad gamma_i decommit");
+
+        let local_sigs = (0..2)
+            .map(|i| {
+                LocalSignature::phase5_local_sig_with_rng(
+                    &sign_keys[i].k_i,
+                    &message(),
+                    &R,
+                    &delta_sigma[i].1,
+                    &shared_keys[i].y,
+                    rng,
+                )
+            })
+            .collect::<Vec<LocalSignature>>();
+        let mut phase5_com = Vec::new();
+        let mut phase5a_decom = Vec::new();
+        let mut elgamal_proofs = Vec::new();
+        let mut dlog_proofs_rho = Vec::new();
+        for sig in &local_sigs {
+            let (com, decom, elgamal_proof, dlog_proof_rho) =
+                sig.phase5a_broadcast_5b_zkproof_with_rng(rng);
+            phase5_com.push(com);
+            phase5a_decom.push(decom);
+            elgamal_proofs.push(elgamal_proof);
+            dlog_proofs_rho.push(dlog_proof_rho);
+        }
+        let (phase5_com2, phase5d_decom2): (Vec<_>, Vec<_>) = (0..2)
+            .map(|i| {
+                local_sigs[i]
+                    .phase5c_with_rng(
+                        &[phase5a_decom[1 - i].clone()],
+                        &[phase5_com[1 - i].clone()],
+                        &[elgamal_proofs[1 - i].clone()],
+                        &dlog_proofs_rho,
+                        &phase5a_decom[i].V_i,
+                        &R,
+                        rng,
+                    )
+                    .expect("error phase5")
+            })
+            .unzip();
+
+        Gg18 {
+            shares: received(0),
+            keys,
+            keygen_bc1,
+            keygen_decom,
+            y_vec,
+            vss_schemes,
+            dlog_proofs,
+            sign_keys,
+            sign_bc1,
+            sign_decom,
+            b_proofs,
+            delta_inv,
+            R,
+            local_sig: local_sigs[0].clone(),
+            phase5_com,
+            phase5a_decom,
+            elgamal_proofs,
+            dlog_proofs_rho,
+            phase5_com2,
+            phase5d_decom2,
+        }
+    }
+}
+
+// Alice holds a and the Paillier key, Bob holds b
+pub struct Mta {
+    pub a: FE,
+    pub b: FE,
+    pub ek: EncryptionKey,
+    pub dk: paillier::DecryptionKey,
+    pub m_a: MessageA,
+    pub m_b: MessageB,
+}
+
+impl Mta {
+    fn new() -> Self {
+        let rng = &mut rng();
+        let (ek, dk) = sampling::paillier_keypair(rng);
+        let a = sampling::sample_scalar(rng);
+        let b = sampling::sample_scalar(rng);
+        let m_a = MessageA::a_with_rng(&a, &ek, rng).0;
+        let m_b = MessageB::b_with_rng(&b, &ek, m_a.clone(), rng).0;
+        Mta {
+            a,
+            b,
+            ek,
+            dk,
+            m_a,
+            m_b,
+        }
+    }
+}
+
+// key generation up to the PDL proof and one ephemeral key exchange
+pub struct Lindell {
+    pub keygen_party_one_first: party_one::KeyGenFirstMsg,
+    pub keygen_party_one_second: party_one::KeyGenSecondMsg,
+    pub comm_witness: party_one::CommWitness,
+    pub keygen_party_two_first: party_two::KeyGenFirstMsg,
+    pub paillier_ek: EncryptionKey,
+    pub party_one_private: party_one::Party1Private,
+    pub pdl_statement: PDLStatement,
+    pub pdl_witness: PDLWitness,
+    pub pdl_verifier_first: PDLVerifierFirstMessage,
+    // the verifier state after the prover's first message
+    pub pdl_verifier_state: PDLVerifierState,
+    pub pdl_verifier_second: PDLVerifierSecondMessage,
+    pub pdl_prover_first: PDLProverFirstMessage,
+    pub pdl_prover_state: PDLProverState,
+    pub pdl_prover_second: PDLProverSecondMessage,
+    pub eph_comm_witness: party_two::EphCommWitness,
+    pub eph_party_one_first: party_one::EphKeyGenFirstMsg,
+    pub eph_ec_key_pair_party1: party_one::EphEcKeyPair,
+    pub eph_party_two_first: party_two::EphKeyGenFirstMsg,
+    pub eph_party_two_second: party_two::EphKeyGenSecondMsg,
+    pub partial_sig: party_two::PartialSig,
+    pub correct_key_proof: zk_paillier::zkproofs::NICorrectKeyProof,
+    pub pubkey: GE,
+}
+
+impl Lindell {
+    fn new() -> Self {
+        let rng = &mut rng();
+        let (keygen_party_one_first, comm_witness, ec_key_pair_party1) =
+            party_one::KeyGenFirstMsg::create_commitments_with_rng(rng);
+        let (keygen_party_two_first, ec_key_pair_party2) =
+            party_two::KeyGenFirstMsg::create_with_rng(rng);
+        let keygen_party_one_second = party_one::KeyGenSecondMsg::verify_and_decommit(
+            comm_witness.clone(),
+            &keygen_party_two_first.d_log_proof,
+        )
+        .expect("failed to verify and decommit");
+
+        let paillier_key_pair =
+            party_one::PaillierKeyPair::generate_keypair_and_encrypted_share_with_rng(
+                &ec_key_pair_party1,
+                rng,
+            );
+        let party_one_private =
+            party_one::Party1Private::set_private_key(&ec_key_pair_party1, &paillier_key_pair);
+        let correct_key_proof =
+            party_one::PaillierKeyPair::generate_ni_proof_correct_key(&paillier_key_pair);
+        let party_two_paillier = party_two::PaillierPublic {
+            ek: paillier_key_pair.ek.clone(),
+            encrypted_secret_share: paillier_key_pair.encrypted_share.clone(),
+        };
+
+        let (pdl_verifier_first, mut pdl_verifier_state, pdl_statement) =
+            party_two_paillier.pdl_first_message_with_rng(&comm_witness.public_share, rng);
+        let (pdl_prover_first, pdl_prover_state, _, pdl_witness) =
+            party_one::PaillierKeyPair::pdl_first_message_with_rng(
+                &party_one_private,
+                &pdl_verifier_first,
+                &paillier_key_pair,
+                rng,
+            );
+        let pdl_verifier_second = party_two::PaillierPublic::pdl_second_message(
+            &pdl_prover_first,
+            &pdl_statement,
+            &mut pdl_verifier_state,
+        )
+        .expect("range proof error");
+        let pdl_prover_second = party_one::PaillierKeyPair::pdl_second_message(
+            &pdl_verifier_first,
+            &pdl_verifier_second,
+            &pdl_witness,
+            &pdl_prover_state,
+        )
+        .expect("pdl error");
+
+        let (eph_party_two_first, eph_comm_witness, eph_ec_key_pair_party2) =
+            party_two::EphKeyGenFirstMsg::create_commitments_with_rng(rng);
+        let (eph_party_one_first, eph_ec_key_pair_party1) =
+            party_one::EphKeyGenFirstMsg::create_with_rng(rng);
+        let eph_party_two_second = party_two::EphKeyGenSecondMsg::verify_and_decommit(
+            eph_comm_witness.clone(),
+            &eph_party_one_first,
+        )
+        .expect("party1 DLog proof failed");
+
+        let party_two_private = party_two::Party2Private::set_private_key(&ec_key_pair_party2);
+        let partial_sig = party_two::PartialSig::compute_with_rng(
+            &paillier_key_pair.ek,
+            &paillier_key_pair.encrypted_share,
+            &party_two_private,
+            &eph_ec_key_pair_party2,
+            &eph_party_one_first.public_share,
+            &message(),
+            rng,
+        );
+        let pubkey =
+            party_one::compute_pubkey(&party_one_private, &keygen_party_two_first.public_share);
+
+        Lindell {
+            keygen_party_one_first,
+            keygen_party_one_second,
+            comm_witness,
+            keygen_party_two_first,
+            paillier_ek: paillier_key_pair.ek.clone(),
+            party_one_private,
+            pdl_statement,
+            pdl_witness,
+            pdl_verifier_first,
+            pdl_verifier_state,
+            pdl_verifier_second,
+            pdl_prover_first,
+            pdl_prover_state,
+            pdl_prover_second,
+            eph_comm_witness,
+            eph_party_one_first,
+            eph_ec_key_pair_party1,
+            eph_party_two_first,
+            eph_party_two_second,
+            partial_sig,
+            correct_key_proof,
+            pubkey,
+        }
+    }
+}
+
+pub struct PdlWithSlack {
+    pub statement: PDLwSlackStatement,
+    pub proof: PDLwSlackProof,
+}
+
+impl PdlWithSlack {
+    fn new() -> Self {
+        let rng = &mut rng();
+        let (ek_tilde, dk_tilde) = sampling::paillier_keypair(rng);
+        let one = BigInt::one();
+        let phi = (&dk_tilde.p - &one) * (&dk_tilde.q - &one);
+        let h1 = sampling::sample_below(rng, &phi);
+        let xhi = sampling::sample_bits(rng, 256);
+        let h2 = curv::arithmetic::traits::Modulo::mod_pow(&h1, &(-&xhi), &ek_tilde.n);
+
+        let (ek, dk) = sampling::paillier_keypair(rng);
+        let r = sampling::sample_below(rng, &ek.n);
+        let x = sampling::sample_scalar(rng);
+        let ciphertext = paillier::EncryptWithChosenRandomness::encrypt_with_chosen_randomness(
+            &ek,
+            paillier::RawPlaintext::from(x.to_big_int()),
+            &paillier::Randomness::from(r.clone()),
+        )
+        .0
+        .into_owned();
+        let statement = PDLwSlackStatement {
+            ciphertext,
+            ek,
+            Q: GE::generator() * &x,
+            G: GE::generator(),
+            h1,
+            h2,
+            N_tilde: ek_tilde.n,
+        };
+        let witness = PDLwSlackWitness { x, r, dk };
+        let proof = PDLwSlackProof::prove_with_rng(&witness, &statement, rng);
+        PdlWithSlack { statement, proof }
+    }
+}
+
+#[cfg(feature = "cclst")]
+pub mod cclst {
+    use super::{message, rng};
+    use class_group::primitives::cl_dl_lcm::CLDLProofPublicSetup;
+    use curv::BigInt;
+    use curv::GE;
+    use lazy_static::lazy_static;
+    use multi_party_ecdsa::protocols::two_party_ecdsa::cclst_2019::party_two::HSMCLPublic;
+    use multi_party_ecdsa::protocols::two_party_ecdsa::cclst_2019::{party_one, party_two};
+
+    lazy_static! {
+        pub static ref CCLST: Cclst = Cclst::new();
+    }
+
+    pub struct Cclst {
+        pub cldl_proof: CLDLProofPublicSetup,
+        pub party_one_private: party_one::Party1Private,
+        pub eph_comm_witness: party_two::EphCommWitness,
+        pub eph_party_one_first: party_one::EphKeyGenFirstMsg,
+        pub eph_ec_key_pair_party1: party_one::EphEcKeyPair,
+        pub eph_party_two_first: party_two::EphKeyGenFirstMsg,
+        pub eph_party_two_second: party_two::EphKeyGenSecondMsg,
+        pub partial_sig: party_two::PartialSig,
+        pub pubkey: GE,
+    }
+
+    impl Cclst {
+        fn new() -> Self {
+            let rng = &mut rng();
+            let (_, _, ec_key_pair_party1) =
+                party_one::KeyGenFirstMsg::create_commitments_with_rng(rng);
+            let (party_two_first, ec_key_pair_party2) =
+                party_two::KeyGenFirstMsg::create_with_rng(rng);
+            let seed: BigInt = str::parse(
+                "314159265358979323846264338327950288419716939937510582097494459230781640628620899862803482534211706798214808651328230664709384460955058223172535940812848"
+            ).unwrap();
+            let hsmcl_key_pair =
+                party_one::HSMCLKeyPair::generate_keypair_and_encrypted_share_with_rng(
+                    &ec_key_pair_party1,
+                    seed.clone(),
+                    rng,
+                );
+            let party_one_private =
+                party_one::Party1Private::set_private_key(&ec_key_pair_party1, &hsmcl_key_pair);
+            let cldl_proof = party_one::HSMCLKeyPair::generate_zkcldl_proof(
+                &hsmcl_key_pair,
+                &party_one_private,
+                seed,
+            );
+
+            let (eph_party_two_first, eph_comm_witness, eph_ec_key_pair_party2) =
+                party_two::EphKeyGenFirstMsg::create_commitments_with_rng(rng);
+            let (eph_party_one_first, eph_ec_key_pair_party1) =
+                party_one::EphKeyGenFirstMsg::create_with_rng(rng);
+            let eph_party_two_second = party_two::EphKeyGenSecondMsg::verify_and_decommit(
+                eph_comm_witness.clone(),
+                &eph_party_one_first,
+            )
+            .expect("party1 DLog proof failed");
+
+            let partial_sig = party_two::PartialSig::compute_with_rng(
+                HSMCLPublic::set(&hsmcl_key_pair.keypair.pk, &hsmcl_key_pair.encrypted_share),
+                &party_two::Party2Private::set_private_key(&ec_key_pair_party2),
+                &eph_ec_key_pair_party2,
+                &eph_party_one_first.public_share,
+                &message(),
+                rng,
+            );
+            let pubkey =
+                party_one::compute_pubkey(&party_one_private, &party_two_first.public_share);
+
+            Cclst {
+                cldl_proof,
+                party_one_private,
+                eph_comm_witness,
+                eph_party_one_first,
+                eph_ec_key_pair_party1,
+                eph_party_two_first,
+                eph_party_two_second,
+                partial_sig,
+                pubkey,
+            }
+        }
+    }
+}
+
+fn json<T: Serialize>(value: &T) -> Vec<u8> {
+    serde_json::to_vec(value).expect("unserializable message")
+}
+
+// honest inputs for every target, the target name is the name of its file in fuzz_targets
+pub fn seed_corpus() -> Vec<(&'static str, Vec<u8>)> {
+    let gg18 = &*GG18;
+    let mta = &*MTA;
+    let lindell = &*LINDELL;
+    let mut corpus = vec![
+        ("gg18_keygen_broadcast", json(&gg18.keygen_bc1[1])),
+        ("gg18_keygen_decommit", json(&gg18.keygen_decom[1])),
+        (
+            "gg18_keygen_vss",
+            json(&(&gg18.vss_schemes[1], &gg18.shares[1])),
+        ),
+        ("gg18_keygen_dlog_proof", json(&gg18.dlog_proofs[1])),
+        (
+            "gg18_sign_phase1",
+            json(&(&gg18.sign_bc1[1], &gg18.sign_decom[1])),
+        ),
+        (
+            "gg18_sign_phase5a",
+            json(&(
+                &gg18.phase5_com[1],
+                &gg18.phase5a_decom[1],
+                &gg18.elgamal_proofs[1],
+                &gg18.dlog_proofs_rho[1],
+            )),
+        ),
+        (
+            "gg18_sign_phase5d",
+            json(&(&gg18.phase5_com2[1], &gg18.phase5d_decom2[1])),
+        ),
+        ("mta_message_a", json(&mta.m_a)),
+        ("mta_message_b", json(&mta.m_b)),
+        ("zk_pdl_verifier_first", json(&lindell.pdl_verifier_first)),
+        ("zk_pdl_prover_first", json(&lindell.pdl_prover_first)),
+        ("zk_pdl_verifier_second", json(&lindell.pdl_verifier_second)),
+        ("zk_pdl_prover_second", json(&lindell.pdl_prover_second)),
+        ("pdl_with_slack", json(&PDL_WITH_SLACK.proof)),
+        (
+            "lindell_keygen_party_one",
+            json(&(
+                &lindell.keygen_party_one_first,
+                &lindell.keygen_party_one_second,
+            )),
+        ),
+        (
+            "lindell_keygen_party_two",
+            json(&lindell.keygen_party_two_first),
+        ),
+        ("lindell_correct_key", json(&lindell.correct_key_proof)),
+        ("lindell_eph_party_one", json(&lindell.eph_party_one_first)),
+        (
+            "lindell_eph_party_two",
+            json(&(&lindell.eph_party_two_first, &lindell.eph_party_two_second)),
+        ),
+        ("lindell_partial_sig", json(&lindell.partial_sig)),
+    ];
+    corpus.extend(cclst_seed_corpus());
+    corpus
+}
+
+#[cfg(feature = "cclst")]
+fn cclst_seed_corpus() -> Vec<(&'static str, Vec<u8>)> {
+    let cclst = &*cclst::CCLST;
+    vec![
+        ("cclst_cldl_proof", json(&cclst.cldl_proof)),
+        ("cclst_eph_party_one", json(&cclst.eph_party_one_first)),
+        (
+            "cclst_eph_party_two",
+            json(&(&cclst.eph_party_two_first, &cclst.eph_party_two_second)),
+        ),
+        ("cclst_partial_sig", json(&cclst.partial_sig)),
+    ]
+}
+
+#[cfg(not(feature = "cclst"))]
+fn cclst_seed_corpus() -> Vec<(&'static str, Vec<u8>)> {
+    Vec::new()
+}
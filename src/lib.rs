@@ -15,12 +15,45 @@
 */
 
 pub mod protocols;
+pub mod simulation;
+pub mod transport;
 pub mod utilities;
-#[derive(Copy, PartialEq, Eq, Clone, Debug)]
+#[derive(PartialEq, Eq, Clone, Debug)]
 pub enum Error {
     InvalidKey,
     InvalidSS,
     InvalidCom,
     InvalidSig,
     Phase5BadSum,
+    InvalidCiphertext,
+    /// The shares dealt by the parties at these positions do not match their commitments.
+    InvalidShare {
+        parties: Vec<usize>,
+    },
+    /// The proofs of the parties at these positions do not verify.
+    InvalidProof {
+        parties: Vec<usize>,
+    },
+    /// The signer at this index into vss_scheme committed to another message, signer set or key.
+    MessageMismatch {
+        party: usize,
+    },
+    /// The g^w_i of the signer at this index into vss_scheme is not its share of the key.
+    PublicShareMismatch {
+        party: usize,
+    },
+    /// A party id that is 0 or beyond the parties of the key.
+    InvalidPartyId {
+        party: u16,
+    },
+    DuplicatePartyId {
+        party: u16,
+    },
+}
+
+// serde reports the errors of try_from conversions, e.g. deserializing PartyId(0), with Display
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
 }
@@ -15,6 +15,11 @@
 */
 
 pub mod party_i;
+pub mod party_id;
+pub mod refresh;
 
 #[cfg(test)]
 mod test;
+
+#[cfg(test)]
+mod test_adversary;
@@ -17,24 +17,34 @@
 */
 use centipede::juggling::proof_system::{Helgamalsegmented, Witness};
 use centipede::juggling::segmentation::Msegmentation;
-use curv::arithmetic::traits::*;
+use curv::arithmetic::traits::Converter;
 use curv::cryptographic_primitives::commitments::hash_commitment::HashCommitment;
 use curv::cryptographic_primitives::commitments::traits::Commitment;
 use curv::cryptographic_primitives::hashing::hash_sha256::HSha256;
 use curv::cryptographic_primitives::hashing::traits::Hash;
 use curv::cryptographic_primitives::proofs::sigma_correct_homomorphic_elgamal_enc::*;
-use curv::cryptographic_primitives::proofs::sigma_dlog::{DLogProof, ProveDLog};
+use curv::cryptographic_primitives::proofs::sigma_dlog::DLogProof;
 use curv::cryptographic_primitives::secret_sharing::feldman_vss::VerifiableSS;
 use curv::elliptic::curves::traits::*;
 use curv::{BigInt, FE, GE};
 
-use paillier::{
-    Decrypt, DecryptionKey, EncryptionKey, KeyGeneration, Paillier, RawCiphertext, RawPlaintext,
-};
+use paillier::{Decrypt, DecryptionKey, EncryptionKey, Paillier, RawCiphertext, RawPlaintext};
+use rand::rngs::OsRng;
+use rand::{CryptoRng, RngCore};
 use serde::{Deserialize, Serialize};
 use zk_paillier::zkproofs::NICorrectKeyProof;
 
-use crate::Error::{self, InvalidCom, InvalidKey, InvalidSS, InvalidSig};
+use super::party_id::{self, PartyId};
+use crate::utilities::batch_verify;
+pub use crate::utilities::ecdsa::SignatureRecid;
+use crate::utilities::ecdsa::{self, Malleability};
+use crate::utilities::mta::MessageB;
+use crate::utilities::parallel;
+use crate::utilities::sampling;
+use crate::Error::{
+    self, InvalidCom, InvalidKey, InvalidPartyId, InvalidProof, InvalidShare, InvalidSig,
+    MessageMismatch, PublicShareMismatch,
+};
 
 const SECURITY: usize = 256;
 
@@ -99,6 +109,17 @@ pub struct SignDecommitPhase1 {
     pub g_gamma_i: GE,
 }
 
+/// What a signing session is about: the message, the signers (as indices into vss_scheme) and
+/// the public key. Every signer broadcasts its commitment with SignBroadcastPhase1, so signers
+/// that were handed different messages stop before the MtA rounds rather than failing in
+/// output_signature.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SignContext {
+    pub message: BigInt,
+    pub signers: Vec<usize>,
+    pub y: GE,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct LocalSignature {
     pub l_i: FE,
@@ -134,18 +155,15 @@ pub struct Phase5DDecom2 {
     pub blind_factor: BigInt,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
-pub struct SignatureRecid {
-    pub r: FE,
-    pub s: FE,
-    pub recid: u8,
-}
-
 impl Keys {
     pub fn create(index: usize) -> Self {
-        let u = FE::new_random();
+        Self::create_with_rng(index, &mut OsRng)
+    }
+
+    pub fn create_with_rng<R: RngCore + CryptoRng>(index: usize, rng: &mut R) -> Self {
+        let u = sampling::sample_scalar(rng);
         let y = GE::generator() * u;
-        let (ek, dk) = Paillier::keypair().keys();
+        let (ek, dk) = sampling::paillier_keypair(rng);
 
         Self {
             u_i: u,
@@ -158,22 +176,30 @@ impl Keys {
 
     // we recommend using safe primes if the code is used in production
     pub fn create_safe_prime(index: usize) -> Keys {
-        let u: FE = ECScalar::new_random();
+        Self::create_safe_prime_with_rng(index, &mut OsRng)
+    }
+
+    pub fn create_safe_prime_with_rng<R: RngCore + CryptoRng>(index: usize, rng: &mut R) -> Keys {
+        let u: FE = sampling::sample_scalar(rng);
         let y = &ECPoint::generator() * &u;
 
-        let (ek, dk) = Paillier::keypair_safe_primes().keys();
+        let (ek, dk) = sampling::paillier_keypair_safe_primes(rng);
 
         Keys {
             u_i: u,
             y_i: y,
             dk,
             ek,
-            party_index: index.clone(),
+            party_index: index,
         }
     }
     pub fn create_from(u: FE, index: usize) -> Keys {
+        Self::create_from_with_rng(u, index, &mut OsRng)
+    }
+
+    pub fn create_from_with_rng<R: RngCore + CryptoRng>(u: FE, index: usize, rng: &mut R) -> Keys {
         let y = &ECPoint::generator() * &u;
-        let (ek, dk) = Paillier::keypair().keys();
+        let (ek, dk) = sampling::paillier_keypair(rng);
 
         Self {
             u_i: u,
@@ -187,7 +213,14 @@ impl Keys {
     pub fn phase1_broadcast_phase3_proof_of_correct_key(
         &self,
     ) -> (KeyGenBroadcastMessage1, KeyGenDecommitMessage1) {
-        let blind_factor = BigInt::sample(SECURITY);
+        self.phase1_broadcast_phase3_proof_of_correct_key_with_rng(&mut OsRng)
+    }
+
+    pub fn phase1_broadcast_phase3_proof_of_correct_key_with_rng<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+    ) -> (KeyGenBroadcastMessage1, KeyGenDecommitMessage1) {
+        let blind_factor = sampling::sample_bits(rng, SECURITY);
         let correct_key_proof = NICorrectKeyProof::proof(&self.dk);
         let com = HashCommitment::create_commitment_with_user_defined_randomness(
             &self.y_i.bytes_compressed_to_big_int(),
@@ -210,22 +243,35 @@ impl Keys {
         params: &Parameters,
         decom_vec: &[KeyGenDecommitMessage1],
         bc1_vec: &[KeyGenBroadcastMessage1],
+    ) -> Result<(VerifiableSS, Vec<FE>, usize), Error> {
+        self.phase1_verify_com_phase3_verify_correct_key_phase2_distribute_with_rng(
+            params, decom_vec, bc1_vec, &mut OsRng,
+        )
+    }
+
+    pub fn phase1_verify_com_phase3_verify_correct_key_phase2_distribute_with_rng<
+        R: RngCore + CryptoRng,
+    >(
+        &self,
+        params: &Parameters,
+        decom_vec: &[KeyGenDecommitMessage1],
+        bc1_vec: &[KeyGenBroadcastMessage1],
+        rng: &mut R,
     ) -> Result<(VerifiableSS, Vec<FE>, usize), Error> {
         // test length:
         assert_eq!(decom_vec.len() as u16, params.share_count);
         assert_eq!(bc1_vec.len() as u16, params.share_count);
         // test paillier correct key and test decommitments
-        let correct_key_correct_decom_all = (0..bc1_vec.len())
-            .map(|i| {
-                HashCommitment::create_commitment_with_user_defined_randomness(
-                    &decom_vec[i].y_i.bytes_compressed_to_big_int(),
-                    &decom_vec[i].blind_factor,
-                ) == bc1_vec[i].com
-                    && bc1_vec[i].correct_key_proof.verify(&bc1_vec[i].e).is_ok()
-            })
-            .all(|x| x);
-
-        let (vss_scheme, secret_shares) = VerifiableSS::share(
+        let correct_key_correct_decom_all = parallel::all_indices(bc1_vec.len(), |i| {
+            HashCommitment::create_commitment_with_user_defined_randomness(
+                &decom_vec[i].y_i.bytes_compressed_to_big_int(),
+                &decom_vec[i].blind_factor,
+            ) == bc1_vec[i].com
+                && bc1_vec[i].correct_key_proof.verify(&bc1_vec[i].e).is_ok()
+        });
+
+        let (vss_scheme, secret_shares) = sampling::share(
+            rng,
             params.threshold as usize,
             params.share_count as usize,
             &self.u_i,
@@ -244,38 +290,51 @@ impl Keys {
         secret_shares_vec: &[FE],
         vss_scheme_vec: &[VerifiableSS],
         index: usize,
+    ) -> Result<(SharedKeys, DLogProof), Error> {
+        self.phase2_verify_vss_construct_keypair_phase3_pok_dlog_with_rng(
+            params,
+            y_vec,
+            secret_shares_vec,
+            vss_scheme_vec,
+            index,
+            &mut OsRng,
+        )
+    }
+
+    pub fn phase2_verify_vss_construct_keypair_phase3_pok_dlog_with_rng<R: RngCore + CryptoRng>(
+        &self,
+        params: &Parameters,
+        y_vec: &[GE],
+        secret_shares_vec: &[FE],
+        vss_scheme_vec: &[VerifiableSS],
+        index: usize,
+        rng: &mut R,
     ) -> Result<(SharedKeys, DLogProof), Error> {
         assert_eq!(y_vec.len() as u16, params.share_count);
         assert_eq!(secret_shares_vec.len() as u16, params.share_count);
         assert_eq!(vss_scheme_vec.len() as u16, params.share_count);
 
-        let correct_ss_verify = (0..y_vec.len())
-            .map(|i| {
-                vss_scheme_vec[i]
-                    .validate_share(&secret_shares_vec[i], index)
-                    .is_ok()
-                    && vss_scheme_vec[i].commitments[0].get_element() == y_vec[i].get_element()
-            })
-            .all(|x| x);
+        let parties = (0..y_vec.len())
+            .filter(|&i| vss_scheme_vec[i].commitments[0].get_element() != y_vec[i].get_element())
+            .collect::<Vec<usize>>();
+        if !parties.is_empty() {
+            return Err(InvalidShare { parties });
+        }
+        batch_verify::validate_shares(vss_scheme_vec, secret_shares_vec, index)
+            .map_err(|parties| InvalidShare { parties })?;
 
-        if correct_ss_verify {
-            let (head, tail) = y_vec.split_at(1);
-            let y = tail.iter().fold(head[0], |acc, x| acc + x);
+        let (head, tail) = y_vec.split_at(1);
+        let y = tail.iter().fold(head[0], |acc, x| acc + x);
 
-            let x_i = secret_shares_vec.iter().fold(FE::zero(), |acc, x| acc + x);
-            let dlog_proof = DLogProof::prove(&x_i);
-            Ok((SharedKeys { y, x_i }, dlog_proof))
-        } else {
-            Err(InvalidSS)
-        }
+        let x_i = secret_shares_vec.iter().fold(FE::zero(), |acc, x| acc + x);
+        let dlog_proof = sampling::prove_dlog(rng, &x_i);
+        Ok((SharedKeys { y, x_i }, dlog_proof))
     }
 
     pub fn get_commitments_to_xi(vss_scheme: &VerifiableSS) -> Vec<GE> {
         let len = vss_scheme.parameters.share_count;
         (1..=len)
-            .map(|i| {
-                vss_scheme.get_point_commitment(i)
-            })
+            .map(|i| vss_scheme.get_point_commitment(i))
             .collect::<Vec<GE>>()
     }
 
@@ -296,15 +355,8 @@ impl Keys {
     ) -> Result<(), Error> {
         assert_eq!(y_vec.len() as u16, params.share_count);
         assert_eq!(dlog_proofs_vec.len() as u16, params.share_count);
-        let xi_dlog_verify = (0..y_vec.len())
-            .map(|i| DLogProof::verify(&dlog_proofs_vec[i]).is_ok())
-            .all(|x| x);
-
-        if xi_dlog_verify {
-            Ok(())
-        } else {
-            Err(InvalidKey)
-        }
+        batch_verify::verify_dlog_proofs(dlog_proofs_vec)
+            .map_err(|parties| InvalidProof { parties })
     }
 }
 
@@ -327,9 +379,18 @@ impl PartyPrivate {
     }
 
     pub fn refresh_private_key(&self, factor: &FE, index: usize) -> Keys {
+        self.refresh_private_key_with_rng(factor, index, &mut OsRng)
+    }
+
+    pub fn refresh_private_key_with_rng<R: RngCore + CryptoRng>(
+        &self,
+        factor: &FE,
+        index: usize,
+        rng: &mut R,
+    ) -> Keys {
         let u: FE = self.u_i + factor;
         let y = GE::generator() * u;
-        let (ek, dk) = Paillier::keypair().keys();
+        let (ek, dk) = sampling::paillier_keypair(rng);
 
         Keys {
             u_i: u,
@@ -342,16 +403,25 @@ impl PartyPrivate {
 
     // we recommend using safe primes if the code is used in production
     pub fn refresh_private_key_safe_prime(&self, factor: &FE, index: usize) -> Keys {
+        self.refresh_private_key_safe_prime_with_rng(factor, index, &mut OsRng)
+    }
+
+    pub fn refresh_private_key_safe_prime_with_rng<R: RngCore + CryptoRng>(
+        &self,
+        factor: &FE,
+        index: usize,
+        rng: &mut R,
+    ) -> Keys {
         let u: FE = self.u_i + factor;
         let y = &ECPoint::generator() * &u;
-        let (ek, dk) = Paillier::keypair_safe_primes().keys();
+        let (ek, dk) = sampling::paillier_keypair_safe_primes(rng);
 
         Keys {
             u_i: u,
             y_i: y,
             dk,
             ek,
-            party_index: index.clone(),
+            party_index: index,
         }
     }
 
@@ -375,31 +445,131 @@ impl PartyPrivate {
     }
 }
 
+impl SignContext {
+    pub fn new(message: &BigInt, signers: &[usize], y: &GE) -> Self {
+        Self {
+            message: message.clone(),
+            signers: signers.to_vec(),
+            y: *y,
+        }
+    }
+
+    // H(y, |s|, s, m), the message goes last so no field needs a length prefix. The encoding
+    // starts with the 0x04 of the uncompressed y, so as a BigInt it keeps all its bytes.
+    pub fn commitment(&self) -> BigInt {
+        let mut encoding = self.y.pk_to_key_slice();
+        encoding.extend_from_slice(&(self.signers.len() as u64).to_be_bytes());
+        for &i in &self.signers {
+            encoding.extend_from_slice(&(i as u64).to_be_bytes());
+        }
+        encoding.extend(BigInt::to_vec(&self.message));
+        HSha256::create_hash(&[&BigInt::from(&encoding[..])])
+    }
+
+    /// Checks the commitments broadcast by the signers, in the order of signers, against this
+    /// party's own context. Fails naming the first signer whose commitment differs, or with
+    /// InvalidCom if there is not one commitment per signer.
+    pub fn verify_commitments(&self, commitments: &[BigInt]) -> Result<(), Error> {
+        if commitments.len() != self.signers.len() {
+            return Err(InvalidCom);
+        }
+        let mine = self.commitment();
+        match commitments.iter().position(|com| *com != mine) {
+            Some(i) => Err(MessageMismatch {
+                party: self.signers[i],
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
 impl SignKeys {
     pub fn create(
         private: &PartyPrivate,
         vss_scheme: &VerifiableSS,
         index: usize,
         s: &[usize],
+    ) -> Self {
+        Self::create_with_rng(private, vss_scheme, index, s, &mut OsRng)
+    }
+
+    /// create for the party id among the signer ids, which are checked against vss_scheme. Fails
+    /// with PublicShareMismatch if the share of private is not the one vss_scheme commits to,
+    /// e.g. one from before a refresh.
+    pub fn create_for_party(
+        private: &PartyPrivate,
+        vss_scheme: &VerifiableSS,
+        id: PartyId,
+        signers: &[PartyId],
+    ) -> Result<Self, Error> {
+        let s = party_id::indices(signers, vss_scheme)?;
+        if !signers.contains(&id) {
+            return Err(InvalidPartyId { party: id.id() });
+        }
+        let sign_keys = Self::create(private, vss_scheme, id.index(), &s);
+        Self::verify_public_share(&sign_keys.g_w_i, vss_scheme, id.index(), &s)?;
+        Ok(sign_keys)
+    }
+
+    pub fn create_with_rng<R: RngCore + CryptoRng>(
+        private: &PartyPrivate,
+        vss_scheme: &VerifiableSS,
+        index: usize,
+        s: &[usize],
+        rng: &mut R,
     ) -> Self {
         let li = vss_scheme.map_share_to_new_params(index, s);
         let w_i = li * private.x_i;
         let g: GE = ECPoint::generator();
         let g_w_i = g * w_i;
-        let gamma_i: FE = ECScalar::new_random();
+        let gamma_i: FE = sampling::sample_scalar(rng);
         let g_gamma_i = g * gamma_i;
 
         Self {
             w_i,
             g_w_i,
-            k_i: ECScalar::new_random(),
+            k_i: sampling::sample_scalar(rng),
             gamma_i,
             g_gamma_i,
         }
     }
 
+    /// g^w_i of the signer at index, the Lagrange-weighted public share every party derives from
+    /// the VSS commitments of the key (of the last refresh, if any).
+    pub fn public_share(vss_scheme: &VerifiableSS, index: usize, s: &[usize]) -> GE {
+        Keys::update_commitments_to_xi(
+            &vss_scheme.get_point_commitment(index + 1),
+            vss_scheme,
+            index,
+            s,
+        )
+    }
+
+    /// Checks the g^w_j a signer uses against its public share. create_for_party does so for
+    /// the own g_w_i and MessageB::verify_proofs_get_alpha_wc for the w_j of everyone else. A
+    /// wrong or stale share fails naming the signer.
+    pub fn verify_public_share(
+        g_w_j: &GE,
+        vss_scheme: &VerifiableSS,
+        index: usize,
+        s: &[usize],
+    ) -> Result<(), Error> {
+        if MessageB::verify_b_against_public(&Self::public_share(vss_scheme, index, s), g_w_j) {
+            Ok(())
+        } else {
+            Err(PublicShareMismatch { party: index })
+        }
+    }
+
     pub fn phase1_broadcast(&self) -> (SignBroadcastPhase1, SignDecommitPhase1) {
-        let blind_factor = BigInt::sample(SECURITY);
+        self.phase1_broadcast_with_rng(&mut OsRng)
+    }
+
+    pub fn phase1_broadcast_with_rng<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+    ) -> (SignBroadcastPhase1, SignDecommitPhase1) {
+        let blind_factor = sampling::sample_bits(rng, SECURITY);
         let g: GE = ECPoint::generator();
         let g_gamma_i = g * self.gamma_i;
         let com = HashCommitment::create_commitment_with_user_defined_randomness(
@@ -479,11 +649,22 @@ impl SignKeys {
 
 impl LocalSignature {
     pub fn phase5_local_sig(k_i: &FE, message: &BigInt, R: &GE, sigma_i: &FE, pubkey: &GE) -> Self {
+        Self::phase5_local_sig_with_rng(k_i, message, R, sigma_i, pubkey, &mut OsRng)
+    }
+
+    pub fn phase5_local_sig_with_rng<T: RngCore + CryptoRng>(
+        k_i: &FE,
+        message: &BigInt,
+        R: &GE,
+        sigma_i: &FE,
+        pubkey: &GE,
+        rng: &mut T,
+    ) -> Self {
         let m_fe: FE = ECScalar::from(message);
         let r: FE = ECScalar::from(&R.x_coor().unwrap().mod_floor(&FE::q()));
         let s_i = m_fe * k_i + r * sigma_i;
-        let l_i: FE = ECScalar::new_random();
-        let rho_i: FE = ECScalar::new_random();
+        let l_i: FE = sampling::sample_scalar(rng);
+        let rho_i: FE = sampling::sample_scalar(rng);
         Self {
             l_i,
             rho_i,
@@ -497,7 +678,14 @@ impl LocalSignature {
     pub fn phase5a_broadcast_5b_zkproof(
         &self,
     ) -> (Phase5Com1, Phase5ADecom1, HomoELGamalProof, DLogProof) {
-        let blind_factor = BigInt::sample(SECURITY);
+        self.phase5a_broadcast_5b_zkproof_with_rng(&mut OsRng)
+    }
+
+    pub fn phase5a_broadcast_5b_zkproof_with_rng<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+    ) -> (Phase5Com1, Phase5ADecom1, HomoELGamalProof, DLogProof) {
+        let blind_factor = sampling::sample_bits(rng, SECURITY);
         let g: GE = ECPoint::generator();
         let A_i = g * self.rho_i;
         let l_i_rho_i = self.l_i.mul(&self.rho_i.get_element());
@@ -519,8 +707,8 @@ impl LocalSignature {
             D: V_i,
             E: B_i,
         };
-        let dlog_proof_rho = DLogProof::prove(&self.rho_i);
-        let proof = HomoELGamalProof::prove(&witness, &delta);
+        let dlog_proof_rho = sampling::prove_dlog(rng, &self.rho_i);
+        let proof = sampling::prove_homo_elgamal(rng, &witness, &delta);
 
         (
             Phase5Com1 { com },
@@ -543,19 +731,33 @@ impl LocalSignature {
         dlog_proofs_rho: &[DLogProof],
         v_i: &GE,
         R: &GE,
+    ) -> Result<(Phase5Com2, Phase5DDecom2), Error> {
+        self.phase5c_with_rng(
+            decom_vec,
+            com_vec,
+            elgamal_proofs,
+            dlog_proofs_rho,
+            v_i,
+            R,
+            &mut OsRng,
+        )
+    }
+
+    pub fn phase5c_with_rng<T: RngCore + CryptoRng>(
+        &self,
+        decom_vec: &[Phase5ADecom1],
+        com_vec: &[Phase5Com1],
+        elgamal_proofs: &[HomoELGamalProof],
+        dlog_proofs_rho: &[DLogProof],
+        v_i: &GE,
+        R: &GE,
+        rng: &mut T,
     ) -> Result<(Phase5Com2, Phase5DDecom2), Error> {
         assert_eq!(decom_vec.len(), com_vec.len());
 
         let g: GE = ECPoint::generator();
-        let test_com_elgamal = (0..com_vec.len())
+        let test_com = (0..com_vec.len())
             .map(|i| {
-                let delta = HomoElGamalStatement {
-                    G: decom_vec[i].A_i,
-                    H: *R,
-                    Y: g,
-                    D: decom_vec[i].V_i,
-                    E: decom_vec[i].B_i,
-                };
                 let input_hash = HSha256::create_hash_from_ge(&[
                     &decom_vec[i].V_i,
                     &decom_vec[i].A_i,
@@ -567,10 +769,24 @@ impl LocalSignature {
                     &input_hash,
                     &decom_vec[i].blind_factor,
                 ) == com_vec[i].com
-                    && elgamal_proofs[i].verify(&delta).is_ok()
-                    && DLogProof::verify(&dlog_proofs_rho[i]).is_ok()
             })
             .all(|x| x);
+        if !test_com {
+            return Err(InvalidCom);
+        }
+        let delta_vec = (0..com_vec.len())
+            .map(|i| HomoElGamalStatement {
+                G: decom_vec[i].A_i,
+                H: *R,
+                Y: g,
+                D: decom_vec[i].V_i,
+                E: decom_vec[i].B_i,
+            })
+            .collect::<Vec<HomoElGamalStatement>>();
+        batch_verify::verify_homo_elgamal_proofs(&elgamal_proofs[..com_vec.len()], &delta_vec)
+            .map_err(|parties| InvalidProof { parties })?;
+        batch_verify::verify_dlog_proofs(dlog_proofs_rho)
+            .map_err(|parties| InvalidProof { parties })?;
 
         let v_vec = (0..com_vec.len())
             .map(|i| &decom_vec[i].V_i)
@@ -595,26 +811,20 @@ impl LocalSignature {
         let u_i = v * self.rho_i;
         let t_i = a * self.l_i;
         let input_hash = HSha256::create_hash_from_ge(&[&u_i, &t_i]).to_big_int();
-        let blind_factor = BigInt::sample(SECURITY);
+        let blind_factor = sampling::sample_bits(rng, SECURITY);
         let com = HashCommitment::create_commitment_with_user_defined_randomness(
             &input_hash,
             &blind_factor,
         );
 
-        if test_com_elgamal {
-            Ok({
-                (
-                    Phase5Com2 { com },
-                    Phase5DDecom2 {
-                        u_i,
-                        t_i,
-                        blind_factor,
-                    },
-                )
-            })
-        } else {
-            Err(InvalidCom)
-        }
+        Ok((
+            Phase5Com2 { com },
+            Phase5DDecom2 {
+                u_i,
+                t_i,
+                blind_factor,
+            },
+        ))
     }
 
     pub fn phase5d(
@@ -693,20 +903,22 @@ impl LocalSignature {
     }
 }
 
+/// Verifies with the low s rule, the form output_signature produces.
 pub fn verify(sig: &SignatureRecid, y: &GE, message: &BigInt) -> Result<(), Error> {
-    let b = sig.s.invert();
-    let a: FE = ECScalar::from(message);
-    let u1 = a * b;
-    let u2 = sig.r * b;
-
-    let g: GE = ECPoint::generator();
-    let gu1 = g * u1;
-    let yu2 = y * &u2;
-    // can be faster using shamir trick
+    verify_with_policy(sig, y, message, Malleability::RequireLowS)
+}
 
-    if sig.r == ECScalar::from(&(gu1 + yu2).x_coor().unwrap().mod_floor(&FE::q())) {
-        Ok(())
-    } else {
-        Err(InvalidSig)
-    }
+pub fn verify_with_policy(
+    sig: &SignatureRecid,
+    y: &GE,
+    message: &BigInt,
+    malleability: Malleability,
+) -> Result<(), Error> {
+    ecdsa::verify(
+        &sig.r.to_big_int(),
+        &sig.s.to_big_int(),
+        y,
+        message,
+        malleability,
+    )
 }
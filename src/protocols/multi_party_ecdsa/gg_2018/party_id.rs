@@ -0,0 +1,115 @@
+/*
+    Multi-party ECDSA
+
+    Copyright 2018 by Kzen Networks
+
+    This file is part of Multi-party ECDSA library
+    (https://github.com/KZen-networks/multi-party-ecdsa)
+
+    Multi-party ECDSA is free software: you can redistribute
+    it and/or modify it under the terms of the GNU General Public
+    License as published by the Free Software Foundation, either
+    version 3 of the License, or (at your option) any later version.
+
+    @license GPL-3.0+ <https://github.com/KZen-networks/multi-party-ecdsa/blob/master/LICENSE>
+*/
+
+/// Stable party identifiers. A party keeps the id keygen (or add-party) gave it for the life of
+/// the key: its share is the key's polynomial evaluated at the id, so refreshes keep it and a
+/// party that leaves leaves a gap rather than renumbering the others. Ids are 1-based like
+/// signup numbers, everything indexed by party (the shares of a VerifiableSS, the paillier keys
+/// and identity rosters of a key file) is indexed by PartyId::index.
+use std::convert::TryFrom;
+use std::fmt;
+
+use curv::cryptographic_primitives::secret_sharing::feldman_vss::VerifiableSS;
+use curv::elliptic::curves::traits::*;
+use curv::{BigInt, FE, GE};
+use serde::{Deserialize, Serialize};
+
+use crate::Error::{self, DuplicatePartyId, InvalidPartyId};
+
+// serialized as the bare u16, deserializing goes through new so 0 is refused
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "u16", into = "u16")]
+pub struct PartyId(u16);
+
+impl PartyId {
+    /// Fails for 0, the point of the secret itself.
+    pub fn new(id: u16) -> Result<Self, Error> {
+        if id == 0 {
+            return Err(InvalidPartyId { party: id });
+        }
+        Ok(PartyId(id))
+    }
+
+    /// The party at index, fails for indices past the last u16 id.
+    pub fn from_index(index: usize) -> Result<Self, Error> {
+        if index >= u16::max_value() as usize {
+            return Err(InvalidPartyId {
+                party: u16::max_value(),
+            });
+        }
+        Ok(PartyId(index as u16 + 1))
+    }
+
+    /// The id of a party joining the key of vss_scheme. Ids of parties that left are not
+    /// reused, whoever kept their shares could otherwise combine them with the new party's.
+    /// Fails once the ids are used up.
+    pub fn next(vss_scheme: &VerifiableSS) -> Result<Self, Error> {
+        Self::from_index(vss_scheme.parameters.share_count)
+    }
+
+    pub fn id(self) -> u16 {
+        self.0
+    }
+
+    pub fn index(self) -> usize {
+        self.0 as usize - 1
+    }
+
+    /// The evaluation point of the party's share.
+    pub fn point(self) -> FE {
+        ECScalar::from(&BigInt::from(u32::from(self.0)))
+    }
+
+    /// g^x_i of the party, from the commitments of the key.
+    pub fn public_share(self, vss_scheme: &VerifiableSS) -> GE {
+        vss_scheme.get_point_commitment(self.0 as usize)
+    }
+}
+
+impl TryFrom<u16> for PartyId {
+    type Error = Error;
+
+    fn try_from(id: u16) -> Result<Self, Error> {
+        Self::new(id)
+    }
+}
+
+impl From<PartyId> for u16 {
+    fn from(id: PartyId) -> u16 {
+        id.0
+    }
+}
+
+impl fmt::Display for PartyId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Checks that ids are distinct parties of the key of vss_scheme and returns their indices, in
+/// the order of ids, as SignKeys and the VerifiableSS lookups take them.
+pub fn indices(ids: &[PartyId], vss_scheme: &VerifiableSS) -> Result<Vec<usize>, Error> {
+    let share_count = vss_scheme.parameters.share_count;
+    for (k, id) in ids.iter().enumerate() {
+        if id.0 == 0 || id.0 as usize > share_count {
+            return Err(InvalidPartyId { party: id.0 });
+        }
+        if ids[..k].contains(id) {
+            return Err(DuplicatePartyId { party: id.0 });
+        }
+    }
+    Ok(ids.iter().map(|id| id.index()).collect())
+}
@@ -0,0 +1,144 @@
+/*
+    Multi-party ECDSA
+
+    Copyright 2018 by Kzen Networks
+
+    This file is part of Multi-party ECDSA library
+    (https://github.com/KZen-networks/multi-party-ecdsa)
+
+    Multi-party ECDSA is free software: you can redistribute
+    it and/or modify it under the terms of the GNU General Public
+    License as published by the Free Software Foundation, either
+    version 3 of the License, or (at your option) any later version.
+
+    @license GPL-3.0+ <https://github.com/KZen-networks/multi-party-ecdsa/blob/master/LICENSE>
+*/
+
+/// Share refresh and add-party for gg18 keys.
+/// Refresh: every party deals a degree t sharing of zero to the others and adds the subshares
+/// it receives to x_i, the key stays the same and the commitments of the key move with the
+/// shares. Add-party: t+1 helpers each hand the new party its share weighted by its lagrange
+/// coefficient at the new party's point, the new party adds them up.
+use curv::cryptographic_primitives::secret_sharing::feldman_vss::{
+    ShamirSecretSharing, VerifiableSS,
+};
+use curv::elliptic::curves::traits::*;
+use curv::{BigInt, FE, GE};
+use rand::rngs::OsRng;
+use rand::{CryptoRng, RngCore};
+
+use super::party_id::{self, PartyId};
+use crate::utilities::sampling;
+use crate::Error::{self, InvalidPartyId, InvalidSS, InvalidShare};
+
+/// The sharing of zero one party deals in a refresh: the subshares for ids, in the order of
+/// ids, and the commitments to all coefficients but the constant term.
+pub fn deal_refresh(vss_scheme: &VerifiableSS, ids: &[PartyId]) -> (Vec<FE>, Vec<GE>) {
+    deal_refresh_with_rng(vss_scheme, ids, &mut OsRng)
+}
+
+pub fn deal_refresh_with_rng<R: RngCore + CryptoRng>(
+    vss_scheme: &VerifiableSS,
+    ids: &[PartyId],
+    rng: &mut R,
+) -> (Vec<FE>, Vec<GE>) {
+    let g: GE = ECPoint::generator();
+    let coefficients = (0..vss_scheme.parameters.threshold)
+        .map(|_| sampling::sample_scalar(rng))
+        .collect::<Vec<FE>>();
+    let commitments = coefficients.iter().map(|c| g * c).collect::<Vec<GE>>();
+    let subshares = ids
+        .iter()
+        .map(|id| {
+            let point = id.point();
+            coefficients
+                .iter()
+                .rev()
+                .fold(FE::zero(), |acc, c| (acc + c) * point)
+        })
+        .collect::<Vec<FE>>();
+    (subshares, commitments)
+}
+
+/// Adds the (subshare, commitments) every party dealt to id to x_i and the commitments to
+/// those of the key. Fails with the positions in received of all subshares that do not match
+/// their commitments.
+pub fn apply_refresh(
+    id: PartyId,
+    x_i: &FE,
+    vss_scheme: &VerifiableSS,
+    received: &[(FE, Vec<GE>)],
+) -> Result<(FE, VerifiableSS), Error> {
+    let g: GE = ECPoint::generator();
+    let mut x_i = *x_i;
+    let mut vss_scheme = vss_scheme.clone();
+    let parties = (0..received.len())
+        .filter(|&k| {
+            let (subshare, commitments) = &received[k];
+            commitments.len() != vss_scheme.parameters.threshold
+                || !verify_subshare(id, subshare, commitments)
+        })
+        .collect::<Vec<usize>>();
+    if !parties.is_empty() {
+        return Err(InvalidShare { parties });
+    }
+    for (subshare, commitments) in received {
+        x_i = x_i + subshare;
+        for (c, c_j) in vss_scheme.commitments[1..].iter_mut().zip(commitments) {
+            *c = *c + c_j;
+        }
+    }
+    if id.public_share(&vss_scheme) != g * x_i {
+        return Err(InvalidSS);
+    }
+    Ok((x_i, vss_scheme))
+}
+
+/// The part of the new party's share that helper id hands it. helpers are the t+1 distinct
+/// parties of the key taking part, id among them, the new party is PartyId::next.
+pub fn add_party_share_part(
+    id: PartyId,
+    x_i: &FE,
+    helpers: &[PartyId],
+    vss_scheme: &VerifiableSS,
+) -> Result<FE, Error> {
+    party_id::indices(helpers, vss_scheme)?;
+    if !helpers.contains(&id) {
+        return Err(InvalidPartyId { party: id.id() });
+    }
+    if helpers.len() != vss_scheme.parameters.threshold + 1 {
+        return Err(InvalidSS);
+    }
+    let x = PartyId::next(vss_scheme)?.point();
+    let x_id = id.point();
+    let lagrange = helpers.iter().filter(|&&j| j != id).fold(
+        ECScalar::from(&BigInt::from(1)),
+        |acc: FE, j| {
+            let x_j = j.point();
+            acc * x.sub(&x_j.get_element()) * x_id.sub(&x_j.get_element()).invert()
+        },
+    );
+    Ok(lagrange * *x_i)
+}
+
+/// The commitments of the key once the new party joined.
+pub fn add_party_vss_scheme(vss_scheme: &VerifiableSS) -> VerifiableSS {
+    VerifiableSS {
+        parameters: ShamirSecretSharing {
+            threshold: vss_scheme.parameters.threshold,
+            share_count: vss_scheme.parameters.share_count + 1,
+        },
+        commitments: vss_scheme.commitments.clone(),
+    }
+}
+
+fn verify_subshare(id: PartyId, subshare: &FE, commitments: &[GE]) -> bool {
+    let g: GE = ECPoint::generator();
+    let point = id.point();
+    let mut reversed = commitments.iter().rev();
+    let head = match reversed.next() {
+        Some(head) => *head * point,
+        None => return false,
+    };
+    reversed.fold(head, |acc, c| (acc + c) * point) == g * subshare
+}
@@ -18,9 +18,17 @@
 
 use crate::protocols::multi_party_ecdsa::gg_2018::party_i::{
     verify, KeyGenBroadcastMessage1, KeyGenDecommitMessage1, Keys, LocalSignature, Parameters,
-    PartyPrivate, Phase5ADecom1, Phase5Com1, SharedKeys, SignKeys,
+    PartyPrivate, Phase5ADecom1, Phase5Com1, SharedKeys, SignContext, SignKeys, SignatureRecid,
 };
+use crate::protocols::multi_party_ecdsa::gg_2018::party_id::{self, PartyId};
+use crate::protocols::multi_party_ecdsa::gg_2018::refresh;
 use crate::utilities::mta::{MessageA, MessageB};
+use crate::utilities::sampling;
+use crate::utilities::test_vectors::{self, Transcript};
+use crate::Error::{
+    DuplicatePartyId, InvalidCom, InvalidPartyId, InvalidShare, MessageMismatch,
+    PublicShareMismatch,
+};
 
 use curv::arithmetic::traits::Converter;
 use curv::cryptographic_primitives::hashing::hash_sha256::HSha256;
@@ -30,6 +38,11 @@ use curv::cryptographic_primitives::secret_sharing::feldman_vss::VerifiableSS;
 use curv::elliptic::curves::traits::*;
 use curv::{FE, GE};
 use paillier::*;
+use proptest::prelude::{any, Just, ProptestConfig, Strategy};
+use proptest::proptest;
+use rand::rngs::OsRng;
+use rand::{CryptoRng, RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
 
 #[test]
 fn test_keygen_t1_n2() {
@@ -55,18 +68,172 @@ fn test_sign_n8_t4_ttag6() {
     sign(4, 8, 6, vec![0, 1, 2, 4, 6, 7])
 }
 
+#[test]
+fn test_sign_reproducible_from_seed() {
+    let sig_a = sign_with_rng(
+        1,
+        3,
+        2,
+        vec![0, 2],
+        &mut ChaCha20Rng::seed_from_u64(7),
+        &mut Transcript::default(),
+    );
+    let sig_b = sign_with_rng(
+        1,
+        3,
+        2,
+        vec![0, 2],
+        &mut ChaCha20Rng::seed_from_u64(7),
+        &mut Transcript::default(),
+    );
+    assert_eq!(sig_a.r, sig_b.r);
+    assert_eq!(sig_a.s, sig_b.s);
+    assert_eq!(sig_a.recid, sig_b.recid);
+}
+
+#[test]
+fn test_vector_keygen() {
+    test_vectors::check("gg18_keygen_t1_n3", 1801, |rng, transcript| {
+        keygen_t_n_parties_with_rng(1, 3, rng, transcript);
+    });
+}
+
+#[test]
+fn test_vector_sign() {
+    test_vectors::check("gg18_sign_t1_n3", 1802, |rng, transcript| {
+        sign_with_rng(1, 3, 2, vec![0, 2], rng, transcript);
+    });
+}
+
+// largest share count the property tests pick, key gen cost grows quadratically in n
+const MAX_PARTIES: u16 = 10;
+
+// random t < n <= MAX_PARTIES and t < t' <= n distinct signers in random order
+fn threshold_and_signers() -> impl Strategy<Value = (u16, u16, Vec<usize>)> {
+    (2..=MAX_PARTIES)
+        .prop_flat_map(|n| (1..n, Just(n)))
+        .prop_flat_map(|(t, n)| {
+            let parties = Just((0..n as usize).collect::<Vec<usize>>()).prop_shuffle();
+            (Just(t), Just(n), parties, t as usize + 1..=n as usize)
+        })
+        .prop_map(|(t, n, mut s, ttag)| {
+            s.truncate(ttag);
+            (t, n, s)
+        })
+}
+
+proptest! {
+    #[test]
+    fn prop_lagrange_interpolation((t, n, s) in threshold_and_signers(), seed in any::<u64>()) {
+        let rng = &mut ChaCha20Rng::seed_from_u64(seed);
+        let secret = sampling::sample_scalar(rng);
+        let (vss_scheme, shares) = sampling::share(rng, t as usize, n as usize, &secret);
+        assert_eq!(interpolate(&vss_scheme, &shares, &s), secret);
+    }
+}
+
+proptest! {
+    // every case runs a full key gen and three signings
+    #![proptest_config(ProptestConfig::with_cases(4))]
+    #[test]
+    fn prop_sign_refresh_add_party((t, n, s) in threshold_and_signers(), seed in any::<u64>()) {
+        let rng = &mut ChaCha20Rng::seed_from_u64(seed);
+        let transcript = &mut Transcript::default();
+        let g: GE = ECPoint::generator();
+        let (mut party_keys_vec, shared_keys_vec, _pk_vec, y, vss_scheme) =
+            keygen_t_n_parties_with_rng(t, n, rng, transcript);
+        let x_vec = shared_keys_vec.iter().map(|k| k.x_i).collect::<Vec<FE>>();
+        let secret = interpolate(&vss_scheme, &x_vec, &s);
+        assert_eq!(g * secret, y);
+        sign_with_keys(&party_keys_vec, &shared_keys_vec, &vss_scheme, &y, &s, rng, transcript);
+
+        // refresh: every party deals a sharing of zero, new shares of the same secret
+        let ids = (0..n as usize)
+            .map(|i| PartyId::from_index(i).unwrap())
+            .collect::<Vec<PartyId>>();
+        let dealings = ids
+            .iter()
+            .map(|_| refresh::deal_refresh_with_rng(&vss_scheme, &ids, rng))
+            .collect::<Vec<(Vec<FE>, Vec<GE>)>>();
+        let refreshed = ids
+            .iter()
+            .enumerate()
+            .map(|(i, id)| {
+                let received = dealings
+                    .iter()
+                    .map(|(subshares, commitments)| (subshares[i], commitments.clone()))
+                    .collect::<Vec<(FE, Vec<GE>)>>();
+                refresh::apply_refresh(*id, &x_vec[i], &vss_scheme, &received)
+                    .expect("refresh failed")
+            })
+            .collect::<Vec<(FE, VerifiableSS)>>();
+        let vss_scheme = refreshed[0].1.clone();
+        assert!(refreshed.iter().all(|(_, vss)| *vss == vss_scheme));
+        let mut x_vec = refreshed.iter().map(|(x_i, _)| *x_i).collect::<Vec<FE>>();
+        assert_eq!(interpolate(&vss_scheme, &x_vec, &s), secret);
+        let shared_keys_vec = x_vec
+            .iter()
+            .map(|x_i| SharedKeys { y, x_i: *x_i })
+            .collect::<Vec<SharedKeys>>();
+        sign_with_keys(&party_keys_vec, &shared_keys_vec, &vss_scheme, &y, &s, rng, transcript);
+
+        // add-party: t+1 of the signers hand party n a share, which then signs with t others
+        let new_party = n as usize;
+        let helpers = s[..=t as usize]
+            .iter()
+            .map(|&i| PartyId::from_index(i).unwrap())
+            .collect::<Vec<PartyId>>();
+        let new_share = helpers
+            .iter()
+            .map(|id| {
+                refresh::add_party_share_part(*id, &x_vec[id.index()], &helpers, &vss_scheme)
+                    .expect("add-party failed")
+            })
+            .fold(FE::zero(), |acc, part| acc + part);
+        x_vec.push(new_share);
+        party_keys_vec.push(Keys::create_with_rng(new_party, rng));
+        let vss_scheme = refresh::add_party_vss_scheme(&vss_scheme);
+        let new_id = PartyId::from_index(new_party).unwrap();
+        assert_eq!(new_id.public_share(&vss_scheme), g * new_share);
+        let mut s_new = s[..t as usize].to_vec();
+        s_new.push(new_party);
+        assert_eq!(interpolate(&vss_scheme, &x_vec, &s_new), secret);
+        let shared_keys_vec = x_vec
+            .iter()
+            .map(|x_i| SharedKeys { y, x_i: *x_i })
+            .collect::<Vec<SharedKeys>>();
+        sign_with_keys(&party_keys_vec, &shared_keys_vec, &vss_scheme, &y, &s_new, rng, transcript);
+    }
+}
+
 fn keygen_t_n_parties(t: u16, n: u16) -> (Vec<Keys>, Vec<SharedKeys>, Vec<GE>, GE, VerifiableSS) {
+    keygen_t_n_parties_with_rng(t, n, &mut OsRng, &mut Transcript::default())
+}
+
+fn keygen_t_n_parties_with_rng<R: RngCore + CryptoRng>(
+    t: u16,
+    n: u16,
+    rng: &mut R,
+    transcript: &mut Transcript,
+) -> (Vec<Keys>, Vec<SharedKeys>, Vec<GE>, GE, VerifiableSS) {
+    transcript.record("threshold", &t);
+    transcript.record("share_count", &n);
     let parames = Parameters {
         threshold: t,
         share_count: n,
     };
     let (t, n) = (t as usize, n as usize);
-    let party_keys_vec = (0..n).map(Keys::create).collect::<Vec<Keys>>();
+    let party_keys_vec = (0..n)
+        .map(|i| Keys::create_with_rng(i, rng))
+        .collect::<Vec<Keys>>();
 
     let (bc1_vec, decom_vec): (Vec<_>, Vec<_>) = party_keys_vec
         .iter()
-        .map(|k| k.phase1_broadcast_phase3_proof_of_correct_key())
+        .map(|k| k.phase1_broadcast_phase3_proof_of_correct_key_with_rng(rng))
         .unzip();
+    transcript.record("party_keys", &party_keys_vec);
+    transcript.record("keygen_bc1", &bc1_vec);
+    transcript.record("keygen_decom", &decom_vec);
 
     let y_vec = (0..n).map(|i| decom_vec[i].y_i).collect::<Vec<GE>>();
     let mut y_vec_iter = y_vec.iter();
@@ -81,8 +248,8 @@ fn keygen_t_n_parties(t: u16, n: u16) -> (Vec<Keys>, Vec<SharedKeys>, Vec<GE>, G
     let vss_result: Vec<_> = party_keys_vec
         .iter()
         .map(|k| {
-            k.phase1_verify_com_phase3_verify_correct_key_phase2_distribute(
-                &parames, &decom_vec, &bc1_vec,
+            k.phase1_verify_com_phase3_verify_correct_key_phase2_distribute_with_rng(
+                &parames, &decom_vec, &bc1_vec, rng,
             )
             .expect("invalid key")
         })
@@ -95,6 +262,8 @@ fn keygen_t_n_parties(t: u16, n: u16) -> (Vec<Keys>, Vec<SharedKeys>, Vec<GE>, G
     }
 
     let vss_scheme_for_test = vss_scheme_vec.clone();
+    transcript.record("vss_schemes", &vss_scheme_vec);
+    transcript.record("secret_shares", &secret_shares_vec);
 
     let party_shares = (0..n)
         .map(|i| {
@@ -111,12 +280,13 @@ fn keygen_t_n_parties(t: u16, n: u16) -> (Vec<Keys>, Vec<SharedKeys>, Vec<GE>, G
     let mut dlog_proof_vec = Vec::new();
     for (i, key) in party_keys_vec.iter().enumerate() {
         let (shared_keys, dlog_proof) = key
-            .phase2_verify_vss_construct_keypair_phase3_pok_dlog(
+            .phase2_verify_vss_construct_keypair_phase3_pok_dlog_with_rng(
                 &parames,
                 &y_vec,
                 &party_shares[i],
                 &vss_scheme_vec,
                 &index_vec[i] + 1,
+                rng,
             )
             .expect("invalid vss");
         shared_keys_vec.push(shared_keys);
@@ -124,6 +294,9 @@ fn keygen_t_n_parties(t: u16, n: u16) -> (Vec<Keys>, Vec<SharedKeys>, Vec<GE>, G
     }
 
     let pk_vec = (0..n).map(|i| dlog_proof_vec[i].pk).collect::<Vec<GE>>();
+    transcript.record("shared_keys", &shared_keys_vec);
+    transcript.record("dlog_proofs", &dlog_proof_vec);
+    transcript.record("y", &y_sum);
 
     //both parties run:
     Keys::verify_dlog_proofs(&parames, &dlog_proof_vec, &y_vec).expect("bad dlog proof");
@@ -141,42 +314,94 @@ fn keygen_t_n_parties(t: u16, n: u16) -> (Vec<Keys>, Vec<SharedKeys>, Vec<GE>, G
         shared_keys_vec,
         pk_vec,
         y_sum,
-        vss_scheme_for_test[0].clone(),
+        key_vss_scheme(&vss_scheme_for_test),
     )
 }
 
+// the key's commitments are the sums of those of every party's sharing, as the clients save them
+pub(crate) fn key_vss_scheme(vss_scheme_vec: &[VerifiableSS]) -> VerifiableSS {
+    let mut vss_scheme = vss_scheme_vec[0].clone();
+    for other in &vss_scheme_vec[1..] {
+        for (c, c_j) in vss_scheme.commitments.iter_mut().zip(&other.commitments) {
+            *c = *c + c_j;
+        }
+    }
+    vss_scheme
+}
+
 fn sign(t: u16, n: u16, ttag: u16, s: Vec<usize>) {
+    sign_with_rng(t, n, ttag, s, &mut OsRng, &mut Transcript::default());
+}
+
+fn sign_with_rng<R: RngCore + CryptoRng>(
+    t: u16,
+    n: u16,
+    ttag: u16,
+    s: Vec<usize>,
+    rng: &mut R,
+    transcript: &mut Transcript,
+) -> SignatureRecid {
     // full key gen emulation
-    let (party_keys_vec, shared_keys_vec, _pk_vec, y, vss_scheme) = keygen_t_n_parties(t, n);
+    let (party_keys_vec, shared_keys_vec, _pk_vec, y, vss_scheme) =
+        keygen_t_n_parties_with_rng(t, n, rng, transcript);
 
-    let private_vec = (0..shared_keys_vec.len())
-        .map(|i| PartyPrivate::set_private(party_keys_vec[i].clone(), shared_keys_vec[i].clone()))
-        .collect::<Vec<PartyPrivate>>();
     // make sure that we have t<t'<n and the group s contains id's for t' parties
     // TODO: make sure s has unique id's and they are all in range 0..n
-    // TODO: make sure this code can run when id's are not in ascending order
     assert!(ttag > t);
-    let ttag = ttag as usize;
-    assert_eq!(s.len(), ttag);
+    assert_eq!(s.len(), ttag as usize);
+    sign_with_keys(
+        &party_keys_vec,
+        &shared_keys_vec,
+        &vss_scheme,
+        &y,
+        &s,
+        rng,
+        transcript,
+    )
+}
+
+// runs the signing rounds for the signers in s (in the given order) on top of
+// an existing key gen, so the same keys can be used across refresh and add-party
+fn sign_with_keys<R: RngCore + CryptoRng>(
+    party_keys_vec: &[Keys],
+    shared_keys_vec: &[SharedKeys],
+    vss_scheme: &VerifiableSS,
+    y: &GE,
+    s: &[usize],
+    rng: &mut R,
+    transcript: &mut Transcript,
+) -> SignatureRecid {
+    transcript.record("signers", &s);
+
+    let private_vec = (0..shared_keys_vec.len())
+        .map(|i| PartyPrivate::set_private(party_keys_vec[i].clone(), shared_keys_vec[i].clone()))
+        .collect::<Vec<PartyPrivate>>();
+    let ttag = s.len();
 
     // each party creates a signing key. This happens in parallel IRL. In this test we
     // create a vector of signing keys, one for each party.
     // throughout i will index parties
     let sign_keys_vec = (0..ttag)
-        .map(|i| SignKeys::create(&private_vec[s[i]], &vss_scheme, s[i], &s))
+        .map(|i| SignKeys::create_with_rng(&private_vec[s[i]], vss_scheme, s[i], s, rng))
         .collect::<Vec<SignKeys>>();
 
     // each party computes [Ci,Di] = com(g^gamma_i) and broadcast the commitments
-    let (bc1_vec, decommit_vec1): (Vec<_>, Vec<_>) =
-        sign_keys_vec.iter().map(|k| k.phase1_broadcast()).unzip();
+    let (bc1_vec, decommit_vec1): (Vec<_>, Vec<_>) = sign_keys_vec
+        .iter()
+        .map(|k| k.phase1_broadcast_with_rng(rng))
+        .unzip();
 
     // each party i sends encryption of k_i under her Paillier key
     // m_a_vec = [ma_0;ma_1;,...]
     let m_a_vec: Vec<_> = sign_keys_vec
         .iter()
         .enumerate()
-        .map(|(i, k)| MessageA::a(&k.k_i, &party_keys_vec[s[i]].ek).0)
+        .map(|(i, k)| MessageA::a_with_rng(&k.k_i, &party_keys_vec[s[i]].ek, rng).0)
         .collect();
+    transcript.record("sign_keys", &sign_keys_vec);
+    transcript.record("sign_bc1", &bc1_vec);
+    transcript.record("sign_decom", &decommit_vec1);
+    transcript.record("m_a", &m_a_vec);
 
     // each party i sends responses to m_a_vec she received (one response with input gamma_i and one with w_i)
     // m_b_gamma_vec_all is a matrix where column i is a vector of message_b's that party i answers to all ma_{j!=i} using paillier key of party j to answer to ma_j
@@ -196,13 +421,18 @@ fn sign(t: u16, n: u16, ttag: u16, s: Vec<usize>) {
         for j in 0..ttag - 1 {
             let ind = if j < i { j } else { j + 1 };
 
-            let (m_b_gamma, beta_gamma, _) = MessageB::b(
+            let (m_b_gamma, beta_gamma, _) = MessageB::b_with_rng(
                 &key.gamma_i,
                 &party_keys_vec[s[ind]].ek,
                 m_a_vec[ind].clone(),
+                rng,
+            );
+            let (m_b_w, beta_wi, _) = MessageB::b_with_rng(
+                &key.w_i,
+                &party_keys_vec[s[ind]].ek,
+                m_a_vec[ind].clone(),
+                rng,
             );
-            let (m_b_w, beta_wi, _) =
-                MessageB::b(&key.w_i, &party_keys_vec[s[ind]].ek, m_a_vec[ind].clone());
 
             m_b_gamma_vec.push(m_b_gamma);
             beta_vec.push(beta_gamma);
@@ -220,6 +450,8 @@ fn sign(t: u16, n: u16, ttag: u16, s: Vec<usize>) {
     //  for index i=1 j=0 we need party at index s[0] to answer to mb that party s[1]. etc.
     // IRL each party i should get only the mb messages that other parties sent in response to the party i ma's.
     // TODO: simulate as IRL
+    transcript.record("m_b_gamma", &m_b_gamma_vec_all);
+    transcript.record("m_b_w", &m_b_w_vec_all);
     let mut alpha_vec_all = Vec::new();
     let mut miu_vec_all = Vec::new();
 
@@ -237,16 +469,19 @@ fn sign(t: u16, n: u16, ttag: u16, s: Vec<usize>) {
             let alpha_ij_gamma = m_b
                 .verify_proofs_get_alpha(&party_keys_vec[s[ind]].dk, &sign_keys_vec[ind].k_i)
                 .expect("wrong dlog or m_b");
-            let m_b = m_b_w_vec_i[j].clone();
-            let alpha_ij_wi = m_b
-                .verify_proofs_get_alpha(&party_keys_vec[s[ind]].dk, &sign_keys_vec[ind].k_i)
-                .expect("wrong dlog or m_b");
-
             // since we actually run two MtAwc each party needs to make sure that the values B are the same as the public values
             // here for b=w_i the parties already know W_i = g^w_i  for each party so this check is done here. for b = gamma_i the check will be later when g^gamma_i will become public
-            // currently we take the W_i from the other parties signing keys
-            // TODO: use pk_vec (first change from x_i to w_i) for this check.
-            assert_eq!(m_b.b_proof.pk, sign_keys_vec[i].g_w_i);
+            // the W_i of the other parties are derived from the vss commitments, not taken from their signing keys
+            let m_b = m_b_w_vec_i[j].clone();
+            let alpha_ij_wi = m_b
+                .verify_proofs_get_alpha_wc(
+                    &party_keys_vec[s[ind]].dk,
+                    &sign_keys_vec[ind].k_i,
+                    vss_scheme,
+                    s[i],
+                    s,
+                )
+                .expect("wrong dlog, m_b or public share");
 
             alpha_vec.push(alpha_ij_gamma);
             miu_vec.push(alpha_ij_wi);
@@ -265,6 +500,7 @@ fn sign(t: u16, n: u16, ttag: u16, s: Vec<usize>) {
         sigma_vec.push(sigma);
     }
 
+    transcript.record("delta", &delta_vec);
     // all parties broadcast delta_i and compute delta_i ^(-1)
     let delta_inv = SignKeys::phase3_reconstruct_delta(&delta_vec);
 
@@ -291,16 +527,19 @@ fn sign(t: u16, n: u16, ttag: u16, s: Vec<usize>) {
 
     let message: [u8; 4] = [79, 77, 69, 82];
     let message_bn = HSha256::create_hash(&[&BigInt::from(&message[..])]);
+    transcript.record("R", &R_vec[0]);
+    transcript.record("message", &message_bn);
     let mut local_sig_vec = Vec::new();
 
     // each party computes s_i but don't send it yet. we start with phase5
     for i in 0..ttag {
-        let local_sig = LocalSignature::phase5_local_sig(
+        let local_sig = LocalSignature::phase5_local_sig_with_rng(
             &sign_keys_vec[i].k_i,
             &message_bn,
             &R_vec[i],
             &sigma_vec[i],
-            &y,
+            y,
+            rng,
         );
         local_sig_vec.push(local_sig);
     }
@@ -312,13 +551,18 @@ fn sign(t: u16, n: u16, ttag: u16, s: Vec<usize>) {
     // we notice that the proof for V= R^sg^l, B = A^l is a general form of homomorphic elgamal.
     for sig in &local_sig_vec {
         let (phase5_com, phase_5a_decom, helgamal_proof, dlog_proof_rho) =
-            sig.phase5a_broadcast_5b_zkproof();
+            sig.phase5a_broadcast_5b_zkproof_with_rng(rng);
         phase5_com_vec.push(phase5_com);
         phase_5a_decom_vec.push(phase_5a_decom);
         helgamal_proof_vec.push(helgamal_proof);
         dlog_proof_rho_vec.push(dlog_proof_rho);
     }
 
+    transcript.record("local_sigs", &local_sig_vec);
+    transcript.record("phase5_com", &phase5_com_vec);
+    transcript.record("phase5a_decom", &phase_5a_decom_vec);
+    transcript.record("elgamal_proofs", &helgamal_proof_vec);
+    transcript.record("dlog_proofs_rho", &dlog_proof_rho_vec);
     let mut phase5_com2_vec = Vec::new();
     let mut phase_5d_decom2_vec = Vec::new();
     for i in 0..ttag {
@@ -331,13 +575,14 @@ fn sign(t: u16, n: u16, ttag: u16, s: Vec<usize>) {
         let _elgamal_i = phase_5b_elgamal_vec_clone.remove(i);
         //        for j in 0..s_minus_i.len() {
         let (phase5_com2, phase_5d_decom2) = local_sig_vec[i]
-            .phase5c(
+            .phase5c_with_rng(
                 &phase_5a_decom_vec_clone,
                 &phase_5a_com_vec_clone,
                 &phase_5b_elgamal_vec_clone,
                 &dlog_proof_rho_vec,
                 &phase_5a_decom_vec[i].V_i,
                 &R_vec[0],
+                rng,
             )
             .expect("error phase5");
         phase5_com2_vec.push(phase5_com2);
@@ -354,15 +599,31 @@ fn sign(t: u16, n: u16, ttag: u16, s: Vec<usize>) {
         s_vec.push(s_i);
     }
 
+    transcript.record("phase5_com2", &phase5_com2_vec);
+    transcript.record("phase5d_decom2", &phase_5d_decom2_vec);
+    transcript.record("s", &s_vec);
     // here we compute the signature only of party i=0 to demonstrate correctness.
     s_vec.remove(0);
     let sig = local_sig_vec[0]
         .output_signature(&s_vec)
         .expect("verification failed");
 
-    assert_eq!(local_sig_vec[0].y, y);
+    assert_eq!(local_sig_vec[0].y, *y);
     verify(&sig, &local_sig_vec[0].y, &local_sig_vec[0].m).unwrap();
-    check_sig(&sig.r, &sig.s, &local_sig_vec[0].m, &y);
+    check_sig(&sig.r, &sig.s, &local_sig_vec[0].m, y);
+    transcript.record("signature", &sig);
+    sig
+}
+
+// sum of lambda_i * x_i over s, the shared secret when s holds more than t shares
+fn interpolate(vss_scheme: &VerifiableSS, x_vec: &[FE], s: &[usize]) -> FE {
+    s.iter()
+        .map(|&i| vss_scheme.map_share_to_new_params(i, s) * x_vec[i])
+        .fold(FE::zero(), |acc, x| acc + x)
+}
+
+fn index_to_point(index: usize) -> FE {
+    ECScalar::from(&BigInt::from(index as u32 + 1))
 }
 
 fn check_sig(r: &FE, s: &FE, msg: &BigInt, pk: &GE) {
@@ -404,6 +665,128 @@ fn check_sig(r: &FE, s: &FE, msg: &BigInt, pk: &GE) {
     assert!(is_correct);
 }
 
+#[test]
+fn test_sign_context_mismatch() {
+    let (_, _, _, y, _) = keygen_t_n_parties(1, 3);
+    let s = vec![0, 2];
+    let context = SignContext::new(&BigInt::from(1234), &s, &y);
+    let agreed = vec![context.commitment(); 2];
+    assert_eq!(context.verify_commitments(&agreed), Ok(()));
+
+    let other_message = SignContext::new(&BigInt::from(4321), &s, &y).commitment();
+    let other_signers = SignContext::new(&BigInt::from(1234), &[0, 1], &y).commitment();
+    let other_key = SignContext::new(&BigInt::from(1234), &s, &(y + y)).commitment();
+    for com in vec![other_message, other_signers, other_key] {
+        assert_eq!(
+            context.verify_commitments(&[context.commitment(), com]),
+            Err(MessageMismatch { party: 2 })
+        );
+    }
+    assert_eq!(
+        context.verify_commitments(&[context.commitment()]),
+        Err(InvalidCom)
+    );
+}
+
+#[test]
+fn test_refresh_blames_bad_subshare() {
+    let rng = &mut ChaCha20Rng::seed_from_u64(34);
+    let secret = sampling::sample_scalar(rng);
+    let (vss_scheme, shares) = sampling::share(rng, 1, 3, &secret);
+    let ids = (0..3)
+        .map(|i| PartyId::from_index(i).unwrap())
+        .collect::<Vec<PartyId>>();
+    let mut received = ids
+        .iter()
+        .map(|_| {
+            let (subshares, commitments) = refresh::deal_refresh_with_rng(&vss_scheme, &ids, rng);
+            (subshares[0], commitments)
+        })
+        .collect::<Vec<(FE, Vec<GE>)>>();
+    assert!(refresh::apply_refresh(ids[0], &shares[0], &vss_scheme, &received).is_ok());
+    received[2].0 = received[2].0 + received[1].0;
+    assert_eq!(
+        refresh::apply_refresh(ids[0], &shares[0], &vss_scheme, &received).err(),
+        Some(InvalidShare { parties: vec![2] })
+    );
+    received[1].1.pop();
+    assert_eq!(
+        refresh::apply_refresh(ids[0], &shares[0], &vss_scheme, &received).err(),
+        Some(InvalidShare {
+            parties: vec![1, 2]
+        })
+    );
+}
+
+#[test]
+fn test_party_ids() {
+    let (party_keys_vec, shared_keys_vec, _, _, vss_scheme) = keygen_t_n_parties(1, 4);
+    let g: GE = ECPoint::generator();
+    for (i, shared_keys) in shared_keys_vec.iter().enumerate() {
+        let id = PartyId::from_index(i).unwrap();
+        assert_eq!(id.index(), i);
+        assert_eq!(id.point(), index_to_point(i));
+        assert_eq!(id.public_share(&vss_scheme), g * shared_keys.x_i);
+    }
+    assert_eq!(PartyId::new(0), Err(InvalidPartyId { party: 0 }));
+    assert_eq!(
+        PartyId::from_index(65534).map(PartyId::id),
+        Ok(u16::max_value())
+    );
+    assert_eq!(
+        PartyId::from_index(65535),
+        Err(InvalidPartyId {
+            party: u16::max_value()
+        })
+    );
+    assert_eq!(PartyId::next(&vss_scheme), PartyId::new(5));
+    assert_eq!(
+        serde_json::to_string(&PartyId::new(3).unwrap()).unwrap(),
+        "3"
+    );
+    assert_eq!(
+        serde_json::from_str::<PartyId>("3").ok(),
+        PartyId::new(3).ok()
+    );
+    assert!(serde_json::from_str::<PartyId>("0").is_err());
+
+    // after party 2 left, parties 1, 3 and 4 keep their ids
+    let ids = |ids: &[u16]| {
+        ids.iter()
+            .map(|&id| PartyId::new(id).unwrap())
+            .collect::<Vec<PartyId>>()
+    };
+    assert_eq!(
+        party_id::indices(&ids(&[4, 1]), &vss_scheme),
+        Ok(vec![3, 0])
+    );
+    assert_eq!(
+        party_id::indices(&ids(&[1, 3, 1]), &vss_scheme),
+        Err(DuplicatePartyId { party: 1 })
+    );
+    assert_eq!(
+        party_id::indices(&ids(&[1, 5]), &vss_scheme),
+        Err(InvalidPartyId { party: 5 })
+    );
+
+    let private = PartyPrivate::set_private(party_keys_vec[2].clone(), shared_keys_vec[2].clone());
+    let id = PartyId::new(3).unwrap();
+    let sign_keys = SignKeys::create_for_party(&private, &vss_scheme, id, &ids(&[1, 3])).unwrap();
+    assert_eq!(
+        SignKeys::verify_public_share(&sign_keys.g_w_i, &vss_scheme, id.index(), &[0, 2]),
+        Ok(())
+    );
+    assert!(SignKeys::create_for_party(&private, &vss_scheme, id, &ids(&[1, 4])).is_err());
+
+    let mut stale_keys = shared_keys_vec[2].clone();
+    stale_keys.x_i = stale_keys.x_i + FE::new_random();
+    let stale = PartyPrivate::set_private(party_keys_vec[2].clone(), stale_keys);
+    assert_eq!(
+        SignKeys::create_for_party(&stale, &vss_scheme, id, &ids(&[1, 3])).err(),
+        Some(PublicShareMismatch { party: 2 })
+    );
+}
+
 #[test]
 fn test_serialize_deserialize() {
     use serde_json;
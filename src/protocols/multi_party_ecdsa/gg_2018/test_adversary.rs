@@ -0,0 +1,528 @@
+#![allow(non_snake_case)]
+
+/*
+    Multi-party ECDSA
+
+    Copyright 2018 by Kzen Networks
+
+    This file is part of Multi-party ECDSA library
+    (https://github.com/KZen-networks/multi-party-ecdsa)
+
+    Multi-party ECDSA is free software: you can redistribute
+    it and/or modify it under the terms of the GNU General Public
+    License as published by the Free Software Foundation, either
+    version 3 of the License, or (at your option) any later version.
+
+    @license GPL-3.0+ <https://github.com/KZen-networks/multi-party-ecdsa/blob/master/LICENSE>
+*/
+
+/// GG18 key generation and signing with one malicious party.
+/// Every party runs the protocol on its own view of the messages. The adversary follows the
+/// protocol on its own view but can change what it sends, per recipient, which also covers
+/// equivocation. Each scenario checks that the honest parties stop in the expected round with
+/// the expected error, without panicking and without producing a signature.
+use std::collections::HashMap;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use crate::protocols::multi_party_ecdsa::gg_2018::party_i::{
+    verify, KeyGenBroadcastMessage1, KeyGenDecommitMessage1, Keys, LocalSignature, Parameters,
+    PartyPrivate, Phase5ADecom1, Phase5Com1, SharedKeys, SignBroadcastPhase1, SignDecommitPhase1,
+    SignKeys, SignatureRecid,
+};
+use crate::protocols::multi_party_ecdsa::gg_2018::test::key_vss_scheme;
+use crate::utilities::mta::{MessageA, MessageB};
+use crate::Error::{self, InvalidCom, InvalidKey, InvalidShare, PublicShareMismatch};
+
+use curv::cryptographic_primitives::commitments::hash_commitment::HashCommitment;
+use curv::cryptographic_primitives::commitments::traits::Commitment;
+use curv::cryptographic_primitives::hashing::hash_sha256::HSha256;
+use curv::cryptographic_primitives::hashing::traits::Hash;
+use curv::cryptographic_primitives::proofs::sigma_correct_homomorphic_elgamal_enc::HomoELGamalProof;
+use curv::cryptographic_primitives::proofs::sigma_dlog::DLogProof;
+use curv::cryptographic_primitives::secret_sharing::feldman_vss::VerifiableSS;
+use curv::elliptic::curves::traits::*;
+use curv::{BigInt, FE, GE};
+use paillier::{Add, Encrypt, Paillier, RawCiphertext, RawPlaintext};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Deviation {
+    // opens the keygen commitment to a different y_i
+    WrongKeyGenDecommitment,
+    // sends a share that is not on its committed polynomial to one party
+    InconsistentVssShare,
+    // adds one to the plaintext of its MessageB for gamma_i
+    TamperedMessageB,
+    // commits and decommits to a g_gamma_i other than the one used in MtA
+    WrongGGammaI,
+    // signs with a share other than the one the vss commitments fix, e.g. from before a refresh
+    StaleShare,
+    // opens the phase 5A commitment to a different V_i
+    BadPhase5ADecom1,
+    // sends two different, individually valid, phase 5A messages to different parties
+    EquivocatingPhase5ADecom1,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Adversary {
+    // position of the party in the list of parties running the protocol
+    party: usize,
+    deviation: Deviation,
+}
+
+// the round in which the honest parties stopped and the error every one that failed reported
+#[derive(Debug)]
+struct Abort {
+    round: &'static str,
+    errors: Vec<(usize, Error)>,
+}
+
+struct KeyGenOutput {
+    keys: Vec<Keys>,
+    shared_keys: Vec<SharedKeys>,
+    y: GE,
+    vss_scheme: VerifiableSS,
+}
+
+fn deviates(adversary: &Option<Adversary>, deviation: Deviation) -> Option<usize> {
+    match adversary {
+        Some(a) if a.deviation == deviation => Some(a.party),
+        _ => None,
+    }
+}
+
+fn message() -> BigInt {
+    HSha256::create_hash(&[&BigInt::from(&[79u8, 77, 69, 82][..])])
+}
+
+fn random_point() -> GE {
+    let g: GE = ECPoint::generator();
+    let x: FE = ECScalar::new_random();
+    g * x
+}
+
+// msgs[j] is what party j sent, the adversary may replace its own message per recipient
+fn received<T: Clone, F>(
+    msgs: &[T],
+    recipient: usize,
+    adversary: &Option<Adversary>,
+    tamper: F,
+) -> Vec<T>
+where
+    F: Fn(&Adversary, &T) -> Option<T>,
+{
+    let mut view = msgs.to_vec();
+    if let Some(a) = adversary {
+        if a.party != recipient {
+            if let Some(msg) = tamper(a, &msgs[a.party]) {
+                view[a.party] = msg;
+            }
+        }
+    }
+    view
+}
+
+// runs one round for every party, the round aborts when an honest party fails.
+// The adversary acts honestly on its own view so its own steps are expected to succeed.
+fn round<T, F>(
+    name: &'static str,
+    parties: usize,
+    adversary: &Option<Adversary>,
+    step: F,
+) -> Result<Vec<T>, Abort>
+where
+    F: Fn(usize) -> Result<T, Error>,
+{
+    let results = (0..parties).map(|i| step(i)).collect::<Vec<_>>();
+    let errors = results
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| adversary.map_or(true, |a| a.party != *i))
+        .filter_map(|(i, res)| res.as_ref().err().map(|e| (i, e.clone())))
+        .collect::<Vec<(usize, Error)>>();
+    if !errors.is_empty() {
+        return Err(Abort {
+            round: name,
+            errors,
+        });
+    }
+    Ok(results
+        .into_iter()
+        .map(|res| res.unwrap_or_else(|e| panic!("adversary failed its own {}: {:?}", name, e)))
+        .collect())
+}
+
+fn keygen(t: u16, n: u16, adversary: &Option<Adversary>) -> Result<KeyGenOutput, Abort> {
+    let params = Parameters {
+        threshold: t,
+        share_count: n,
+    };
+    let n = n as usize;
+    let keys = (0..n).map(Keys::create).collect::<Vec<Keys>>();
+    let (bc1_vec, decom_vec): (Vec<KeyGenBroadcastMessage1>, Vec<KeyGenDecommitMessage1>) = keys
+        .iter()
+        .map(|k| k.phase1_broadcast_phase3_proof_of_correct_key())
+        .unzip();
+    let wrong_y_i = random_point();
+
+    let distribute = round("keygen_phase1", n, adversary, |i| {
+        let decom_view = received(&decom_vec, i, adversary, |a, decom| {
+            if a.deviation == Deviation::WrongKeyGenDecommitment {
+                Some(KeyGenDecommitMessage1 {
+                    blind_factor: decom.blind_factor.clone(),
+                    y_i: wrong_y_i,
+                })
+            } else {
+                None
+            }
+        });
+        keys[i].phase1_verify_com_phase3_verify_correct_key_phase2_distribute(
+            &params,
+            &decom_view,
+            &bc1_vec,
+        )
+    })?;
+    let y_vec = decom_vec.iter().map(|d| d.y_i).collect::<Vec<GE>>();
+    let vss_scheme_vec = distribute
+        .iter()
+        .map(|(vss_scheme, _, _)| vss_scheme.clone())
+        .collect::<Vec<VerifiableSS>>();
+
+    // the party after the adversary gets a bad share
+    let victim = deviates(adversary, Deviation::InconsistentVssShare).map(|a| (a + 1) % n);
+    let keypairs = round("keygen_phase2", n, adversary, |i| {
+        let mut shares = distribute
+            .iter()
+            .map(|(_, secret_shares, _)| secret_shares[i])
+            .collect::<Vec<FE>>();
+        if victim == Some(i) {
+            let a = adversary.unwrap().party;
+            let one: FE = ECScalar::from(&BigInt::one());
+            shares[a] = shares[a] + one;
+        }
+        keys[i].phase2_verify_vss_construct_keypair_phase3_pok_dlog(
+            &params,
+            &y_vec,
+            &shares,
+            &vss_scheme_vec,
+            i + 1,
+        )
+    })?;
+    let (shared_keys, dlog_proofs): (Vec<SharedKeys>, Vec<DLogProof>) =
+        keypairs.into_iter().unzip();
+
+    round("keygen_phase3", n, adversary, |_| {
+        Keys::verify_dlog_proofs(&params, &dlog_proofs, &y_vec)
+    })?;
+
+    Ok(KeyGenOutput {
+        keys,
+        y: shared_keys[0].y,
+        shared_keys,
+        vss_scheme: key_vss_scheme(&vss_scheme_vec),
+    })
+}
+
+// s holds the indices of the signers, the adversary is given by its position in s
+fn sign(
+    keygen: &KeyGenOutput,
+    s: &[usize],
+    adversary: &Option<Adversary>,
+) -> Result<Vec<SignatureRecid>, Abort> {
+    let ttag = s.len();
+    let sign_keys = (0..ttag)
+        .map(|i| {
+            let mut shared_keys = keygen.shared_keys[s[i]].clone();
+            if deviates(adversary, Deviation::StaleShare) == Some(i) {
+                let offset: FE = ECScalar::new_random();
+                shared_keys.x_i = shared_keys.x_i + offset;
+            }
+            let private = PartyPrivate::set_private(keygen.keys[s[i]].clone(), shared_keys);
+            SignKeys::create(&private, &keygen.vss_scheme, s[i], s)
+        })
+        .collect::<Vec<SignKeys>>();
+    let (bc1_vec, decom1_vec): (Vec<SignBroadcastPhase1>, Vec<SignDecommitPhase1>) =
+        sign_keys.iter().map(|k| k.phase1_broadcast()).unzip();
+    let m_a_vec = (0..ttag)
+        .map(|i| MessageA::a(&sign_keys[i].k_i, &keygen.keys[s[i]].ek).0)
+        .collect::<Vec<MessageA>>();
+
+    let wrong_g_gamma_i = random_point();
+    let tamper_bc1 = |a: &Adversary, _: &SignBroadcastPhase1| match a.deviation {
+        Deviation::WrongGGammaI => Some(SignBroadcastPhase1 {
+            com: HashCommitment::create_commitment_with_user_defined_randomness(
+                &wrong_g_gamma_i.bytes_compressed_to_big_int(),
+                &decom1_vec[a.party].blind_factor,
+            ),
+        }),
+        _ => None,
+    };
+    let tamper_decom1 = |a: &Adversary, decom: &SignDecommitPhase1| match a.deviation {
+        Deviation::WrongGGammaI => Some(SignDecommitPhase1 {
+            blind_factor: decom.blind_factor.clone(),
+            g_gamma_i: wrong_g_gamma_i,
+        }),
+        _ => None,
+    };
+
+    // (sender, receiver) -> MtA responses for gamma_i and w_i with the sender's betas
+    let mut m_b: HashMap<(usize, usize), (MessageB, FE, MessageB, FE)> = HashMap::new();
+    for j in 0..ttag {
+        for i in (0..ttag).filter(|&i| i != j) {
+            let ek = &keygen.keys[s[i]].ek;
+            let (mut m_b_gamma, beta, _) =
+                MessageB::b(&sign_keys[j].gamma_i, ek, m_a_vec[i].clone());
+            let (m_b_w, ni, _) = MessageB::b(&sign_keys[j].w_i, ek, m_a_vec[i].clone());
+            if deviates(adversary, Deviation::TamperedMessageB) == Some(j) {
+                let enc_one = Paillier::encrypt(ek, RawPlaintext::from(BigInt::one()));
+                m_b_gamma.c = Paillier::add(ek, RawCiphertext::from(m_b_gamma.c), enc_one)
+                    .0
+                    .into_owned();
+            }
+            m_b.insert((j, i), (m_b_gamma, beta, m_b_w, ni));
+        }
+    }
+
+    let delta_sigma = round("mta", ttag, adversary, |i| {
+        let dk = &keygen.keys[s[i]].dk;
+        let mut alpha_vec = Vec::new();
+        let mut beta_vec = Vec::new();
+        let mut miu_vec = Vec::new();
+        let mut ni_vec = Vec::new();
+        for j in (0..ttag).filter(|&j| j != i) {
+            let (m_b_gamma, _, m_b_w, _) = &m_b[&(j, i)];
+            alpha_vec.push(m_b_gamma.verify_proofs_get_alpha(dk, &sign_keys[i].k_i)?);
+            miu_vec.push(m_b_w.verify_proofs_get_alpha_wc(
+                dk,
+                &sign_keys[i].k_i,
+                &keygen.vss_scheme,
+                s[j],
+                s,
+            )?);
+            let (_, beta, _, ni) = &m_b[&(i, j)];
+            beta_vec.push(*beta);
+            ni_vec.push(*ni);
+        }
+        Ok((
+            sign_keys[i].phase2_delta_i(&alpha_vec, &beta_vec),
+            sign_keys[i].phase2_sigma_i(&miu_vec, &ni_vec),
+        ))
+    })?;
+    let delta_vec = delta_sigma
+        .iter()
+        .map(|(delta, _)| *delta)
+        .collect::<Vec<FE>>();
+    let delta_inv = SignKeys::phase3_reconstruct_delta(&delta_vec);
+
+    let R_vec = round("phase4", ttag, adversary, |i| {
+        let b_proof_vec = (0..ttag)
+            .map(|j| {
+                // a party knows its own g_gamma_i from any of the MtAs it answered
+                let key = if j == i { (i, (i + 1) % ttag) } else { (j, i) };
+                &m_b[&key].0.b_proof
+            })
+            .collect::<Vec<&DLogProof>>();
+        SignKeys::phase4(
+            &delta_inv,
+            &b_proof_vec,
+            received(&decom1_vec, i, adversary, &tamper_decom1),
+            &received(&bc1_vec, i, adversary, &tamper_bc1),
+        )
+    })?;
+
+    let message = message();
+    let local_sig_vec = (0..ttag)
+        .map(|i| {
+            LocalSignature::phase5_local_sig(
+                &sign_keys[i].k_i,
+                &message,
+                &R_vec[i],
+                &delta_sigma[i].1,
+                &keygen.y,
+            )
+        })
+        .collect::<Vec<LocalSignature>>();
+    let phase5a_vec = local_sig_vec
+        .iter()
+        .map(|sig| sig.phase5a_broadcast_5b_zkproof())
+        .collect::<Vec<_>>();
+
+    // a second, equally valid, opening of the adversary's s_i for every other party
+    let equivocation = deviates(adversary, Deviation::EquivocatingPhase5ADecom1).map(|a| {
+        let sig = &local_sig_vec[a];
+        LocalSignature::phase5_local_sig(
+            &sign_keys[a].k_i,
+            &message,
+            &sig.R,
+            &delta_sigma[a].1,
+            &keygen.y,
+        )
+        .phase5a_broadcast_5b_zkproof()
+    });
+    let phase5a_view = |i: usize| {
+        received(&phase5a_vec, i, adversary, |a, msg| match a.deviation {
+            Deviation::BadPhase5ADecom1 => {
+                let mut msg = msg.clone();
+                msg.1.V_i = msg.1.V_i + random_point();
+                Some(msg)
+            }
+            Deviation::EquivocatingPhase5ADecom1 if i % 2 == 1 => equivocation.clone(),
+            _ => None,
+        })
+    };
+
+    let phase5c = round("phase5c", ttag, adversary, |i| {
+        let view = phase5a_view(i);
+        let others = (0..ttag).filter(|&j| j != i);
+        let com_vec = others
+            .clone()
+            .map(|j| view[j].0.clone())
+            .collect::<Vec<Phase5Com1>>();
+        let decom_vec = others
+            .clone()
+            .map(|j| view[j].1.clone())
+            .collect::<Vec<Phase5ADecom1>>();
+        let elgamal_proofs = others
+            .map(|j| view[j].2.clone())
+            .collect::<Vec<HomoELGamalProof>>();
+        let dlog_proofs_rho = view.iter().map(|m| m.3.clone()).collect::<Vec<DLogProof>>();
+        local_sig_vec[i].phase5c(
+            &decom_vec,
+            &com_vec,
+            &elgamal_proofs,
+            &dlog_proofs_rho,
+            &view[i].1.V_i,
+            &R_vec[i],
+        )
+    })?;
+    let (com2_vec, decom2_vec): (Vec<_>, Vec<_>) = phase5c.into_iter().unzip();
+
+    let s_vec = round("phase5d", ttag, adversary, |i| {
+        let decom1_vec = phase5a_view(i)
+            .into_iter()
+            .map(|m| m.1)
+            .collect::<Vec<Phase5ADecom1>>();
+        local_sig_vec[i].phase5d(&decom2_vec, &com2_vec, &decom1_vec)
+    })?;
+
+    round("output", ttag, adversary, |i| {
+        let others = (0..ttag)
+            .filter(|&j| j != i)
+            .map(|j| s_vec[j])
+            .collect::<Vec<FE>>();
+        local_sig_vec[i].output_signature(&others)
+    })
+}
+
+fn expect_abort<T>(run: impl FnOnce() -> Result<T, Abort>) -> Abort {
+    match catch_unwind(AssertUnwindSafe(run)) {
+        Ok(Ok(_)) => panic!("the honest parties completed the protocol"),
+        Ok(Err(abort)) => abort,
+        Err(_) => panic!("an honest party panicked"),
+    }
+}
+
+fn assert_aborted(abort: &Abort, round: &str, parties: &[usize], error: Error) {
+    assert_eq!(abort.round, round);
+    let expected = parties
+        .iter()
+        .map(|&i| (i, error.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(abort.errors, expected);
+}
+
+fn keygen_t1_n3() -> KeyGenOutput {
+    keygen(1, 3, &None).expect("honest keygen failed")
+}
+
+#[test]
+fn test_honest_parties() {
+    let keygen = keygen_t1_n3();
+    let signatures = sign(&keygen, &[0, 1, 2], &None).expect("honest signing failed");
+    assert_eq!(signatures.len(), 3);
+    for sig in &signatures {
+        verify(sig, &keygen.y, &message()).expect("invalid signature");
+    }
+}
+
+#[test]
+fn test_wrong_keygen_decommitment() {
+    let adversary = Some(Adversary {
+        party: 1,
+        deviation: Deviation::WrongKeyGenDecommitment,
+    });
+    let abort = expect_abort(|| keygen(1, 3, &adversary));
+    assert_aborted(&abort, "keygen_phase1", &[0, 2], InvalidKey);
+}
+
+#[test]
+fn test_inconsistent_vss_share() {
+    let adversary = Some(Adversary {
+        party: 0,
+        deviation: Deviation::InconsistentVssShare,
+    });
+    let abort = expect_abort(|| keygen(1, 3, &adversary));
+    // only the party that got the bad share notices
+    assert_aborted(
+        &abort,
+        "keygen_phase2",
+        &[1],
+        InvalidShare { parties: vec![0] },
+    );
+}
+
+#[test]
+fn test_tampered_message_b() {
+    let keygen = keygen_t1_n3();
+    let adversary = Some(Adversary {
+        party: 2,
+        deviation: Deviation::TamperedMessageB,
+    });
+    let abort = expect_abort(|| sign(&keygen, &[0, 1, 2], &adversary));
+    assert_aborted(&abort, "mta", &[0, 1], InvalidKey);
+}
+
+#[test]
+fn test_stale_share() {
+    let keygen = keygen_t1_n3();
+    let adversary = Some(Adversary {
+        party: 1,
+        deviation: Deviation::StaleShare,
+    });
+    let abort = expect_abort(|| sign(&keygen, &[0, 1, 2], &adversary));
+    assert_aborted(&abort, "mta", &[0, 2], PublicShareMismatch { party: 1 });
+}
+
+#[test]
+fn test_wrong_g_gamma_i() {
+    let keygen = keygen_t1_n3();
+    let adversary = Some(Adversary {
+        party: 0,
+        deviation: Deviation::WrongGGammaI,
+    });
+    let abort = expect_abort(|| sign(&keygen, &[0, 1, 2], &adversary));
+    assert_aborted(&abort, "phase4", &[1, 2], InvalidKey);
+}
+
+#[test]
+fn test_bad_phase5a_decommitment() {
+    let keygen = keygen_t1_n3();
+    let adversary = Some(Adversary {
+        party: 1,
+        deviation: Deviation::BadPhase5ADecom1,
+    });
+    let abort = expect_abort(|| sign(&keygen, &[0, 1, 2], &adversary));
+    assert_aborted(&abort, "phase5c", &[0, 2], InvalidCom);
+}
+
+#[test]
+fn test_equivocating_phase5a() {
+    let keygen = keygen_t1_n3();
+    let adversary = Some(Adversary {
+        party: 0,
+        deviation: Deviation::EquivocatingPhase5ADecom1,
+    });
+    // party 1 and party 2 see different, valid, openings of the adversary's V_i, A_i, B_i.
+    // The commitments and proofs check out, the final consistency check does not.
+    let abort = expect_abort(|| sign(&keygen, &[0, 1, 2], &adversary));
+    assert_aborted(&abort, "phase5d", &[1, 2], InvalidKey);
+}
@@ -30,13 +30,16 @@ use curv::elliptic::curves::traits::*;
 use curv::BigInt;
 use curv::FE;
 use curv::GE;
+use rand::rngs::OsRng;
+use rand::{CryptoRng, RngCore};
 use serde::{Deserialize, Serialize};
-use subtle::ConstantTimeEq;
 
 use super::party_two::EphKeyGenFirstMsg as Party2EphKeyGenFirstMessage;
 use super::party_two::EphKeyGenSecondMsg as Party2EphKeyGenSecondMessage;
 use super::SECURITY_BITS;
-use crate::Error::{self, InvalidSig};
+use crate::utilities::ecdsa::{self, Malleability};
+use crate::utilities::sampling;
+use crate::Error;
 
 //****************** Begin: Party One structs ******************//
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -127,24 +130,30 @@ pub struct EphKeyGenSecondMsg {}
 
 impl KeyGenFirstMsg {
     pub fn create_commitments() -> (KeyGenFirstMsg, CommWitness, EcKeyPair) {
+        Self::create_commitments_with_rng(&mut OsRng)
+    }
+
+    pub fn create_commitments_with_rng<R: RngCore + CryptoRng>(
+        rng: &mut R,
+    ) -> (KeyGenFirstMsg, CommWitness, EcKeyPair) {
         let base: GE = ECPoint::generator();
 
-        let secret_share: FE = ECScalar::new_random();
+        let secret_share: FE = sampling::sample_scalar(rng);
         //in Lindell's protocol range proof works only for x1<q/3
         let secret_share: FE =
             ECScalar::from(&secret_share.to_big_int().div_floor(&BigInt::from(3)));
 
         let public_share = base.scalar_mul(&secret_share.get_element());
 
-        let d_log_proof = DLogProof::prove(&secret_share);
+        let d_log_proof = sampling::prove_dlog(rng, &secret_share);
         // we use hash based commitment
-        let pk_commitment_blind_factor = BigInt::sample(SECURITY_BITS);
+        let pk_commitment_blind_factor = sampling::sample_bits(rng, SECURITY_BITS);
         let pk_commitment = HashCommitment::create_commitment_with_user_defined_randomness(
             &public_share.bytes_compressed_to_big_int(),
             &pk_commitment_blind_factor,
         );
 
-        let zk_pok_blind_factor = BigInt::sample(SECURITY_BITS);
+        let zk_pok_blind_factor = sampling::sample_bits(rng, SECURITY_BITS);
         let zk_pok_commitment = HashCommitment::create_commitment_with_user_defined_randomness(
             &d_log_proof
                 .pk_t_rand_commitment
@@ -172,6 +181,13 @@ impl KeyGenFirstMsg {
 
     pub fn create_commitments_with_fixed_secret_share(
         secret_share: FE,
+    ) -> (KeyGenFirstMsg, CommWitness, EcKeyPair) {
+        Self::create_commitments_with_fixed_secret_share_and_rng(secret_share, &mut OsRng)
+    }
+
+    pub fn create_commitments_with_fixed_secret_share_and_rng<R: RngCore + CryptoRng>(
+        secret_share: FE,
+        rng: &mut R,
     ) -> (KeyGenFirstMsg, CommWitness, EcKeyPair) {
         //in Lindell's protocol range proof works only for x1<q/3
         let sk_bigint = secret_share.to_big_int();
@@ -180,15 +196,15 @@ impl KeyGenFirstMsg {
         let base: GE = ECPoint::generator();
         let public_share = base.scalar_mul(&secret_share.get_element());
 
-        let d_log_proof = DLogProof::prove(&secret_share);
+        let d_log_proof = sampling::prove_dlog(rng, &secret_share);
 
-        let pk_commitment_blind_factor = BigInt::sample(SECURITY_BITS);
+        let pk_commitment_blind_factor = sampling::sample_bits(rng, SECURITY_BITS);
         let pk_commitment = HashCommitment::create_commitment_with_user_defined_randomness(
             &public_share.bytes_compressed_to_big_int(),
             &pk_commitment_blind_factor,
         );
 
-        let zk_pok_blind_factor = BigInt::sample(SECURITY_BITS);
+        let zk_pok_blind_factor = sampling::sample_bits(rng, SECURITY_BITS);
         let zk_pok_commitment = HashCommitment::create_commitment_with_user_defined_randomness(
             &d_log_proof
                 .pk_t_rand_commitment
@@ -242,9 +258,18 @@ impl Party1Private {
 
 impl HSMCLKeyPair {
     pub fn generate_keypair_and_encrypted_share(keygen: &EcKeyPair, seed: BigInt) -> HSMCLKeyPair {
+        Self::generate_keypair_and_encrypted_share_with_rng(keygen, seed, &mut OsRng)
+    }
+
+    // the class group secret key is sampled inside class_group, which does not take an rng
+    pub fn generate_keypair_and_encrypted_share_with_rng<R: RngCore + CryptoRng>(
+        keygen: &EcKeyPair,
+        seed: BigInt,
+        rng: &mut R,
+    ) -> HSMCLKeyPair {
         let hsmcl = HSMCL::keygen_with_setup(&FE::q(), &1348, &seed);
         let ek = hsmcl.pk.clone();
-        let randomness = BigInt::sample_below(&(&ek.stilde * BigInt::from(2).pow(40)));
+        let randomness = sampling::sample_below(rng, &(&ek.stilde * BigInt::from(2).pow(40)));
 
         let encrypted_share = HSMCL::encrypt_predefined_randomness(
             &ek,
@@ -282,8 +307,14 @@ impl HSMCLKeyPair {
 
 impl EphKeyGenFirstMsg {
     pub fn create() -> (EphKeyGenFirstMsg, EphEcKeyPair) {
+        Self::create_with_rng(&mut OsRng)
+    }
+
+    pub fn create_with_rng<R: RngCore + CryptoRng>(
+        rng: &mut R,
+    ) -> (EphKeyGenFirstMsg, EphEcKeyPair) {
         let base: GE = ECPoint::generator();
-        let secret_share: FE = ECScalar::new_random();
+        let secret_share: FE = sampling::sample_scalar(rng);
         let public_share = &base * &secret_share;
         let h: GE = GE::base_point2();
         let w = ECDDHWitness {
@@ -296,7 +327,7 @@ impl EphKeyGenFirstMsg {
             g2: h.clone(),
             h2: c.clone(),
         };
-        let d_log_proof = ECDDHProof::prove(&w, &delta);
+        let d_log_proof = sampling::prove_ecddh(rng, &w, &delta);
         let ec_key_pair = EphEcKeyPair {
             public_share: public_share.clone(),
             secret_share,
@@ -383,24 +414,16 @@ impl Signature {
     }
 }
 
+/// Verifies with the low s rule, the form Signature::compute produces.
 pub fn verify(signature: &Signature, pubkey: &GE, message: &BigInt) -> Result<(), Error> {
-    let s_fe: FE = ECScalar::from(&signature.s);
-    let rx_fe: FE = ECScalar::from(&signature.r);
-
-    let s_inv_fe = s_fe.invert();
-    let e_fe: FE = ECScalar::from(&message.mod_floor(&FE::q()));
-    let u1 = GE::generator() * e_fe * s_inv_fe;
-    let u2 = *pubkey * rx_fe * s_inv_fe;
-
-    // second condition is against malleability
-    let rx_bytes = &BigInt::to_vec(&signature.r)[..];
-    let u1_plus_u2_bytes = &BigInt::to_vec(&(u1 + u2).x_coor().unwrap())[..];
-
-    if rx_bytes.ct_eq(&u1_plus_u2_bytes).unwrap_u8() == 1
-        && signature.s < FE::q() - signature.s.clone()
-    {
-        Ok(())
-    } else {
-        Err(InvalidSig)
-    }
+    verify_with_policy(signature, pubkey, message, Malleability::RequireLowS)
+}
+
+pub fn verify_with_policy(
+    signature: &Signature,
+    pubkey: &GE,
+    message: &BigInt,
+    malleability: Malleability,
+) -> Result<(), Error> {
+    ecdsa::verify(&signature.r, &signature.s, pubkey, message, malleability)
 }
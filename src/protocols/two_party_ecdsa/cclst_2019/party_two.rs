@@ -30,12 +30,15 @@ use curv::elliptic::curves::traits::*;
 use curv::BigInt;
 use curv::FE;
 use curv::GE;
+use rand::rngs::OsRng;
+use rand::{CryptoRng, RngCore};
 use serde::{Deserialize, Serialize};
 
 use super::party_one::EphKeyGenFirstMsg as Party1EphKeyGenFirstMsg;
 use super::party_one::KeyGenFirstMsg as Party1KeyGenFirstMessage;
 use super::party_one::KeyGenSecondMsg as Party1KeyGenSecondMessage;
 use super::SECURITY_BITS;
+use crate::utilities::sampling;
 
 //****************** Begin: Party Two structs ******************//
 
@@ -126,27 +129,25 @@ pub struct EphKeyGenSecondMsg {
 
 impl KeyGenFirstMsg {
     pub fn create() -> (KeyGenFirstMsg, EcKeyPair) {
-        let base: GE = ECPoint::generator();
-        let secret_share: FE = ECScalar::new_random();
-        let public_share = base * &secret_share;
-        let d_log_proof = DLogProof::prove(&secret_share);
-        let ec_key_pair = EcKeyPair {
-            public_share: public_share.clone(),
-            secret_share,
-        };
-        (
-            KeyGenFirstMsg {
-                d_log_proof,
-                public_share,
-            },
-            ec_key_pair,
-        )
+        Self::create_with_rng(&mut OsRng)
+    }
+
+    pub fn create_with_rng<R: RngCore + CryptoRng>(rng: &mut R) -> (KeyGenFirstMsg, EcKeyPair) {
+        let secret_share: FE = sampling::sample_scalar(rng);
+        Self::create_with_fixed_secret_share_and_rng(secret_share, rng)
     }
 
     pub fn create_with_fixed_secret_share(secret_share: FE) -> (KeyGenFirstMsg, EcKeyPair) {
+        Self::create_with_fixed_secret_share_and_rng(secret_share, &mut OsRng)
+    }
+
+    pub fn create_with_fixed_secret_share_and_rng<R: RngCore + CryptoRng>(
+        secret_share: FE,
+        rng: &mut R,
+    ) -> (KeyGenFirstMsg, EcKeyPair) {
         let base: GE = ECPoint::generator();
         let public_share = base * &secret_share;
-        let d_log_proof = DLogProof::prove(&secret_share);
+        let d_log_proof = sampling::prove_dlog(rng, &secret_share);
         let ec_key_pair = EcKeyPair {
             public_share: public_share.clone(),
             secret_share,
@@ -238,9 +239,15 @@ impl HSMCLPublic {
 
 impl EphKeyGenFirstMsg {
     pub fn create_commitments() -> (EphKeyGenFirstMsg, EphCommWitness, EphEcKeyPair) {
+        Self::create_commitments_with_rng(&mut OsRng)
+    }
+
+    pub fn create_commitments_with_rng<R: RngCore + CryptoRng>(
+        rng: &mut R,
+    ) -> (EphKeyGenFirstMsg, EphCommWitness, EphEcKeyPair) {
         let base: GE = ECPoint::generator();
 
-        let secret_share: FE = ECScalar::new_random();
+        let secret_share: FE = sampling::sample_scalar(rng);
 
         let public_share = base.scalar_mul(&secret_share.get_element());
 
@@ -255,16 +262,16 @@ impl EphKeyGenFirstMsg {
             g2: h.clone(),
             h2: c.clone(),
         };
-        let d_log_proof = ECDDHProof::prove(&w, &delta);
+        let d_log_proof = sampling::prove_ecddh(rng, &w, &delta);
 
         // we use hash based commitment
-        let pk_commitment_blind_factor = BigInt::sample(SECURITY_BITS);
+        let pk_commitment_blind_factor = sampling::sample_bits(rng, SECURITY_BITS);
         let pk_commitment = HashCommitment::create_commitment_with_user_defined_randomness(
             &public_share.bytes_compressed_to_big_int(),
             &pk_commitment_blind_factor,
         );
 
-        let zk_pok_blind_factor = BigInt::sample(SECURITY_BITS);
+        let zk_pok_blind_factor = sampling::sample_bits(rng, SECURITY_BITS);
         let zk_pok_commitment = HashCommitment::create_commitment_with_user_defined_randomness(
             &HSha256::create_hash_from_ge(&[&d_log_proof.a1, &d_log_proof.a2]).to_big_int(),
             &zk_pok_blind_factor,
@@ -314,6 +321,24 @@ impl PartialSig {
         ephemeral_local_share: &EphEcKeyPair,
         ephemeral_other_public_share: &GE,
         message: &BigInt,
+    ) -> PartialSig {
+        Self::compute_with_rng(
+            party_two_public,
+            local_share,
+            ephemeral_local_share,
+            ephemeral_other_public_share,
+            message,
+            &mut OsRng,
+        )
+    }
+
+    pub fn compute_with_rng<R: RngCore + CryptoRng>(
+        party_two_public: HSMCLPublic,
+        local_share: &Party2Private,
+        ephemeral_local_share: &EphEcKeyPair,
+        ephemeral_other_public_share: &GE,
+        message: &BigInt,
+        rng: &mut R,
     ) -> PartialSig {
         let q = FE::q();
         //compute r = k2* R1
@@ -327,7 +352,12 @@ impl PartialSig {
             .invert(&q)
             .unwrap();
         let k2_inv_m = BigInt::mod_mul(&k2_inv, message, &q);
-        let c1 = HSMCL::encrypt(&party_two_public.ek, &k2_inv_m);
+        // sampled from the same range as the randomness of the encrypted key share
+        let randomness = sampling::sample_below(
+            rng,
+            &(&party_two_public.ek.stilde * BigInt::from(2).pow(40)),
+        );
+        let c1 = HSMCL::encrypt_predefined_randomness(&party_two_public.ek, &k2_inv_m, &randomness);
         let v = BigInt::mod_mul(&k2_inv, &local_share.x2.to_big_int(), &q);
         let v = BigInt::mod_mul(&v, &rx, &q);
 
@@ -17,7 +17,6 @@ use std::cmp;
 
 use centipede::juggling::proof_system::{Helgamalsegmented, Witness};
 use centipede::juggling::segmentation::Msegmentation;
-use curv::arithmetic::traits::*;
 use curv::cryptographic_primitives::commitments::hash_commitment::HashCommitment;
 use curv::cryptographic_primitives::commitments::traits::Commitment;
 use curv::cryptographic_primitives::hashing::hash_sha256::HSha256;
@@ -30,10 +29,11 @@ use curv::BigInt;
 use curv::FE;
 use curv::GE;
 use paillier::Paillier;
-use paillier::{Decrypt, EncryptWithChosenRandomness, KeyGeneration};
+use paillier::{Decrypt, EncryptWithChosenRandomness};
 use paillier::{DecryptionKey, EncryptionKey, Randomness, RawCiphertext, RawPlaintext};
+use rand::rngs::OsRng;
+use rand::{CryptoRng, RngCore};
 use serde::{Deserialize, Serialize};
-use subtle::ConstantTimeEq;
 use zeroize::Zeroize;
 use zk_paillier::zkproofs::{NICorrectKeyProof, RangeProofNi};
 
@@ -41,7 +41,9 @@ use super::party_two::EphKeyGenFirstMsg as Party2EphKeyGenFirstMessage;
 use super::party_two::EphKeyGenSecondMsg as Party2EphKeyGenSecondMessage;
 use super::SECURITY_BITS;
 
+use crate::utilities::ecdsa::{self, Malleability};
 use crate::utilities::mta::MessageB;
+use crate::utilities::sampling;
 use crate::Error;
 
 use crate::utilities::zk_pdl::PDLStatement;
@@ -139,24 +141,30 @@ pub struct EphKeyGenSecondMsg {}
 
 impl KeyGenFirstMsg {
     pub fn create_commitments() -> (KeyGenFirstMsg, CommWitness, EcKeyPair) {
+        Self::create_commitments_with_rng(&mut OsRng)
+    }
+
+    pub fn create_commitments_with_rng<R: RngCore + CryptoRng>(
+        rng: &mut R,
+    ) -> (KeyGenFirstMsg, CommWitness, EcKeyPair) {
         let base: GE = ECPoint::generator();
 
-        let mut scalar: FE = ECScalar::new_random();
+        let mut scalar: FE = sampling::sample_scalar(rng);
         //in Lindell's protocol range proof works only for x1<q/3
         let mut secret_share: FE = ECScalar::from(&scalar.to_big_int().div_floor(&BigInt::from(3)));
         scalar.zeroize();
 
         let public_share = base.scalar_mul(&secret_share.get_element());
 
-        let d_log_proof = DLogProof::prove(&secret_share);
+        let d_log_proof = sampling::prove_dlog(rng, &secret_share);
         // we use hash based commitment
-        let pk_commitment_blind_factor = BigInt::sample(SECURITY_BITS);
+        let pk_commitment_blind_factor = sampling::sample_bits(rng, SECURITY_BITS);
         let pk_commitment = HashCommitment::create_commitment_with_user_defined_randomness(
             &public_share.bytes_compressed_to_big_int(),
             &pk_commitment_blind_factor,
         );
 
-        let zk_pok_blind_factor = BigInt::sample(SECURITY_BITS);
+        let zk_pok_blind_factor = sampling::sample_bits(rng, SECURITY_BITS);
         let zk_pok_commitment = HashCommitment::create_commitment_with_user_defined_randomness(
             &d_log_proof
                 .pk_t_rand_commitment
@@ -184,7 +192,14 @@ impl KeyGenFirstMsg {
     }
 
     pub fn create_commitments_with_fixed_secret_share(
+        secret_share: FE,
+    ) -> (KeyGenFirstMsg, CommWitness, EcKeyPair) {
+        Self::create_commitments_with_fixed_secret_share_and_rng(secret_share, &mut OsRng)
+    }
+
+    pub fn create_commitments_with_fixed_secret_share_and_rng<R: RngCore + CryptoRng>(
         mut secret_share: FE,
+        rng: &mut R,
     ) -> (KeyGenFirstMsg, CommWitness, EcKeyPair) {
         //in Lindell's protocol range proof works only for x1<q/3
         let sk_bigint = secret_share.to_big_int();
@@ -193,15 +208,15 @@ impl KeyGenFirstMsg {
         let base: GE = ECPoint::generator();
         let public_share = base.scalar_mul(&secret_share.get_element());
 
-        let d_log_proof = DLogProof::prove(&secret_share);
+        let d_log_proof = sampling::prove_dlog(rng, &secret_share);
 
-        let pk_commitment_blind_factor = BigInt::sample(SECURITY_BITS);
+        let pk_commitment_blind_factor = sampling::sample_bits(rng, SECURITY_BITS);
         let pk_commitment = HashCommitment::create_commitment_with_user_defined_randomness(
             &public_share.bytes_compressed_to_big_int(),
             &pk_commitment_blind_factor,
         );
 
-        let zk_pok_blind_factor = BigInt::sample(SECURITY_BITS);
+        let zk_pok_blind_factor = sampling::sample_bits(rng, SECURITY_BITS);
         let zk_pok_commitment = HashCommitment::create_commitment_with_user_defined_randomness(
             &d_log_proof
                 .pk_t_rand_commitment
@@ -262,8 +277,23 @@ impl Party1Private {
         NICorrectKeyProof,
         RangeProofNi,
     ) {
-        let (ek_new, dk_new) = Paillier::keypair().keys();
-        let randomness = Randomness::sample(&ek_new);
+        Self::refresh_private_key_with_rng(party_one_private, factor, &mut OsRng)
+    }
+
+    // the range proof samples its own randomness, zk-paillier does not take an rng
+    pub fn refresh_private_key_with_rng<R: RngCore + CryptoRng>(
+        party_one_private: &Party1Private,
+        factor: &BigInt,
+        rng: &mut R,
+    ) -> (
+        EncryptionKey,
+        BigInt,
+        Party1Private,
+        NICorrectKeyProof,
+        RangeProofNi,
+    ) {
+        let (ek_new, dk_new) = sampling::paillier_keypair(rng);
+        let randomness = Randomness::from(sampling::sample_below(rng, &ek_new.n));
         let factor_fe: FE = ECScalar::from(&factor);
         let x1_new = party_one_private.x1 * factor_fe;
         let three = BigInt::from(3);
@@ -318,8 +348,15 @@ impl Party1Private {
 
 impl PaillierKeyPair {
     pub fn generate_keypair_and_encrypted_share(keygen: &EcKeyPair) -> PaillierKeyPair {
-        let (ek, dk) = Paillier::keypair().keys();
-        let randomness = Randomness::sample(&ek);
+        Self::generate_keypair_and_encrypted_share_with_rng(keygen, &mut OsRng)
+    }
+
+    pub fn generate_keypair_and_encrypted_share_with_rng<R: RngCore + CryptoRng>(
+        keygen: &EcKeyPair,
+        rng: &mut R,
+    ) -> PaillierKeyPair {
+        let (ek, dk) = sampling::paillier_keypair(rng);
+        let randomness = Randomness::from(sampling::sample_below(rng, &ek.n));
 
         let encrypted_share = Paillier::encrypt_with_chosen_randomness(
             &ek,
@@ -342,7 +379,18 @@ impl PaillierKeyPair {
         dk: &DecryptionKey,
         keygen: &EcKeyPair,
     ) -> PaillierKeyPair {
-        let randomness = Randomness::sample(ek);
+        Self::generate_encrypted_share_from_fixed_paillier_keypair_with_rng(
+            ek, dk, keygen, &mut OsRng,
+        )
+    }
+
+    pub fn generate_encrypted_share_from_fixed_paillier_keypair_with_rng<R: RngCore + CryptoRng>(
+        ek: &EncryptionKey,
+        dk: &DecryptionKey,
+        keygen: &EcKeyPair,
+        rng: &mut R,
+    ) -> PaillierKeyPair {
+        let randomness = Randomness::from(sampling::sample_below(rng, &ek.n));
 
         let encrypted_share = Paillier::encrypt_with_chosen_randomness(
             ek,
@@ -386,6 +434,25 @@ impl PaillierKeyPair {
         PDLProverState,
         PDLStatement,
         PDLWitness,
+    ) {
+        Self::pdl_first_message_with_rng(
+            party_one_private,
+            verifier_first_messsage,
+            paillier_key_pair,
+            &mut OsRng,
+        )
+    }
+
+    pub fn pdl_first_message_with_rng<R: RngCore + CryptoRng>(
+        party_one_private: &Party1Private,
+        verifier_first_messsage: &PDLVerifierFirstMessage,
+        paillier_key_pair: &PaillierKeyPair,
+        rng: &mut R,
+    ) -> (
+        PDLProverFirstMessage,
+        PDLProverState,
+        PDLStatement,
+        PDLWitness,
     ) {
         let statement = PDLStatement {
             ciphertext: paillier_key_pair.encrypted_share.clone(),
@@ -399,7 +466,7 @@ impl PaillierKeyPair {
             dk: party_one_private.paillier_priv.clone(),
         };
         let (prover_message1, prover_state) =
-            PDLProver::message1(&witness, &statement, &verifier_first_messsage);
+            PDLProver::message1_with_rng(&witness, &statement, &verifier_first_messsage, rng);
         (prover_message1, prover_state, statement, witness)
     }
 
@@ -420,8 +487,14 @@ impl PaillierKeyPair {
 
 impl EphKeyGenFirstMsg {
     pub fn create() -> (EphKeyGenFirstMsg, EphEcKeyPair) {
+        Self::create_with_rng(&mut OsRng)
+    }
+
+    pub fn create_with_rng<R: RngCore + CryptoRng>(
+        rng: &mut R,
+    ) -> (EphKeyGenFirstMsg, EphEcKeyPair) {
         let base: GE = ECPoint::generator();
-        let mut secret_share: FE = ECScalar::new_random();
+        let mut secret_share: FE = sampling::sample_scalar(rng);
         let public_share = &base * &secret_share;
         let h: GE = GE::base_point2();
 
@@ -434,7 +507,7 @@ impl EphKeyGenFirstMsg {
             g2: h,
             h2: c,
         };
-        let d_log_proof = ECDDHProof::prove(&w, &delta);
+        let d_log_proof = sampling::prove_ecddh(rng, &w, &delta);
         let ec_key_pair = EphEcKeyPair {
             public_share,
             secret_share,
@@ -576,24 +649,16 @@ impl Signature {
     }
 }
 
+/// Verifies with the low s rule, the form Signature::compute produces.
 pub fn verify(signature: &Signature, pubkey: &GE, message: &BigInt) -> Result<(), Error> {
-    let s_fe: FE = ECScalar::from(&signature.s);
-    let rx_fe: FE = ECScalar::from(&signature.r);
-
-    let s_inv_fe = s_fe.invert();
-    let e_fe: FE = ECScalar::from(&message.mod_floor(&FE::q()));
-    let u1 = GE::generator() * e_fe * s_inv_fe;
-    let u2 = *pubkey * rx_fe * s_inv_fe;
-
-    // second condition is against malleability
-    let rx_bytes = &BigInt::to_vec(&signature.r)[..];
-    let u1_plus_u2_bytes = &BigInt::to_vec(&(u1 + u2).x_coor().unwrap())[..];
-
-    if rx_bytes.ct_eq(&u1_plus_u2_bytes).unwrap_u8() == 1
-        && signature.s < FE::q() - signature.s.clone()
-    {
-        Ok(())
-    } else {
-        Err(Error::InvalidSig)
-    }
+    verify_with_policy(signature, pubkey, message, Malleability::RequireLowS)
+}
+
+pub fn verify_with_policy(
+    signature: &Signature,
+    pubkey: &GE,
+    message: &BigInt,
+    malleability: Malleability,
+) -> Result<(), Error> {
+    ecdsa::verify(&signature.r, &signature.s, pubkey, message, malleability)
 }
@@ -29,8 +29,10 @@ use curv::BigInt;
 use curv::FE;
 use curv::GE;
 use paillier::Paillier;
-use paillier::{Add, Encrypt, Mul};
-use paillier::{EncryptionKey, RawCiphertext, RawPlaintext};
+use paillier::{Add, EncryptWithChosenRandomness, Mul};
+use paillier::{EncryptionKey, Randomness, RawCiphertext, RawPlaintext};
+use rand::rngs::OsRng;
+use rand::{CryptoRng, RngCore};
 use serde::{Deserialize, Serialize};
 use zk_paillier::zkproofs::{CorrectKeyProofError, NICorrectKeyProof};
 
@@ -39,6 +41,7 @@ use super::party_one::KeyGenFirstMsg as Party1KeyGenFirstMessage;
 use super::party_one::KeyGenSecondMsg as Party1KeyGenSecondMessage;
 use super::SECURITY_BITS;
 use crate::utilities::mta::{MessageA, MessageB};
+use crate::utilities::sampling;
 use crate::utilities::zk_pdl::PDLStatement;
 use crate::utilities::zk_pdl::Verifier as PDLVerifier;
 use crate::utilities::zk_pdl::*;
@@ -134,10 +137,14 @@ pub struct EphKeyGenSecondMsg {
 
 impl KeyGenFirstMsg {
     pub fn create() -> (KeyGenFirstMsg, EcKeyPair) {
+        Self::create_with_rng(&mut OsRng)
+    }
+
+    pub fn create_with_rng<R: RngCore + CryptoRng>(rng: &mut R) -> (KeyGenFirstMsg, EcKeyPair) {
         let base: GE = ECPoint::generator();
-        let mut secret_share: FE = ECScalar::new_random();
+        let mut secret_share: FE = sampling::sample_scalar(rng);
         let public_share = base * secret_share;
-        let d_log_proof = DLogProof::prove(&secret_share);
+        let d_log_proof = sampling::prove_dlog(rng, &secret_share);
         let ec_key_pair = EcKeyPair {
             public_share,
             secret_share,
@@ -152,10 +159,17 @@ impl KeyGenFirstMsg {
         )
     }
 
-    pub fn create_with_fixed_secret_share(mut secret_share: FE) -> (KeyGenFirstMsg, EcKeyPair) {
+    pub fn create_with_fixed_secret_share(secret_share: FE) -> (KeyGenFirstMsg, EcKeyPair) {
+        Self::create_with_fixed_secret_share_and_rng(secret_share, &mut OsRng)
+    }
+
+    pub fn create_with_fixed_secret_share_and_rng<R: RngCore + CryptoRng>(
+        mut secret_share: FE,
+        rng: &mut R,
+    ) -> (KeyGenFirstMsg, EcKeyPair) {
         let base: GE = ECPoint::generator();
         let public_share = base * secret_share;
-        let d_log_proof = DLogProof::prove(&secret_share);
+        let d_log_proof = sampling::prove_dlog(rng, &secret_share);
         let ec_key_pair = EcKeyPair {
             public_share,
             secret_share,
@@ -247,10 +261,19 @@ impl Party2Private {
 
     // used to transform lindell master key to gg18 master key
     pub fn to_mta_message_b(&self, ek: &EncryptionKey, ciphertext: &BigInt) -> (MessageB, FE) {
+        self.to_mta_message_b_with_rng(ek, ciphertext, &mut OsRng)
+    }
+
+    pub fn to_mta_message_b_with_rng<R: RngCore + CryptoRng>(
+        &self,
+        ek: &EncryptionKey,
+        ciphertext: &BigInt,
+        rng: &mut R,
+    ) -> (MessageB, FE) {
         let message_a = MessageA {
             c: ciphertext.clone(),
         };
-        let (a, b, _) = MessageB::b(&self.x2, &ek, message_a);
+        let (a, b, _) = MessageB::b_with_rng(&self.x2, &ek, message_a, rng);
         (a, b)
     }
 }
@@ -259,6 +282,14 @@ impl PaillierPublic {
     pub fn pdl_first_message(
         &self,
         other_share_public_share: &GE,
+    ) -> (PDLVerifierFirstMessage, PDLVerifierState, PDLStatement) {
+        self.pdl_first_message_with_rng(other_share_public_share, &mut OsRng)
+    }
+
+    pub fn pdl_first_message_with_rng<R: RngCore + CryptoRng>(
+        &self,
+        other_share_public_share: &GE,
+        rng: &mut R,
     ) -> (PDLVerifierFirstMessage, PDLVerifierState, PDLStatement) {
         let statement = PDLStatement {
             ciphertext: self.encrypted_secret_share.clone(),
@@ -266,7 +297,7 @@ impl PaillierPublic {
             Q: other_share_public_share.clone(),
             G: GE::generator(),
         };
-        let (verifier_message1, verifier_state) = PDLVerifier::message1(&statement);
+        let (verifier_message1, verifier_state) = PDLVerifier::message1_with_rng(&statement, rng);
         (verifier_message1, verifier_state, statement)
     }
 
@@ -300,9 +331,15 @@ impl PaillierPublic {
 
 impl EphKeyGenFirstMsg {
     pub fn create_commitments() -> (EphKeyGenFirstMsg, EphCommWitness, EphEcKeyPair) {
+        Self::create_commitments_with_rng(&mut OsRng)
+    }
+
+    pub fn create_commitments_with_rng<R: RngCore + CryptoRng>(
+        rng: &mut R,
+    ) -> (EphKeyGenFirstMsg, EphCommWitness, EphEcKeyPair) {
         let base: GE = ECPoint::generator();
 
-        let mut secret_share: FE = ECScalar::new_random();
+        let mut secret_share: FE = sampling::sample_scalar(rng);
 
         let public_share = base.scalar_mul(&secret_share.get_element());
 
@@ -317,16 +354,16 @@ impl EphKeyGenFirstMsg {
             g2: h,
             h2: c,
         };
-        let d_log_proof = ECDDHProof::prove(&w, &delta);
+        let d_log_proof = sampling::prove_ecddh(rng, &w, &delta);
 
         // we use hash based commitment
-        let pk_commitment_blind_factor = BigInt::sample(SECURITY_BITS);
+        let pk_commitment_blind_factor = sampling::sample_bits(rng, SECURITY_BITS);
         let pk_commitment = HashCommitment::create_commitment_with_user_defined_randomness(
             &public_share.bytes_compressed_to_big_int(),
             &pk_commitment_blind_factor,
         );
 
-        let zk_pok_blind_factor = BigInt::sample(SECURITY_BITS);
+        let zk_pok_blind_factor = sampling::sample_bits(rng, SECURITY_BITS);
         let zk_pok_commitment = HashCommitment::create_commitment_with_user_defined_randomness(
             &HSha256::create_hash_from_ge(&[&d_log_proof.a1, &d_log_proof.a2]).to_big_int(),
             &zk_pok_blind_factor,
@@ -379,6 +416,26 @@ impl PartialSig {
         ephemeral_local_share: &EphEcKeyPair,
         ephemeral_other_public_share: &GE,
         message: &BigInt,
+    ) -> PartialSig {
+        Self::compute_with_rng(
+            ek,
+            encrypted_secret_share,
+            local_share,
+            ephemeral_local_share,
+            ephemeral_other_public_share,
+            message,
+            &mut OsRng,
+        )
+    }
+
+    pub fn compute_with_rng<R: RngCore + CryptoRng>(
+        ek: &EncryptionKey,
+        encrypted_secret_share: &BigInt,
+        local_share: &Party2Private,
+        ephemeral_local_share: &EphEcKeyPair,
+        ephemeral_other_public_share: &GE,
+        message: &BigInt,
+        rng: &mut R,
     ) -> PartialSig {
         let q = FE::q();
         //compute r = k2* R1
@@ -386,7 +443,7 @@ impl PartialSig {
             .scalar_mul(&ephemeral_local_share.secret_share.get_element());
 
         let rx = r.x_coor().unwrap().mod_floor(&q);
-        let rho = BigInt::sample_below(&q.pow(2));
+        let rho = sampling::sample_below(rng, &q.pow(2));
         let mut k2_inv = ephemeral_local_share
             .secret_share
             .to_big_int()
@@ -394,7 +451,11 @@ impl PartialSig {
             .unwrap();
         let partial_sig = rho * &q + BigInt::mod_mul(&k2_inv, message, &q);
 
-        let c1 = Paillier::encrypt(ek, RawPlaintext::from(partial_sig));
+        let c1 = Paillier::encrypt_with_chosen_randomness(
+            ek,
+            RawPlaintext::from(partial_sig),
+            &Randomness::from(sampling::sample_below(rng, &ek.n)),
+        );
         let v = BigInt::mod_mul(
             &k2_inv,
             &BigInt::mod_mul(&rx, &local_share.x2.to_big_int(), &q),
@@ -1,9 +1,11 @@
 // For integration tests, please add your tests in /tests instead
 
 use crate::protocols::two_party_ecdsa::lindell_2017::{party_one, party_two};
+use crate::utilities::ecdsa::Malleability;
 use curv::arithmetic::traits::Samplable;
 use curv::elliptic::curves::traits::*;
 use curv::BigInt;
+use curv::FE;
 
 #[test]
 fn test_d_log_proof_party_two_party_one() {
@@ -158,5 +160,83 @@ fn test_two_party_sign() {
 
     let pubkey =
         party_one::compute_pubkey(&party1_private, &party_two_private_share_gen.public_share);
-    party_one::verify(&signature, &pubkey, &message).expect("Invalid signature")
+    party_one::verify(&signature, &pubkey, &message).expect("Invalid signature");
+
+    // the high s twin only passes when the caller allows it
+    let high_s = party_one::Signature {
+        r: signature.r.clone(),
+        s: FE::q() - &signature.s,
+    };
+    assert!(party_one::verify(&high_s, &pubkey, &message).is_err());
+    party_one::verify_with_policy(&high_s, &pubkey, &message, Malleability::Allow)
+        .expect("Invalid signature")
+}
+
+#[test]
+fn test_vector_two_party_sign() {
+    use crate::utilities::test_vectors;
+
+    test_vectors::check("lindell17_sign", 1701, |rng, transcript| {
+        let (party_one_first_message, _comm_witness, ec_key_pair_party1) =
+            party_one::KeyGenFirstMsg::create_commitments_with_rng(rng);
+        let (party_two_first_message, ec_key_pair_party2) =
+            party_two::KeyGenFirstMsg::create_with_rng(rng);
+        let keypair = party_one::PaillierKeyPair::generate_keypair_and_encrypted_share_with_rng(
+            &ec_key_pair_party1,
+            rng,
+        );
+
+        let (eph_party_two_first_message, eph_comm_witness, eph_ec_key_pair_party2) =
+            party_two::EphKeyGenFirstMsg::create_commitments_with_rng(rng);
+        let (eph_party_one_first_message, eph_ec_key_pair_party1) =
+            party_one::EphKeyGenFirstMsg::create_with_rng(rng);
+        let eph_party_two_second_message = party_two::EphKeyGenSecondMsg::verify_and_decommit(
+            eph_comm_witness,
+            &eph_party_one_first_message,
+        )
+        .expect("party1 DLog proof failed");
+        party_one::EphKeyGenSecondMsg::verify_commitments_and_dlog_proof(
+            &eph_party_two_first_message,
+            &eph_party_two_second_message,
+        )
+        .expect("failed to verify commitments and DLog proof");
+
+        let party2_private = party_two::Party2Private::set_private_key(&ec_key_pair_party2);
+        let message = BigInt::from(1234);
+        let partial_sig = party_two::PartialSig::compute_with_rng(
+            &keypair.ek,
+            &keypair.encrypted_share,
+            &party2_private,
+            &eph_ec_key_pair_party2,
+            &eph_party_one_first_message.public_share,
+            &message,
+            rng,
+        );
+
+        let party1_private =
+            party_one::Party1Private::set_private_key(&ec_key_pair_party1, &keypair);
+        let signature = party_one::Signature::compute(
+            &party1_private,
+            &partial_sig.c3,
+            &eph_ec_key_pair_party1,
+            &eph_party_two_second_message.comm_witness.public_share,
+        );
+        let pubkey =
+            party_one::compute_pubkey(&party1_private, &party_two_first_message.public_share);
+        party_one::verify(&signature, &pubkey, &message).expect("Invalid signature");
+
+        transcript.record("keygen_party_one_first_message", &party_one_first_message);
+        transcript.record("keygen_party_two_first_message", &party_two_first_message);
+        transcript.record("paillier_key_pair", &keypair);
+        transcript.record("eph_party_one_first_message", &eph_party_one_first_message);
+        transcript.record("eph_party_two_first_message", &eph_party_two_first_message);
+        transcript.record(
+            "eph_party_two_second_message",
+            &eph_party_two_second_message,
+        );
+        transcript.record("message", &message);
+        transcript.record("partial_sig", &partial_sig);
+        transcript.record("pubkey", &pubkey);
+        transcript.record("signature", &signature);
+    });
 }
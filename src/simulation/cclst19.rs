@@ -0,0 +1,189 @@
+/*
+    Multi-party ECDSA
+
+    Copyright 2018 by Kzen Networks
+
+    This file is part of Multi-party ECDSA library
+    (https://github.com/KZen-networks/multi-party-ecdsa)
+
+    Multi-party ECDSA is free software: you can redistribute
+    it and/or modify it under the terms of the GNU General Public
+    License as published by the Free Software Foundation, either
+    version 3 of the License, or (at your option) any later version.
+
+    @license GPL-3.0+ <https://github.com/KZen-networks/multi-party-ecdsa/blob/master/LICENSE>
+*/
+
+/// CCLST 2019 two-party key generation (with the CL-DL proof) and signing, party one on
+/// channel 0 and party two on channel 1.
+use class_group::primitives::cl_dl_lcm::CLDLProofPublicSetup;
+use curv::{BigInt, GE};
+use serde::{Deserialize, Serialize};
+
+use super::{run_two_party, Channel, SimulationError, Transcript};
+use crate::protocols::two_party_ecdsa::cclst_2019::{party_one, party_two};
+use crate::Error::InvalidKey;
+
+// public class group setup seed, the same one the tests use
+const HSMCL_SEED: &str = "314159265358979323846264338327950288419716939937510582097494459230781640628620899862803482534211706798214808651328230664709384460955058223172535940812848";
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PartyOneKey {
+    pub ec_key_pair: party_one::EcKeyPair,
+    pub hsmcl_key_pair: party_one::HSMCLKeyPair,
+    pub public_key: GE,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PartyTwoKey {
+    pub ec_key_pair: party_two::EcKeyPair,
+    pub hsmcl_public: party_two::HSMCLPublic,
+    pub public_key: GE,
+}
+
+pub fn keygen() -> Result<(PartyOneKey, PartyTwoKey, Transcript), SimulationError> {
+    run_two_party(keygen_party_one, keygen_party_two)
+}
+
+/// Signs message with the outputs of keygen, handing the keys back with the signature.
+pub fn sign(
+    party_one_key: PartyOneKey,
+    party_two_key: PartyTwoKey,
+    message: &BigInt,
+) -> Result<(PartyOneKey, PartyTwoKey, party_one::Signature, Transcript), SimulationError> {
+    let message_one = message.clone();
+    let message_two = message.clone();
+    let ((party_one_key, signature), party_two_key, transcript) = run_two_party(
+        move |channel| sign_party_one(party_one_key, &message_one, channel),
+        move |channel| sign_party_two(party_two_key, &message_two, channel),
+    )?;
+    Ok((party_one_key, party_two_key, signature, transcript))
+}
+
+fn keygen_party_one(channel: &mut Channel) -> Result<PartyOneKey, SimulationError> {
+    let (first_message, comm_witness, ec_key_pair) =
+        party_one::KeyGenFirstMsg::create_commitments();
+    channel.send("keygen1", 1, &first_message);
+    let party_two_first_message: party_two::KeyGenFirstMsg = channel.receive("keygen1", 1)?;
+
+    let second_message = channel.check(
+        "keygen2",
+        party_one::KeyGenSecondMsg::verify_and_decommit(
+            comm_witness,
+            &party_two_first_message.d_log_proof,
+        )
+        .map_err(|_| InvalidKey),
+    )?;
+    channel.send("keygen2", 1, &second_message);
+
+    let seed: BigInt = str::parse(HSMCL_SEED).unwrap();
+    let hsmcl_key_pair =
+        party_one::HSMCLKeyPair::generate_keypair_and_encrypted_share(&ec_key_pair, seed.clone());
+    let private = party_one::Party1Private::set_private_key(&ec_key_pair, &hsmcl_key_pair);
+    let cldl_proof =
+        party_one::HSMCLKeyPair::generate_zkcldl_proof(&hsmcl_key_pair, &private, seed);
+    channel.send("cldl", 1, &cldl_proof);
+
+    let public_key = party_one::compute_pubkey(&private, &party_two_first_message.public_share);
+    Ok(PartyOneKey {
+        ec_key_pair,
+        hsmcl_key_pair,
+        public_key,
+    })
+}
+
+fn keygen_party_two(channel: &mut Channel) -> Result<PartyTwoKey, SimulationError> {
+    let (first_message, ec_key_pair) = party_two::KeyGenFirstMsg::create();
+    channel.send("keygen1", 0, &first_message);
+    let party_one_first_message: party_one::KeyGenFirstMsg = channel.receive("keygen1", 0)?;
+    let party_one_second_message: party_one::KeyGenSecondMsg = channel.receive("keygen2", 0)?;
+    channel.check(
+        "keygen2",
+        party_two::KeyGenSecondMsg::verify_commitments_and_dlog_proof(
+            &party_one_first_message,
+            &party_one_second_message,
+        )
+        .map_err(|_| InvalidKey),
+    )?;
+
+    let cldl_proof: CLDLProofPublicSetup = channel.receive("cldl", 0)?;
+    let hsmcl_public = channel.check(
+        "cldl",
+        party_two::HSMCLPublic::verify_zkcldl_proof(cldl_proof).map_err(|_| InvalidKey),
+    )?;
+
+    let public_key = party_two::compute_pubkey(
+        &ec_key_pair,
+        &party_one_second_message.comm_witness.public_share,
+    );
+    Ok(PartyTwoKey {
+        ec_key_pair,
+        hsmcl_public,
+        public_key,
+    })
+}
+
+fn sign_party_one(
+    key: PartyOneKey,
+    message: &BigInt,
+    channel: &mut Channel,
+) -> Result<(PartyOneKey, party_one::Signature), SimulationError> {
+    let (eph_first_message, eph_ec_key_pair) = party_one::EphKeyGenFirstMsg::create();
+    channel.send("eph1", 1, &eph_first_message);
+    let eph_party_two_first: party_two::EphKeyGenFirstMsg = channel.receive("eph1", 1)?;
+    let eph_party_two_second: party_two::EphKeyGenSecondMsg = channel.receive("eph2", 1)?;
+    channel.check(
+        "eph2",
+        party_one::EphKeyGenSecondMsg::verify_commitments_and_dlog_proof(
+            &eph_party_two_first,
+            &eph_party_two_second,
+        )
+        .map_err(|_| InvalidKey),
+    )?;
+
+    let partial_sig: party_two::PartialSig = channel.receive("partial_sig", 1)?;
+    let private = party_one::Party1Private::set_private_key(&key.ec_key_pair, &key.hsmcl_key_pair);
+    let signature = party_one::Signature::compute(
+        &private,
+        partial_sig.c3,
+        &eph_ec_key_pair,
+        &eph_party_two_second.comm_witness.public_share,
+    );
+    channel.check(
+        "partial_sig",
+        party_one::verify(&signature, &key.public_key, message),
+    )?;
+    Ok((key, signature))
+}
+
+fn sign_party_two(
+    key: PartyTwoKey,
+    message: &BigInt,
+    channel: &mut Channel,
+) -> Result<PartyTwoKey, SimulationError> {
+    let (eph_first_message, eph_comm_witness, eph_ec_key_pair) =
+        party_two::EphKeyGenFirstMsg::create_commitments();
+    channel.send("eph1", 0, &eph_first_message);
+    let eph_party_one_first: party_one::EphKeyGenFirstMsg = channel.receive("eph1", 0)?;
+    let eph_second_message = channel.check(
+        "eph2",
+        party_two::EphKeyGenSecondMsg::verify_and_decommit(eph_comm_witness, &eph_party_one_first)
+            .map_err(|_| InvalidKey),
+    )?;
+    channel.send("eph2", 0, &eph_second_message);
+
+    let private = party_two::Party2Private::set_private_key(&key.ec_key_pair);
+    let hsmcl_public = party_two::HSMCLPublic::set(
+        &key.hsmcl_public.ek,
+        &key.hsmcl_public.encrypted_secret_share,
+    );
+    let partial_sig = party_two::PartialSig::compute(
+        hsmcl_public,
+        &private,
+        &eph_ec_key_pair,
+        &eph_party_one_first.public_share,
+        message,
+    );
+    channel.send("partial_sig", 0, &partial_sig);
+    Ok(key)
+}
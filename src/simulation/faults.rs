@@ -0,0 +1,171 @@
+/*
+    Multi-party ECDSA
+
+    Copyright 2018 by Kzen Networks
+
+    This file is part of Multi-party ECDSA library
+    (https://github.com/KZen-networks/multi-party-ecdsa)
+
+    Multi-party ECDSA is free software: you can redistribute
+    it and/or modify it under the terms of the GNU General Public
+    License as published by the Free Software Foundation, either
+    version 3 of the License, or (at your option) any later version.
+
+    @license GPL-3.0+ <https://github.com/KZen-networks/multi-party-ecdsa/blob/master/LICENSE>
+*/
+
+/// Network faults for the simulation: latency, reordering, duplicated and dropped messages and
+/// crashing parties. What happens to a message is drawn from an RNG seeded with the run's seed
+/// and the message's round, sender and receiver, so a seed gives the same faults however the
+/// threads happen to be scheduled.
+use std::sync::Mutex;
+use std::time::Duration;
+
+use curv::arithmetic::traits::Converter;
+use curv::cryptographic_primitives::hashing::hash_sha256::HSha256;
+use curv::cryptographic_primitives::hashing::traits::Hash;
+use curv::BigInt;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+
+use super::Message;
+
+#[derive(Clone, Debug)]
+pub struct FaultConfig {
+    pub seed: u64,
+    // every message is delayed by up to latency
+    pub latency: Duration,
+    // probabilities, per message and receiver
+    pub reorder: f64,
+    pub duplicate: f64,
+    pub drop: f64,
+    // (party, round): party goes silent instead of sending in round
+    pub crashes: Vec<(usize, String)>,
+    // how long a party waits for a single message
+    pub timeout: Duration,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FaultKind {
+    Dropped,
+    Duplicated,
+    // held back long enough for messages sent after it to arrive first
+    Reordered,
+    Crashed,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct FaultEvent {
+    pub round: String,
+    pub sender: usize,
+    pub receiver: Option<usize>, // None for crashes
+    pub kind: FaultKind,
+}
+
+pub(super) struct Injector {
+    config: FaultConfig,
+    events: Mutex<Vec<FaultEvent>>,
+}
+
+impl Default for FaultConfig {
+    fn default() -> Self {
+        FaultConfig {
+            seed: 0,
+            latency: Duration::from_millis(0),
+            reorder: 0.0,
+            duplicate: 0.0,
+            drop: 0.0,
+            crashes: Vec::new(),
+            timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+impl Injector {
+    pub fn new(config: FaultConfig) -> Self {
+        Injector {
+            config,
+            events: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn timeout(&self) -> Duration {
+        self.config.timeout
+    }
+
+    pub fn crash(&self, party: usize, round: &str) -> bool {
+        let crash = self
+            .config
+            .crashes
+            .iter()
+            .any(|(p, r)| *p == party && r == round);
+        if crash {
+            self.record(round, party, None, FaultKind::Crashed);
+        }
+        crash
+    }
+
+    // one delay per copy of message receiver gets, none if it is dropped
+    pub fn delays(&self, message: &Message, receiver: usize) -> Vec<Duration> {
+        let mut rng = self.rng(&message.round, message.sender, receiver);
+        let latency = self.config.latency.as_micros() as u64;
+        let mut delays = vec![Duration::from_micros(rng.gen_range(0, latency + 1))];
+        if rng.gen_bool(self.config.reorder) {
+            delays[0] += self.config.latency;
+            self.record(
+                &message.round,
+                message.sender,
+                Some(receiver),
+                FaultKind::Reordered,
+            );
+        }
+        if rng.gen_bool(self.config.duplicate) {
+            delays.push(Duration::from_micros(rng.gen_range(0, latency + 1)));
+            self.record(
+                &message.round,
+                message.sender,
+                Some(receiver),
+                FaultKind::Duplicated,
+            );
+        }
+        if rng.gen_bool(self.config.drop) {
+            delays.clear();
+            self.record(
+                &message.round,
+                message.sender,
+                Some(receiver),
+                FaultKind::Dropped,
+            );
+        }
+        delays
+    }
+
+    // sorted, the order they happened in depends on the threads
+    pub fn events(&self) -> Vec<FaultEvent> {
+        let mut events = self.events.lock().unwrap().clone();
+        events.sort();
+        events
+    }
+
+    fn record(&self, round: &str, sender: usize, receiver: Option<usize>, kind: FaultKind) {
+        self.events.lock().unwrap().push(FaultEvent {
+            round: round.to_string(),
+            sender,
+            receiver,
+            kind,
+        });
+    }
+
+    fn rng(&self, round: &str, sender: usize, receiver: usize) -> ChaCha20Rng {
+        let hash = HSha256::create_hash(&[
+            &BigInt::from(self.config.seed),
+            &BigInt::from(round.as_bytes()),
+            &BigInt::from(sender as u64),
+            &BigInt::from(receiver as u64),
+        ]);
+        let bytes = BigInt::to_vec(&hash);
+        let mut seed = [0u8; 32];
+        seed[32 - bytes.len()..].copy_from_slice(&bytes);
+        ChaCha20Rng::from_seed(seed)
+    }
+}
@@ -0,0 +1,409 @@
+#![allow(non_snake_case)]
+
+/*
+    Multi-party ECDSA
+
+    Copyright 2018 by Kzen Networks
+
+    This file is part of Multi-party ECDSA library
+    (https://github.com/KZen-networks/multi-party-ecdsa)
+
+    Multi-party ECDSA is free software: you can redistribute
+    it and/or modify it under the terms of the GNU General Public
+    License as published by the Free Software Foundation, either
+    version 3 of the License, or (at your option) any later version.
+
+    @license GPL-3.0+ <https://github.com/KZen-networks/multi-party-ecdsa/blob/master/LICENSE>
+*/
+
+/// GG18 key generation, signing and share refresh, one thread per party, following the
+/// gg18_*_client examples round by round. Secret shares travel in the clear since the
+/// channels never leave the process.
+use curv::cryptographic_primitives::proofs::sigma_correct_homomorphic_elgamal_enc::HomoELGamalProof;
+use curv::cryptographic_primitives::proofs::sigma_dlog::DLogProof;
+use curv::cryptographic_primitives::secret_sharing::feldman_vss::VerifiableSS;
+use curv::{BigInt, FE, GE};
+use paillier::EncryptionKey;
+use serde::{Deserialize, Serialize};
+
+use super::faults::FaultConfig;
+use super::{run, run_with_faults, Channel, FaultyRun, Simulation, SimulationError};
+use crate::protocols::multi_party_ecdsa::gg_2018::party_i::{
+    verify, KeyGenBroadcastMessage1, KeyGenDecommitMessage1, Keys, LocalSignature, Parameters,
+    PartyPrivate, Phase5ADecom1, Phase5Com1, Phase5Com2, Phase5DDecom2, SharedKeys,
+    SignBroadcastPhase1, SignContext, SignDecommitPhase1, SignKeys, SignatureRecid,
+};
+use crate::protocols::multi_party_ecdsa::gg_2018::party_id::{self, PartyId};
+use crate::protocols::multi_party_ecdsa::gg_2018::refresh;
+use crate::transport::identity::IdentityKey;
+use crate::utilities::mta::{MessageA, MessageB};
+
+/// What the keygen client saves to its key file.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct KeyShare {
+    pub party_keys: Keys,
+    pub shared_keys: SharedKeys,
+    pub party_id: PartyId,
+    pub vss_scheme: VerifiableSS,
+    pub paillier_key_vec: Vec<EncryptionKey>,
+    pub y_sum: GE,
+    pub identity: IdentityKey,
+    pub roster: Vec<GE>, // identity public keys by party_id - 1
+}
+
+pub fn keygen(t: u16, n: u16) -> Result<Simulation<KeyShare>, SimulationError> {
+    run(n as usize, move |channel| keygen_party(t, n, channel))
+}
+
+/// signers are the PartyId::index of the parties taking part, channel i runs signers[i].
+pub fn sign(
+    keys: &[KeyShare],
+    signers: &[usize],
+    message: &BigInt,
+) -> Result<Simulation<SignatureRecid>, SimulationError> {
+    let keys = signers
+        .iter()
+        .map(|&i| keys[i].clone())
+        .collect::<Vec<KeyShare>>();
+    let signers = signers.to_vec();
+    let message = message.clone();
+    run(signers.len(), move |channel| {
+        sign_party(&keys[channel.party()], &signers, &message, channel)
+    })
+}
+
+/// keygen over a network injecting the faults of config.
+pub fn keygen_with_faults(t: u16, n: u16, config: FaultConfig) -> FaultyRun<KeyShare> {
+    run_with_faults(n as usize, config, move |channel| {
+        keygen_party(t, n, channel)
+    })
+}
+
+/// sign over a network injecting the faults of config, crashes name channels, not party ids.
+pub fn sign_with_faults(
+    keys: &[KeyShare],
+    signers: &[usize],
+    message: &BigInt,
+    config: FaultConfig,
+) -> FaultyRun<SignatureRecid> {
+    let keys = signers
+        .iter()
+        .map(|&i| keys[i].clone())
+        .collect::<Vec<KeyShare>>();
+    let signers = signers.to_vec();
+    let message = message.clone();
+    run_with_faults(signers.len(), config, move |channel| {
+        sign_party(&keys[channel.party()], &signers, &message, channel)
+    })
+}
+
+/// Every party adds a sharing of zero to its share, as in gg18_update_client.
+pub fn refresh(keys: &[KeyShare]) -> Result<Simulation<KeyShare>, SimulationError> {
+    let keys = keys.to_vec();
+    let ids = keys
+        .iter()
+        .map(|key| key.party_id)
+        .collect::<Vec<PartyId>>();
+    run(keys.len(), move |channel| {
+        refresh_party(&keys[channel.party()], &ids, channel)
+    })
+}
+
+fn keygen_party(t: u16, n: u16, channel: &mut Channel) -> Result<KeyShare, SimulationError> {
+    let params = Parameters {
+        threshold: t,
+        share_count: n,
+    };
+    let party_num_int = channel.party() as u16 + 1;
+    let party_keys = Keys::create(party_num_int as usize);
+    let identity = IdentityKey::new();
+    let (bc_i, decom_i) = party_keys.phase1_broadcast_phase3_proof_of_correct_key();
+
+    // the roster of identity keys is fixed here, with the first commitments
+    let round1 = (bc_i, identity.public);
+    channel.broadcast("round1", &round1);
+    let round1_vec: Vec<(KeyGenBroadcastMessage1, GE)> = channel.gather("round1", round1)?;
+    let (bc1_vec, roster): (Vec<KeyGenBroadcastMessage1>, Vec<GE>) = round1_vec.into_iter().unzip();
+
+    channel.broadcast("round2", &decom_i);
+    let decom_vec: Vec<KeyGenDecommitMessage1> = channel.gather("round2", decom_i)?;
+    let point_vec = decom_vec.iter().map(|d| d.y_i).collect::<Vec<GE>>();
+    let (head, tail) = point_vec.split_at(1);
+    let y_sum = tail.iter().fold(head[0], |acc, x| acc + x);
+
+    let (vss_scheme, secret_shares, _index) = channel.check(
+        "round2",
+        party_keys.phase1_verify_com_phase3_verify_correct_key_phase2_distribute(
+            &params, &decom_vec, &bc1_vec,
+        ),
+    )?;
+
+    for (i, share) in secret_shares.iter().enumerate() {
+        if i != channel.party() {
+            channel.send("round3", i, share);
+        }
+    }
+    let own_share = secret_shares[channel.party()];
+    let party_shares: Vec<FE> = channel.gather("round3", own_share)?;
+
+    channel.broadcast("round4", &vss_scheme);
+    let vss_scheme_vec: Vec<VerifiableSS> = channel.gather("round4", vss_scheme)?;
+    let (shared_keys, dlog_proof) = channel.check(
+        "round4",
+        party_keys.phase2_verify_vss_construct_keypair_phase3_pok_dlog(
+            &params,
+            &point_vec,
+            &party_shares,
+            &vss_scheme_vec,
+            party_num_int as usize,
+        ),
+    )?;
+
+    channel.broadcast("round5", &dlog_proof);
+    let dlog_proof_vec: Vec<DLogProof> = channel.gather("round5", dlog_proof)?;
+    channel.check(
+        "round5",
+        Keys::verify_dlog_proofs(&params, &dlog_proof_vec, &point_vec),
+    )?;
+
+    // the joint scheme: sum of every party's commitments
+    let mut vss_commitments = vss_scheme_vec[0].commitments.clone();
+    for vss_scheme_j in &vss_scheme_vec[1..] {
+        for (c, c_j) in vss_commitments.iter_mut().zip(&vss_scheme_j.commitments) {
+            *c = *c + c_j;
+        }
+    }
+    let one_vss_scheme = VerifiableSS {
+        parameters: vss_scheme_vec[0].parameters.clone(),
+        commitments: vss_commitments,
+    };
+    let paillier_key_vec = bc1_vec
+        .iter()
+        .map(|bc1| bc1.e.clone())
+        .collect::<Vec<EncryptionKey>>();
+
+    Ok(KeyShare {
+        party_keys,
+        shared_keys,
+        party_id: PartyId::new(party_num_int).unwrap(),
+        vss_scheme: one_vss_scheme,
+        paillier_key_vec,
+        y_sum,
+        identity,
+        roster,
+    })
+}
+
+fn sign_party(
+    key: &KeyShare,
+    signers_vec: &[usize],
+    message: &BigInt,
+    channel: &mut Channel,
+) -> Result<SignatureRecid, SimulationError> {
+    let me = channel.party();
+    let others = (0..signers_vec.len())
+        .filter(|&j| j != me)
+        .collect::<Vec<usize>>();
+    let private = PartyPrivate::set_private(key.party_keys.clone(), key.shared_keys.clone());
+    let ids = channel.check(
+        "round1",
+        signers_vec
+            .iter()
+            .map(|&i| PartyId::from_index(i))
+            .collect::<Result<Vec<PartyId>, _>>(),
+    )?;
+    let sign_keys = channel.check(
+        "round1",
+        SignKeys::create_for_party(&private, &key.vss_scheme, key.party_id, &ids),
+    )?;
+
+    let context = SignContext::new(message, signers_vec, &key.y_sum);
+    let (com, decommit) = sign_keys.phase1_broadcast();
+    let (m_a_k, _) = MessageA::a(&sign_keys.k_i, &key.party_keys.ek);
+    let mine = (com, m_a_k, context.commitment());
+    channel.broadcast("round1", &mine);
+    let round1: Vec<(SignBroadcastPhase1, MessageA, BigInt)> = channel.gather("round1", mine)?;
+    let context_coms = round1.iter().map(|r| r.2.clone()).collect::<Vec<BigInt>>();
+    channel.check("round1", context.verify_commitments(&context_coms))?;
+
+    // MtA and MtAwc with every other signer, j answers the MessageA of signer j
+    let ek_vec = others
+        .iter()
+        .map(|&j| key.paillier_key_vec[signers_vec[j]].clone())
+        .collect::<Vec<EncryptionKey>>();
+    let m_a_vec = others
+        .iter()
+        .map(|&j| round1[j].1.clone())
+        .collect::<Vec<MessageA>>();
+    let m_b_gamma_vec = MessageB::b_vec(&sign_keys.gamma_i, &ek_vec, &m_a_vec);
+    let m_b_w_vec = MessageB::b_vec(&sign_keys.w_i, &ek_vec, &m_a_vec);
+    for (k, &j) in others.iter().enumerate() {
+        channel.send("round2", j, &(&m_b_gamma_vec[k].0, &m_b_w_vec[k].0));
+    }
+    let beta_vec = m_b_gamma_vec.iter().map(|b| b.1).collect::<Vec<FE>>();
+    let ni_vec = m_b_w_vec.iter().map(|b| b.1).collect::<Vec<FE>>();
+
+    let mut m_b_gamma_rec_vec = Vec::new();
+    let mut m_b_w_rec_vec = Vec::new();
+    for &j in &others {
+        let (m_b_gamma, m_b_w): (MessageB, MessageB) = channel.receive("round2", j)?;
+        m_b_gamma_rec_vec.push(m_b_gamma);
+        m_b_w_rec_vec.push(m_b_w);
+    }
+    let alpha_vec = channel.check(
+        "round2",
+        MessageB::verify_proofs_get_alpha_vec(
+            &m_b_gamma_rec_vec,
+            &key.party_keys.dk,
+            &sign_keys.k_i,
+        ),
+    )?;
+    let other_indices = others
+        .iter()
+        .map(|&j| signers_vec[j])
+        .collect::<Vec<usize>>();
+    let miu_vec = channel.check(
+        "round2",
+        MessageB::verify_proofs_get_alpha_wc_vec(
+            &m_b_w_rec_vec,
+            &key.party_keys.dk,
+            &sign_keys.k_i,
+            &key.vss_scheme,
+            &other_indices,
+            signers_vec,
+        ),
+    )?;
+
+    let delta_i = sign_keys.phase2_delta_i(&alpha_vec, &beta_vec);
+    let sigma = sign_keys.phase2_sigma_i(&miu_vec, &ni_vec);
+    channel.broadcast("round3", &delta_i);
+    let delta_vec: Vec<FE> = channel.gather("round3", delta_i)?;
+    let delta_inv = SignKeys::phase3_reconstruct_delta(&delta_vec);
+
+    channel.broadcast("round4", &decommit);
+    let mut decommit_vec: Vec<SignDecommitPhase1> = channel.gather("round4", decommit)?;
+    let decomm_i = decommit_vec.remove(me);
+    let bc1_vec = others
+        .iter()
+        .map(|&j| round1[j].0.clone())
+        .collect::<Vec<SignBroadcastPhase1>>();
+    let b_proof_vec = m_b_gamma_rec_vec
+        .iter()
+        .map(|m_b| &m_b.b_proof)
+        .collect::<Vec<&DLogProof>>();
+    let R = channel.check(
+        "round4",
+        SignKeys::phase4(&delta_inv, &b_proof_vec, decommit_vec, &bc1_vec),
+    )?;
+    let R = R + decomm_i.g_gamma_i * delta_inv;
+
+    let local_sig =
+        LocalSignature::phase5_local_sig(&sign_keys.k_i, message, &R, &sigma, &key.y_sum);
+    let (phase5_com, phase_5a_decom, helgamal_proof, dlog_proof_rho) =
+        local_sig.phase5a_broadcast_5b_zkproof();
+
+    channel.broadcast("round5", &phase5_com);
+    let mut commit5a_vec: Vec<Phase5Com1> = channel.gather("round5", phase5_com)?;
+
+    let round6_i = (phase_5a_decom.clone(), helgamal_proof, dlog_proof_rho);
+    channel.broadcast("round6", &round6_i);
+    let mut round6: Vec<(Phase5ADecom1, HomoELGamalProof, DLogProof)> =
+        channel.gather("round6", round6_i)?;
+    let phase_5a_decomm_vec_includes_i = round6
+        .iter()
+        .map(|r| r.0.clone())
+        .collect::<Vec<Phase5ADecom1>>();
+    round6.remove(me);
+    commit5a_vec.remove(me);
+    let phase_5a_decomm_vec = round6
+        .iter()
+        .map(|r| r.0.clone())
+        .collect::<Vec<Phase5ADecom1>>();
+    let phase_5a_elgamal_vec = round6
+        .iter()
+        .map(|r| r.1.clone())
+        .collect::<Vec<HomoELGamalProof>>();
+    let phase_5a_dlog_vec = round6
+        .iter()
+        .map(|r| r.2.clone())
+        .collect::<Vec<DLogProof>>();
+    let (phase5_com2, phase_5d_decom2) = channel.check(
+        "round6",
+        local_sig.phase5c(
+            &phase_5a_decomm_vec,
+            &commit5a_vec,
+            &phase_5a_elgamal_vec,
+            &phase_5a_dlog_vec,
+            &phase_5a_decom.V_i,
+            &R,
+        ),
+    )?;
+
+    channel.broadcast("round7", &phase5_com2);
+    let commit5c_vec: Vec<Phase5Com2> = channel.gather("round7", phase5_com2)?;
+
+    channel.broadcast("round8", &phase_5d_decom2);
+    let decommit5d_vec: Vec<Phase5DDecom2> = channel.gather("round8", phase_5d_decom2)?;
+    let s_i = channel.check(
+        "round8",
+        local_sig.phase5d(
+            &decommit5d_vec,
+            &commit5c_vec,
+            &phase_5a_decomm_vec_includes_i,
+        ),
+    )?;
+
+    channel.broadcast("round9", &s_i);
+    let mut s_i_vec: Vec<FE> = channel.gather("round9", s_i)?;
+    s_i_vec.remove(me);
+    let sig = channel.check("round9", local_sig.output_signature(&s_i_vec))?;
+    channel.check("round9", verify(&sig, &key.y_sum, message))?;
+    Ok(sig)
+}
+
+// channel i runs the party with ids[i]
+fn refresh_party(
+    key: &KeyShare,
+    ids: &[PartyId],
+    channel: &mut Channel,
+) -> Result<KeyShare, SimulationError> {
+    channel.check(
+        "refresh_commitments",
+        party_id::indices(ids, &key.vss_scheme),
+    )?;
+
+    let (subshares, commitments) = refresh::deal_refresh(&key.vss_scheme, ids);
+    channel.broadcast("refresh_commitments", &commitments);
+    let commitments_vec: Vec<Vec<GE>> = channel.gather("refresh_commitments", commitments)?;
+
+    for (i, subshare) in subshares.iter().enumerate() {
+        if i != channel.party() {
+            channel.send("refresh_subshares", i, subshare);
+        }
+    }
+    let own = subshares[channel.party()];
+    let subshares_vec: Vec<FE> = channel.gather("refresh_subshares", own)?;
+
+    let received = subshares_vec
+        .into_iter()
+        .zip(commitments_vec)
+        .collect::<Vec<(FE, Vec<GE>)>>();
+    let (x_i, vss_scheme) = channel.check(
+        "refresh_subshares",
+        refresh::apply_refresh(
+            key.party_id,
+            &key.shared_keys.x_i,
+            &key.vss_scheme,
+            &received,
+        ),
+    )?;
+
+    Ok(KeyShare {
+        shared_keys: SharedKeys {
+            y: key.shared_keys.y,
+            x_i,
+        },
+        vss_scheme,
+        ..key.clone()
+    })
+}
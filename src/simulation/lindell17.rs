@@ -0,0 +1,245 @@
+/*
+    Multi-party ECDSA
+
+    Copyright 2018 by Kzen Networks
+
+    This file is part of Multi-party ECDSA library
+    (https://github.com/KZen-networks/multi-party-ecdsa)
+
+    Multi-party ECDSA is free software: you can redistribute
+    it and/or modify it under the terms of the GNU General Public
+    License as published by the Free Software Foundation, either
+    version 3 of the License, or (at your option) any later version.
+
+    @license GPL-3.0+ <https://github.com/KZen-networks/multi-party-ecdsa/blob/master/LICENSE>
+*/
+
+/// Lindell 2017 two-party key generation (including the Paillier key proofs and PDL) and
+/// signing, party one on channel 0 and party two on channel 1.
+use curv::{BigInt, GE};
+use paillier::EncryptionKey;
+use serde::{Deserialize, Serialize};
+use zk_paillier::zkproofs::NICorrectKeyProof;
+
+use super::{run_two_party, Channel, SimulationError, Transcript};
+use crate::protocols::two_party_ecdsa::lindell_2017::{party_one, party_two};
+use crate::utilities::zk_pdl::{
+    PDLProverFirstMessage, PDLProverSecondMessage, PDLVerifierFirstMessage,
+    PDLVerifierSecondMessage,
+};
+use crate::Error::InvalidKey;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PartyOneKey {
+    pub ec_key_pair: party_one::EcKeyPair,
+    pub paillier_key_pair: party_one::PaillierKeyPair,
+    pub public_key: GE,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PartyTwoKey {
+    pub ec_key_pair: party_two::EcKeyPair,
+    pub paillier_public: party_two::PaillierPublic,
+    pub public_key: GE,
+}
+
+pub fn keygen() -> Result<(PartyOneKey, PartyTwoKey, Transcript), SimulationError> {
+    run_two_party(keygen_party_one, keygen_party_two)
+}
+
+/// Signs message with the outputs of keygen, handing the keys back with the signature.
+pub fn sign(
+    party_one_key: PartyOneKey,
+    party_two_key: PartyTwoKey,
+    message: &BigInt,
+) -> Result<(PartyOneKey, PartyTwoKey, party_one::Signature, Transcript), SimulationError> {
+    let message_one = message.clone();
+    let message_two = message.clone();
+    let ((party_one_key, signature), party_two_key, transcript) = run_two_party(
+        move |channel| sign_party_one(party_one_key, &message_one, channel),
+        move |channel| sign_party_two(party_two_key, &message_two, channel),
+    )?;
+    Ok((party_one_key, party_two_key, signature, transcript))
+}
+
+fn keygen_party_one(channel: &mut Channel) -> Result<PartyOneKey, SimulationError> {
+    let (first_message, comm_witness, ec_key_pair) =
+        party_one::KeyGenFirstMsg::create_commitments();
+    channel.send("keygen1", 1, &first_message);
+    let party_two_first_message: party_two::KeyGenFirstMsg = channel.receive("keygen1", 1)?;
+
+    let second_message = channel.check(
+        "keygen2",
+        party_one::KeyGenSecondMsg::verify_and_decommit(
+            comm_witness,
+            &party_two_first_message.d_log_proof,
+        )
+        .map_err(|_| InvalidKey),
+    )?;
+    channel.send("keygen2", 1, &second_message);
+
+    let paillier_key_pair =
+        party_one::PaillierKeyPair::generate_keypair_and_encrypted_share(&ec_key_pair);
+    let correct_key_proof =
+        party_one::PaillierKeyPair::generate_ni_proof_correct_key(&paillier_key_pair);
+    channel.send(
+        "paillier",
+        1,
+        &(
+            &paillier_key_pair.ek,
+            &paillier_key_pair.encrypted_share,
+            correct_key_proof,
+        ),
+    );
+
+    let private = party_one::Party1Private::set_private_key(&ec_key_pair, &paillier_key_pair);
+    let pdl_verifier_first: PDLVerifierFirstMessage = channel.receive("pdl1", 1)?;
+    let (pdl_prover_first, pdl_state, _statement, pdl_witness) =
+        party_one::PaillierKeyPair::pdl_first_message(
+            &private,
+            &pdl_verifier_first,
+            &paillier_key_pair,
+        );
+    channel.send("pdl2", 1, &pdl_prover_first);
+    let pdl_verifier_second: PDLVerifierSecondMessage = channel.receive("pdl3", 1)?;
+    let pdl_prover_second = channel.check(
+        "pdl4",
+        party_one::PaillierKeyPair::pdl_second_message(
+            &pdl_verifier_first,
+            &pdl_verifier_second,
+            &pdl_witness,
+            &pdl_state,
+        )
+        .map_err(|_| InvalidKey),
+    )?;
+    channel.send("pdl4", 1, &pdl_prover_second);
+
+    let public_key = party_one::compute_pubkey(&private, &party_two_first_message.public_share);
+    Ok(PartyOneKey {
+        ec_key_pair,
+        paillier_key_pair,
+        public_key,
+    })
+}
+
+fn keygen_party_two(channel: &mut Channel) -> Result<PartyTwoKey, SimulationError> {
+    let (first_message, ec_key_pair) = party_two::KeyGenFirstMsg::create();
+    channel.send("keygen1", 0, &first_message);
+    let party_one_first_message: party_one::KeyGenFirstMsg = channel.receive("keygen1", 0)?;
+    let party_one_second_message: party_one::KeyGenSecondMsg = channel.receive("keygen2", 0)?;
+    channel.check(
+        "keygen2",
+        party_two::KeyGenSecondMsg::verify_commitments_and_dlog_proof(
+            &party_one_first_message,
+            &party_one_second_message,
+        )
+        .map_err(|_| InvalidKey),
+    )?;
+
+    let (ek, encrypted_secret_share, correct_key_proof): (
+        EncryptionKey,
+        BigInt,
+        NICorrectKeyProof,
+    ) = channel.receive("paillier", 0)?;
+    channel.check(
+        "paillier",
+        party_two::PaillierPublic::verify_ni_proof_correct_key(correct_key_proof, &ek)
+            .map_err(|_| InvalidKey),
+    )?;
+    let paillier_public = party_two::PaillierPublic {
+        ek,
+        encrypted_secret_share,
+    };
+
+    let party_one_public_share = party_one_second_message.comm_witness.public_share;
+    let (pdl_verifier_first, mut pdl_state, pdl_statement) =
+        paillier_public.pdl_first_message(&party_one_public_share);
+    channel.send("pdl1", 0, &pdl_verifier_first);
+    let pdl_prover_first: PDLProverFirstMessage = channel.receive("pdl2", 0)?;
+    let pdl_verifier_second = channel.check(
+        "pdl3",
+        party_two::PaillierPublic::pdl_second_message(
+            &pdl_prover_first,
+            &pdl_statement,
+            &mut pdl_state,
+        )
+        .map_err(|_| InvalidKey),
+    )?;
+    channel.send("pdl3", 0, &pdl_verifier_second);
+    let pdl_prover_second: PDLProverSecondMessage = channel.receive("pdl4", 0)?;
+    channel.check(
+        "pdl4",
+        party_two::PaillierPublic::pdl_finalize(&pdl_prover_first, &pdl_prover_second, &pdl_state)
+            .map_err(|_| InvalidKey),
+    )?;
+
+    let public_key = party_two::compute_pubkey(&ec_key_pair, &party_one_public_share);
+    Ok(PartyTwoKey {
+        ec_key_pair,
+        paillier_public,
+        public_key,
+    })
+}
+
+fn sign_party_one(
+    key: PartyOneKey,
+    message: &BigInt,
+    channel: &mut Channel,
+) -> Result<(PartyOneKey, party_one::Signature), SimulationError> {
+    let (eph_first_message, eph_ec_key_pair) = party_one::EphKeyGenFirstMsg::create();
+    channel.send("eph1", 1, &eph_first_message);
+    let eph_party_two_first: party_two::EphKeyGenFirstMsg = channel.receive("eph1", 1)?;
+    let eph_party_two_second: party_two::EphKeyGenSecondMsg = channel.receive("eph2", 1)?;
+    channel.check(
+        "eph2",
+        party_one::EphKeyGenSecondMsg::verify_commitments_and_dlog_proof(
+            &eph_party_two_first,
+            &eph_party_two_second,
+        )
+        .map_err(|_| InvalidKey),
+    )?;
+
+    let partial_sig: party_two::PartialSig = channel.receive("partial_sig", 1)?;
+    let private =
+        party_one::Party1Private::set_private_key(&key.ec_key_pair, &key.paillier_key_pair);
+    let signature = party_one::Signature::compute(
+        &private,
+        &partial_sig.c3,
+        &eph_ec_key_pair,
+        &eph_party_two_second.comm_witness.public_share,
+    );
+    channel.check(
+        "partial_sig",
+        party_one::verify(&signature, &key.public_key, message),
+    )?;
+    Ok((key, signature))
+}
+
+fn sign_party_two(
+    key: PartyTwoKey,
+    message: &BigInt,
+    channel: &mut Channel,
+) -> Result<PartyTwoKey, SimulationError> {
+    let (eph_first_message, eph_comm_witness, eph_ec_key_pair) =
+        party_two::EphKeyGenFirstMsg::create_commitments();
+    channel.send("eph1", 0, &eph_first_message);
+    let eph_party_one_first: party_one::EphKeyGenFirstMsg = channel.receive("eph1", 0)?;
+    let eph_second_message = channel.check(
+        "eph2",
+        party_two::EphKeyGenSecondMsg::verify_and_decommit(eph_comm_witness, &eph_party_one_first)
+            .map_err(|_| InvalidKey),
+    )?;
+    channel.send("eph2", 0, &eph_second_message);
+
+    let private = party_two::Party2Private::set_private_key(&key.ec_key_pair);
+    let partial_sig = party_two::PartialSig::compute(
+        &key.paillier_public.ek,
+        &key.paillier_public.encrypted_secret_share,
+        &private,
+        &eph_ec_key_pair,
+        &eph_party_one_first.public_share,
+        message,
+    );
+    channel.send("partial_sig", 0, &partial_sig);
+    Ok(key)
+}
@@ -0,0 +1,480 @@
+/*
+    Multi-party ECDSA
+
+    Copyright 2018 by Kzen Networks
+
+    This file is part of Multi-party ECDSA library
+    (https://github.com/KZen-networks/multi-party-ecdsa)
+
+    Multi-party ECDSA is free software: you can redistribute
+    it and/or modify it under the terms of the GNU General Public
+    License as published by the Free Software Foundation, either
+    version 3 of the License, or (at your option) any later version.
+
+    @license GPL-3.0+ <https://github.com/KZen-networks/multi-party-ecdsa/blob/master/LICENSE>
+*/
+
+/// In-process runs of the protocols: every party gets its own thread and talks to the others
+/// over in-memory channels instead of sm_manager. Messages are serialized to json like the
+/// example clients do, and every message sent is kept in a transcript grouped by round.
+use std::collections::HashSet;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use self::faults::{FaultConfig, FaultEvent, Injector};
+use crate::Error;
+
+#[cfg(feature = "cclst")]
+pub mod cclst19;
+pub mod faults;
+pub mod gg18;
+pub mod lindell17;
+
+// how often a waiting party checks whether another party gave up
+const ABORT_POLL: Duration = Duration::from_millis(10);
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Message {
+    pub round: String,
+    pub sender: usize,
+    pub receiver: Option<usize>, // None for broadcasts
+    pub body: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Round {
+    pub name: String,
+    pub messages: Vec<Message>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Transcript {
+    pub rounds: Vec<Round>,
+}
+
+#[derive(Debug)]
+pub struct Simulation<O> {
+    pub outputs: Vec<O>,
+    pub transcript: Transcript,
+}
+
+/// A run under injected faults keeps every party's own result, the faults that were
+/// injected and the transcript of what the parties sent.
+#[derive(Debug)]
+pub struct FaultyRun<O> {
+    pub results: Vec<Result<O, SimulationError>>,
+    pub transcript: Transcript,
+    pub faults: Vec<FaultEvent>,
+}
+
+// a message on its way, the receiver only takes it from due on
+struct Delivery {
+    message: Message,
+    due: Instant,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SimulationError {
+    // party failed a protocol check in round
+    Protocol {
+        party: usize,
+        round: String,
+        error: Error,
+    },
+    // party could not decode a message it received in round
+    Malformed {
+        party: usize,
+        round: String,
+    },
+    // party stopped waiting in round because another party failed
+    Aborted {
+        party: usize,
+        round: String,
+    },
+    // party gave up waiting for sender's message in round
+    Timeout {
+        party: usize,
+        round: String,
+        sender: usize,
+    },
+    // party was made to crash before sending in round
+    Crashed {
+        party: usize,
+        round: String,
+    },
+    Panicked {
+        party: usize,
+    },
+}
+
+impl Transcript {
+    // rounds in the order they were first used, messages within a round by sender then receiver
+    fn from_log(log: Vec<Message>) -> Self {
+        let mut rounds: Vec<Round> = Vec::new();
+        for message in log {
+            match rounds.iter_mut().find(|r| r.name == message.round) {
+                Some(round) => round.messages.push(message),
+                None => rounds.push(Round {
+                    name: message.round.clone(),
+                    messages: vec![message],
+                }),
+            }
+        }
+        for round in rounds.iter_mut() {
+            round.messages.sort_by_key(|m| (m.sender, m.receiver));
+        }
+        Transcript { rounds }
+    }
+
+    pub fn round(&self, name: &str) -> Option<&Round> {
+        self.rounds.iter().find(|r| r.name == name)
+    }
+}
+
+/// One party's end of the in-memory network. Parties are numbered 0..parties.
+pub struct Channel {
+    party: usize,
+    parties: usize,
+    senders: Vec<Sender<Delivery>>,
+    receiver: Receiver<Delivery>,
+    // messages for a later round that arrived early, or that are not due yet
+    pending: Vec<Delivery>,
+    // (round, sender) of every message taken so far, later copies are dropped
+    received: HashSet<(String, usize)>,
+    log: Arc<Mutex<Vec<Message>>>,
+    aborted: Arc<AtomicBool>,
+    faults: Option<Arc<Injector>>,
+    crashed: bool,
+}
+
+impl Channel {
+    pub fn party(&self) -> usize {
+        self.party
+    }
+
+    pub fn parties(&self) -> usize {
+        self.parties
+    }
+
+    pub fn broadcast<T: Serialize>(&mut self, round: &str, msg: &T) {
+        if self.crash(round) {
+            return;
+        }
+        let message = self.message(round, None, msg);
+        for i in 0..self.parties {
+            if i != self.party {
+                self.deliver(i, &message);
+            }
+        }
+        self.log.lock().unwrap().push(message);
+    }
+
+    pub fn send<T: Serialize>(&mut self, round: &str, receiver: usize, msg: &T) {
+        if self.crash(round) {
+            return;
+        }
+        let message = self.message(round, Some(receiver), msg);
+        self.deliver(receiver, &message);
+        self.log.lock().unwrap().push(message);
+    }
+
+    // the message sender sent to this party in round, broadcast or not
+    pub fn receive<T: DeserializeOwned>(
+        &mut self,
+        round: &str,
+        sender: usize,
+    ) -> Result<T, SimulationError> {
+        if self.crashed {
+            return Err(SimulationError::Crashed {
+                party: self.party,
+                round: round.to_string(),
+            });
+        }
+        let deadline = self.faults.as_ref().map(|f| Instant::now() + f.timeout());
+        let message = loop {
+            let now = Instant::now();
+            if let Some(pos) = self.pending.iter().position(|d| {
+                d.message.round == round && d.message.sender == sender && d.due <= now
+            }) {
+                break self.pending.remove(pos).message;
+            }
+            if self.aborted.load(Ordering::SeqCst) {
+                return Err(SimulationError::Aborted {
+                    party: self.party,
+                    round: round.to_string(),
+                });
+            }
+            if deadline.map_or(false, |deadline| now >= deadline) {
+                return Err(SimulationError::Timeout {
+                    party: self.party,
+                    round: round.to_string(),
+                    sender,
+                });
+            }
+            // wake up in time for the next pending message falling due
+            let wait = self
+                .pending
+                .iter()
+                .map(|d| d.due.saturating_duration_since(now))
+                .fold(ABORT_POLL, Duration::min);
+            match self.receiver.recv_timeout(wait) {
+                Ok(d) => {
+                    let key = (d.message.round.clone(), d.message.sender);
+                    let duplicate = self.received.contains(&key)
+                        || self
+                            .pending
+                            .iter()
+                            .any(|p| p.message.round == key.0 && p.message.sender == key.1);
+                    if !duplicate {
+                        self.pending.push(d);
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => continue,
+                // cannot happen while this channel holds a sender to itself
+                Err(RecvTimeoutError::Disconnected) => unreachable!(),
+            }
+        };
+        self.received.insert((round.to_string(), sender));
+        serde_json::from_str(&message.body).map_err(|_| SimulationError::Malformed {
+            party: self.party,
+            round: round.to_string(),
+        })
+    }
+
+    // one message from every party in round, ordered by party, with own in this party's place
+    pub fn gather<T: DeserializeOwned>(
+        &mut self,
+        round: &str,
+        own: T,
+    ) -> Result<Vec<T>, SimulationError> {
+        let mut own = Some(own);
+        (0..self.parties)
+            .map(|i| {
+                if i == self.party {
+                    Ok(own.take().unwrap())
+                } else {
+                    self.receive(round, i)
+                }
+            })
+            .collect()
+    }
+
+    pub fn check<T>(&self, round: &str, result: Result<T, Error>) -> Result<T, SimulationError> {
+        result.map_err(|error| SimulationError::Protocol {
+            party: self.party,
+            round: round.to_string(),
+            error,
+        })
+    }
+
+    // whether this party is made to crash before sending in round, it stays down afterwards
+    fn crash(&mut self, round: &str) -> bool {
+        if !self.crashed {
+            if let Some(faults) = &self.faults {
+                self.crashed = faults.crash(self.party, round);
+            }
+        }
+        self.crashed
+    }
+
+    fn deliver(&self, receiver: usize, message: &Message) {
+        let now = Instant::now();
+        let delays = match &self.faults {
+            Some(faults) => faults.delays(message, receiver),
+            None => vec![Duration::from_secs(0)],
+        };
+        for delay in delays {
+            // a party that already finished has dropped its receiver
+            let _ = self.senders[receiver].send(Delivery {
+                message: message.clone(),
+                due: now + delay,
+            });
+        }
+    }
+
+    fn message<T: Serialize>(&self, round: &str, receiver: Option<usize>, msg: &T) -> Message {
+        Message {
+            round: round.to_string(),
+            sender: self.party,
+            receiver,
+            body: serde_json::to_string(msg).expect("unserializable message"),
+        }
+    }
+}
+
+/// Fully connected network of parties channels sharing one transcript.
+pub fn network(parties: usize) -> (Vec<Channel>, Arc<Mutex<Vec<Message>>>) {
+    network_with_faults(parties, None)
+}
+
+fn network_with_faults(
+    parties: usize,
+    faults: Option<Arc<Injector>>,
+) -> (Vec<Channel>, Arc<Mutex<Vec<Message>>>) {
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let aborted = Arc::new(AtomicBool::new(false));
+    let (senders, receivers): (Vec<_>, Vec<_>) = (0..parties).map(|_| channel()).unzip();
+    let channels = receivers
+        .into_iter()
+        .enumerate()
+        .map(|(party, receiver)| Channel {
+            party,
+            parties,
+            senders: senders.clone(),
+            receiver,
+            pending: Vec::new(),
+            received: HashSet::new(),
+            log: log.clone(),
+            aborted: aborted.clone(),
+            faults: faults.clone(),
+            crashed: false,
+        })
+        .collect();
+    (channels, log)
+}
+
+/// Runs party on its own thread. When it fails or panics every other party waiting on the
+/// same network stops with SimulationError::Aborted. A crashed party just goes silent.
+pub fn spawn<O, F>(mut channel: Channel, party: F) -> JoinHandle<Result<O, SimulationError>>
+where
+    O: Send + 'static,
+    F: FnOnce(&mut Channel) -> Result<O, SimulationError> + Send + 'static,
+{
+    thread::spawn(move || {
+        let index = channel.party;
+        let result = catch_unwind(AssertUnwindSafe(|| party(&mut channel)))
+            .unwrap_or_else(|_| Err(SimulationError::Panicked { party: index }));
+        match result {
+            Ok(_) | Err(SimulationError::Crashed { .. }) => (),
+            Err(_) => channel.aborted.store(true, Ordering::SeqCst),
+        }
+        result
+    })
+}
+
+/// Waits for every party and returns the outputs in party order, or the first failure that
+/// did not just follow from another party's.
+pub fn join<O>(
+    handles: Vec<JoinHandle<Result<O, SimulationError>>>,
+) -> Result<Vec<O>, SimulationError> {
+    let results = handles
+        .into_iter()
+        .enumerate()
+        .map(|(party, handle)| {
+            handle
+                .join()
+                .unwrap_or_else(|_| Err(SimulationError::Panicked { party }))
+        })
+        .collect::<Vec<_>>();
+    let cause = results
+        .iter()
+        .filter_map(|r| r.as_ref().err())
+        .find(|e| match e {
+            SimulationError::Aborted { .. } => false,
+            _ => true,
+        });
+    if let Some(error) = cause {
+        return Err(error.clone());
+    }
+    results.into_iter().collect()
+}
+
+/// Runs party once per channel of a network of parties, each on its own thread.
+pub fn run<O, F>(parties: usize, party: F) -> Result<Simulation<O>, SimulationError>
+where
+    O: Send + 'static,
+    F: Fn(&mut Channel) -> Result<O, SimulationError> + Send + Sync + 'static,
+{
+    let party = Arc::new(party);
+    let (channels, log) = network(parties);
+    let handles = channels
+        .into_iter()
+        .map(|channel| {
+            let party = party.clone();
+            spawn(channel, move |channel| party(channel))
+        })
+        .collect();
+    let outputs = join(handles)?;
+    let log = log.lock().unwrap().clone();
+    Ok(Simulation {
+        outputs,
+        transcript: Transcript::from_log(log),
+    })
+}
+
+/// Like run, with the faults of config injected into the network. Every party waits at most
+/// config.timeout for each message, and its own result is kept whether or not it failed.
+pub fn run_with_faults<O, F>(parties: usize, config: FaultConfig, party: F) -> FaultyRun<O>
+where
+    O: Send + 'static,
+    F: Fn(&mut Channel) -> Result<O, SimulationError> + Send + Sync + 'static,
+{
+    let party = Arc::new(party);
+    let injector = Arc::new(Injector::new(config));
+    let (channels, log) = network_with_faults(parties, Some(injector.clone()));
+    let handles = channels
+        .into_iter()
+        .map(|channel| {
+            let party = party.clone();
+            spawn(channel, move |channel| party(channel))
+        })
+        .collect::<Vec<_>>();
+    let results = handles
+        .into_iter()
+        .enumerate()
+        .map(|(party, handle)| {
+            handle
+                .join()
+                .unwrap_or_else(|_| Err(SimulationError::Panicked { party }))
+        })
+        .collect();
+    let log = log.lock().unwrap().clone();
+    FaultyRun {
+        results,
+        transcript: Transcript::from_log(log),
+        faults: injector.events(),
+    }
+}
+
+/// Runs a two-party protocol, party_one on channel 0 and party_two on channel 1.
+pub fn run_two_party<A, B, F1, F2>(
+    party_one: F1,
+    party_two: F2,
+) -> Result<(A, B, Transcript), SimulationError>
+where
+    A: Send + 'static,
+    B: Send + 'static,
+    F1: FnOnce(&mut Channel) -> Result<A, SimulationError> + Send + 'static,
+    F2: FnOnce(&mut Channel) -> Result<B, SimulationError> + Send + 'static,
+{
+    let (mut channels, log) = network(2);
+    let channel_two = channels.pop().unwrap();
+    let channel_one = channels.pop().unwrap();
+    let handle_one = spawn(channel_one, party_one);
+    let handle_two = spawn(channel_two, party_two);
+    // join both before reporting, so a failure of either is blamed correctly
+    let result_one = handle_one
+        .join()
+        .unwrap_or_else(|_| Err(SimulationError::Panicked { party: 0 }));
+    let result_two = handle_two
+        .join()
+        .unwrap_or_else(|_| Err(SimulationError::Panicked { party: 1 }));
+    let (one, two) = match (result_one, result_two) {
+        (Ok(one), Ok(two)) => (one, two),
+        (Err(SimulationError::Aborted { .. }), Err(e)) | (Err(e), _) | (_, Err(e)) => {
+            return Err(e)
+        }
+    };
+    let log = log.lock().unwrap().clone();
+    Ok((one, two, Transcript::from_log(log)))
+}
+
+#[cfg(test)]
+mod test;
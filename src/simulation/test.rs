@@ -0,0 +1,287 @@
+use crate::protocols::multi_party_ecdsa::gg_2018::party_i::verify;
+use crate::simulation::faults::{FaultConfig, FaultKind};
+use crate::simulation::{gg18, lindell17, run, run_with_faults, Channel, SimulationError};
+use crate::Error::{DuplicatePartyId, InvalidKey, MessageMismatch, PublicShareMismatch};
+use curv::elliptic::curves::traits::ECScalar;
+use curv::{BigInt, FE};
+use std::time::Duration;
+
+#[test]
+fn test_gather_and_transcript() {
+    let simulation = run(3, |channel: &mut Channel| {
+        let me = channel.party();
+        channel.broadcast("hello", &me);
+        let all: Vec<usize> = channel.gather("hello", me)?;
+        // send to the next party, receive from the previous one
+        let parties = channel.parties();
+        channel.send("ring", (me + 1) % parties, &(me * 10));
+        let from_previous: usize = channel.receive("ring", (me + parties - 1) % parties)?;
+        Ok((all, from_previous))
+    })
+    .expect("honest run failed");
+
+    for (i, (all, from_previous)) in simulation.outputs.iter().enumerate() {
+        assert_eq!(all, &vec![0, 1, 2]);
+        assert_eq!(*from_previous, ((i + 2) % 3) * 10);
+    }
+    let names = simulation
+        .transcript
+        .rounds
+        .iter()
+        .map(|r| r.name.as_str())
+        .collect::<Vec<&str>>();
+    assert_eq!(names, vec!["hello", "ring"]);
+    let hello = simulation.transcript.round("hello").unwrap();
+    assert_eq!(hello.messages.len(), 3);
+    assert!(hello.messages.iter().all(|m| m.receiver.is_none()));
+    let ring = simulation.transcript.round("ring").unwrap();
+    assert_eq!(ring.messages[2].receiver, Some(0));
+    assert_eq!(ring.messages[2].body, "20");
+}
+
+#[test]
+fn test_failing_party_aborts_the_others() {
+    let result = run(3, |channel: &mut Channel| {
+        if channel.party() == 1 {
+            return channel.check("first", Err(InvalidKey));
+        }
+        channel.broadcast("first", &());
+        channel.gather("first", ())
+    });
+    assert_eq!(
+        result.unwrap_err(),
+        SimulationError::Protocol {
+            party: 1,
+            round: "first".to_string(),
+            error: InvalidKey,
+        }
+    );
+}
+
+#[test]
+fn test_gg18_keygen_sign_refresh() {
+    let keygen = gg18::keygen(1, 3).expect("keygen failed");
+    let keys = keygen.outputs;
+    let y = keys[0].y_sum;
+    assert!(keys.iter().all(|k| k.y_sum == y));
+    for key in &keys {
+        assert_eq!(key.roster, keys[0].roster);
+        assert_eq!(key.roster[key.party_id.index()], key.identity.public);
+    }
+    let round3 = keygen.transcript.round("round3").unwrap();
+    assert_eq!(round3.messages.len(), 6);
+
+    let message = BigInt::from(1234);
+    let signing = gg18::sign(&keys, &[0, 2], &message).expect("signing failed");
+    assert_eq!(signing.transcript.rounds.len(), 9);
+    for sig in &signing.outputs {
+        verify(sig, &y, &message).unwrap();
+    }
+
+    let refreshed = gg18::refresh(&keys).expect("refresh failed").outputs;
+    for (old, new) in keys.iter().zip(&refreshed) {
+        assert_ne!(old.shared_keys.x_i, new.shared_keys.x_i);
+        assert_eq!(new.y_sum, y);
+    }
+    // signers need not be given in ascending order
+    let signing = gg18::sign(&refreshed, &[2, 1], &message).expect("signing failed");
+    verify(&signing.outputs[0], &y, &message).unwrap();
+}
+
+#[test]
+fn test_gg18_bad_share_is_reported() {
+    let mut keys = gg18::keygen(1, 2).expect("keygen failed").outputs;
+    keys[0].shared_keys.x_i = FE::new_random();
+    let result = gg18::sign(&keys, &[0, 1], &BigInt::from(1234));
+    assert_eq!(
+        result.unwrap_err(),
+        SimulationError::Protocol {
+            party: 0,
+            round: "round1".to_string(),
+            error: PublicShareMismatch { party: 0 },
+        }
+    );
+}
+
+#[test]
+fn test_gg18_key_mismatch_stops_in_round1() {
+    let mut keys = gg18::keygen(1, 3).expect("keygen failed").outputs;
+    keys[2].y_sum = keys[2].y_sum + keys[2].y_sum;
+    let result = gg18::sign(&keys, &[0, 2], &BigInt::from(1234));
+    assert_eq!(
+        result.unwrap_err(),
+        SimulationError::Protocol {
+            party: 0,
+            round: "round1".to_string(),
+            error: MessageMismatch { party: 2 },
+        }
+    );
+}
+
+#[test]
+fn test_gg18_bad_mta_is_reported() {
+    // party 1 answers party 0's MessageA under the wrong paillier key
+    let mut keys = gg18::keygen(1, 3).expect("keygen failed").outputs;
+    keys[1].paillier_key_vec[0] = keys[1].paillier_key_vec[2].clone();
+    let result = gg18::sign(&keys, &[0, 1], &BigInt::from(1234));
+    assert_eq!(
+        result.unwrap_err(),
+        SimulationError::Protocol {
+            party: 0,
+            round: "round2".to_string(),
+            error: InvalidKey,
+        }
+    );
+}
+
+#[test]
+fn test_gg18_duplicate_signer_is_rejected() {
+    let keys = gg18::keygen(1, 3).expect("keygen failed").outputs;
+    let result = gg18::sign(&keys, &[1, 1], &BigInt::from(1234));
+    assert_eq!(
+        result.unwrap_err(),
+        SimulationError::Protocol {
+            party: 0,
+            round: "round1".to_string(),
+            error: DuplicatePartyId { party: 2 },
+        }
+    );
+}
+
+#[test]
+fn test_duplicated_and_late_messages_are_handled() {
+    let config = FaultConfig {
+        seed: 1,
+        latency: Duration::from_millis(20),
+        reorder: 0.5,
+        duplicate: 0.5,
+        ..FaultConfig::default()
+    };
+    let faults = |channel: &mut Channel| {
+        let me = channel.party();
+        channel.broadcast("first", &me);
+        channel.broadcast("second", &(me * 10));
+        // take the rounds out of order, as a party waiting on a slow message would
+        let second: Vec<usize> = channel.gather("second", me * 10)?;
+        let first: Vec<usize> = channel.gather("first", me)?;
+        Ok((first, second))
+    };
+    let one = run_with_faults(4, config.clone(), faults);
+    for result in &one.results {
+        assert_eq!(result, &Ok((vec![0, 1, 2, 3], vec![0, 10, 20, 30])));
+    }
+    assert!(one.faults.iter().any(|f| f.kind == FaultKind::Duplicated));
+    assert!(one.faults.iter().any(|f| f.kind == FaultKind::Reordered));
+    // same seed, same faults
+    let two = run_with_faults(4, config, faults);
+    assert_eq!(one.faults, two.faults);
+}
+
+#[test]
+fn test_gg18_sign_with_faults() {
+    let keys = gg18::keygen(1, 3).expect("keygen failed").outputs;
+    let y = keys[0].y_sum;
+    let message = BigInt::from(1234);
+    let config = FaultConfig {
+        seed: 7,
+        latency: Duration::from_millis(10),
+        reorder: 0.3,
+        duplicate: 0.3,
+        ..FaultConfig::default()
+    };
+    let signing = gg18::sign_with_faults(&keys, &[0, 1, 2], &message, config);
+    assert!(!signing.faults.is_empty());
+    for result in &signing.results {
+        verify(result.as_ref().unwrap(), &y, &message).unwrap();
+    }
+}
+
+#[test]
+fn test_gg18_crash_times_out() {
+    let keys = gg18::keygen(1, 3).expect("keygen failed").outputs;
+    let config = FaultConfig {
+        crashes: vec![(1, "round3".to_string())],
+        timeout: Duration::from_secs(5),
+        ..FaultConfig::default()
+    };
+    let signing = gg18::sign_with_faults(&keys, &[0, 1, 2], &BigInt::from(1234), config);
+    assert_eq!(
+        signing.results[1].as_ref().unwrap_err(),
+        &SimulationError::Crashed {
+            party: 1,
+            round: "round3".to_string(),
+        }
+    );
+    // whoever gave up first names the crashed party, the other stopped with it
+    let mut timeouts = 0;
+    for i in [0, 2].iter() {
+        match signing.results[*i].as_ref().unwrap_err() {
+            SimulationError::Timeout { round, sender, .. } => {
+                assert_eq!((round.as_str(), *sender), ("round3", 1));
+                timeouts += 1;
+            }
+            SimulationError::Aborted { .. } => (),
+            e => panic!("unexpected error {:?}", e),
+        }
+    }
+    assert!(timeouts > 0);
+    assert!(signing.transcript.round("round4").is_none());
+}
+
+#[test]
+fn test_dropped_messages_time_out() {
+    let config = FaultConfig {
+        seed: 3,
+        drop: 1.0,
+        timeout: Duration::from_millis(200),
+        ..FaultConfig::default()
+    };
+    let run = run_with_faults(3, config, |channel: &mut Channel| {
+        let me = channel.party();
+        channel.broadcast("first", &me);
+        channel.gather("first", me)
+    });
+    assert!(run.results.iter().all(|r| match r {
+        Err(SimulationError::Timeout { round, .. })
+        | Err(SimulationError::Aborted { round, .. }) => round == "first",
+        _ => false,
+    }));
+    assert_eq!(run.faults.len(), 6);
+    assert!(run.faults.iter().all(|f| f.kind == FaultKind::Dropped));
+}
+
+#[test]
+fn test_lindell17_keygen_sign() {
+    let (party_one_key, party_two_key, transcript) = lindell17::keygen().expect("keygen failed");
+    assert_eq!(party_one_key.public_key, party_two_key.public_key);
+    assert!(transcript.round("pdl4").is_some());
+
+    let message = BigInt::from(1234);
+    let (party_one_key, _party_two_key, signature, _transcript) =
+        lindell17::sign(party_one_key, party_two_key, &message).expect("signing failed");
+    crate::protocols::two_party_ecdsa::lindell_2017::party_one::verify(
+        &signature,
+        &party_one_key.public_key,
+        &message,
+    )
+    .unwrap();
+}
+
+#[cfg(feature = "cclst")]
+#[test]
+fn test_cclst19_keygen_sign() {
+    use crate::simulation::cclst19;
+
+    let (party_one_key, party_two_key, _transcript) = cclst19::keygen().expect("keygen failed");
+    assert_eq!(party_one_key.public_key, party_two_key.public_key);
+
+    let message = BigInt::from(1234);
+    let (party_one_key, _party_two_key, signature, _transcript) =
+        cclst19::sign(party_one_key, party_two_key, &message).expect("signing failed");
+    crate::protocols::two_party_ecdsa::cclst_2019::party_one::verify(
+        &signature,
+        &party_one_key.public_key,
+        &message,
+    )
+    .unwrap();
+}
@@ -0,0 +1,83 @@
+/*
+    Multi-party ECDSA
+
+    Copyright 2018 by Kzen Networks
+
+    This file is part of Multi-party ECDSA library
+    (https://github.com/KZen-networks/multi-party-ecdsa)
+
+    Multi-party ECDSA is free software: you can redistribute
+    it and/or modify it under the terms of the GNU General Public
+    License as published by the Free Software Foundation, either
+    version 3 of the License, or (at your option) any later version.
+
+    @license GPL-3.0+ <https://github.com/KZen-networks/multi-party-ecdsa/blob/master/LICENSE>
+*/
+
+/// Echo broadcast. Nothing stops the relay, or a party posting p2p messages, from handing
+/// different peers different broadcasts in the same round, e.g. two KeyGenBroadcastMessage1
+/// commitments. After such a round every party broadcasts the SHA-256 of each broadcast it
+/// received in round "{round}-echo" and compares its peers' digests with its own. A digest that
+/// differs names both the sender and the echoer: one of them lied, about what it broadcast or
+/// about what it received, and the digests alone do not tell which.
+use std::collections::BTreeMap;
+
+use crypto::digest::Digest;
+use crypto::sha2::Sha256;
+
+use super::{Envelope, Transport, TransportError};
+
+/// Digest of every broadcast one party received in a round, by sender.
+pub type Echo = BTreeMap<u16, String>;
+
+pub fn digest(body: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.input_str(body);
+    hasher.result_str()
+}
+
+pub fn echo_round(round: &str) -> String {
+    format!("{}-echo", round)
+}
+
+/// Runs the echo round for the broadcasts received in round, which should include this party's
+/// own broadcast so that equivocation towards it is caught too. Echoes are expected from every
+/// other sender in received.
+pub fn echo<T: Transport>(
+    transport: &mut T,
+    session: &str,
+    round: &str,
+    received: &[Envelope],
+) -> Result<(), TransportError> {
+    let mine = received
+        .iter()
+        .map(|envelope| (envelope.sender, digest(&envelope.body)))
+        .collect::<Echo>();
+    let body = serde_json::to_string(&mine).expect("unserializable echo");
+    transport.broadcast(session, &echo_round(round), &body)?;
+
+    let me = transport.party();
+    for &peer in mine.keys().filter(|&&peer| peer != me) {
+        let envelope = transport.receive(session, &echo_round(round), peer)?;
+        let theirs: Echo = serde_json::from_str(&envelope.body)
+            .map_err(|_| TransportError::Malformed(envelope.body.clone()))?;
+        compare(round, peer, &mine, &theirs)?;
+    }
+    Ok(())
+}
+
+/// Fails naming the first sender for which this party's echo and the one of echoer hold
+/// different digests. Senders missing from either echo are not compared.
+pub fn compare(round: &str, echoer: u16, mine: &Echo, theirs: &Echo) -> Result<(), TransportError> {
+    match mine
+        .iter()
+        .find(|&(sender, d)| theirs.get(sender).map_or(false, |other| other != d))
+    {
+        Some((&sender, _)) => Err(TransportError::Equivocation {
+            round: round.to_string(),
+            sender,
+            echoer,
+        }),
+        None => Ok(()),
+    }
+}
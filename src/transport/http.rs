@@ -0,0 +1,245 @@
+/*
+    Multi-party ECDSA
+
+    Copyright 2018 by Kzen Networks
+
+    This file is part of Multi-party ECDSA library
+    (https://github.com/KZen-networks/multi-party-ecdsa)
+
+    Multi-party ECDSA is free software: you can redistribute
+    it and/or modify it under the terms of the GNU General Public
+    License as published by the Free Software Foundation, either
+    version 3 of the License, or (at your option) any later version.
+
+    @license GPL-3.0+ <https://github.com/KZen-networks/multi-party-ecdsa/blob/master/LICENSE>
+*/
+
+/// Client for the sm_manager relay: a key value store per session where a broadcast is kept
+/// under "{sender}-{round}-{session}" and a p2p message under
+/// "{sender}-{receiver}-{round}-{session}", the session being the uuid handed out at signup.
+/// Receiving is a long poll: the relay holds the request until the messages are there, or
+/// answers 503 when it already holds all the waits it can.
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use reqwest::{Client, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use super::{aborted, timeout, Backoff, Envelope, Transport, TransportError, ABORT_ROUND};
+
+// tries per request before the relay counts as unreachable
+const CONNECT_ATTEMPTS: u32 = 3;
+// longest the relay is asked to hold a request, below reqwest's 30s default timeout
+const LONG_POLL: Duration = Duration::from_secs(20);
+
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    key: String,
+    value: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Wait {
+    round: String,
+    receiver: u16,
+    senders: Vec<u16>,
+    timeout_ms: u64,
+}
+
+// what the relay answered a wait, Ok(None) if it was busy
+type Reply = Result<Option<Result<Vec<Envelope>, ()>>, TransportError>;
+
+pub struct HttpTransport {
+    client: Client,
+    address: String,
+    party: u16,
+    backoff: Backoff,
+    // with a Cancel the waits run on their own thread and send their reply here, and so does
+    // the Cancel to wake the party up
+    replies: Receiver<Reply>,
+    replied: Sender<Reply>,
+}
+
+impl HttpTransport {
+    /// address of the relay, e.g. http://127.0.0.1:8001
+    pub fn new(address: &str, party: u16, backoff: Backoff) -> Self {
+        let (replied, replies) = channel();
+        let wake = replied.clone();
+        backoff.on_cancel(move || {
+            let _ = wake.send(Err(TransportError::Cancelled));
+        });
+        HttpTransport {
+            client: Client::new(),
+            address: address.trim_end_matches('/').to_string(),
+            party,
+            backoff,
+            replies,
+            replied,
+        }
+    }
+
+    fn set(&self, session: &str, key: String, body: &str) -> Result<(), TransportError> {
+        let entry = Entry {
+            key: key.clone(),
+            value: body.to_string(),
+        };
+        let reply: Result<(), ()> = self.post(&format!("session/{}/set", session), &entry)?;
+        reply.map_err(|_| TransportError::Rejected(key))
+    }
+
+    // long polls until the relay has a message from every sender or the backoff timeout is up
+    fn wait(
+        &self,
+        session: &str,
+        round: &str,
+        senders: &[u16],
+    ) -> Result<Vec<Envelope>, TransportError> {
+        let start = Instant::now();
+        let mut delay = self.backoff.initial;
+        loop {
+            let slice = self.backoff.slice(start)?;
+            let request = Wait {
+                round: round.to_string(),
+                receiver: self.party,
+                senders: senders.to_vec(),
+                timeout_ms: slice.map_or(LONG_POLL, |s| s.min(LONG_POLL)).as_millis() as u64,
+            };
+            let url = format!("{}/session/{}/wait", self.address, session);
+            let reply = match self.backoff.cancel {
+                None => post_or_busy(&self.client, &url, &self.backoff, &request)?,
+                // the relay cannot be interrupted, so the party waits for whichever comes first
+                Some(_) => {
+                    let (client, backoff) = (self.client.clone(), self.backoff.clone());
+                    let replied = self.replied.clone();
+                    thread::spawn(move || {
+                        let _ = replied.send(post_or_busy(&client, &url, &backoff, &request));
+                    });
+                    self.replies.recv().map_err(|_| TransportError::Closed)??
+                }
+            };
+            let found = match reply {
+                Some(reply) => reply.map_err(|_| TransportError::Rejected(session.to_string()))?,
+                // every wait slot of the relay is taken, ask again after a pause
+                None => {
+                    let remaining = self.backoff.remaining(start);
+                    let pause = remaining.map_or(delay, |r| r.min(delay));
+                    self.backoff.sleep(pause);
+                    delay = (delay * 2).min(self.backoff.max);
+                    Vec::new()
+                }
+            };
+            // the relay hands out aborts from the other parties instead of the round's messages
+            if let Some(abort) = found.iter().find(|e| e.round == ABORT_ROUND) {
+                return Err(aborted(abort));
+            }
+            if found.len() == senders.len() {
+                return Ok(found);
+            }
+            if self.backoff.remaining(start) == Some(Duration::default()) {
+                let missing = senders
+                    .iter()
+                    .find(|&&sender| found.iter().all(|e| e.sender != sender))
+                    .unwrap();
+                return Err(timeout(session, round, *missing));
+            }
+        }
+    }
+
+    fn post<B: Serialize, R: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> Result<R, TransportError> {
+        let url = format!("{}/{}", self.address, path);
+        post_or_busy(&self.client, &url, &self.backoff, body)?.ok_or_else(|| {
+            TransportError::Rejected(format!("{}: {}", url, StatusCode::SERVICE_UNAVAILABLE))
+        })
+    }
+}
+
+// Ok(None) if the relay answered 503
+fn post_or_busy<B: Serialize, R: DeserializeOwned>(
+    client: &Client,
+    url: &str,
+    backoff: &Backoff,
+    body: &B,
+) -> Result<Option<R>, TransportError> {
+    let mut delay = backoff.initial;
+    let mut error = String::new();
+    for attempt in 1..=CONNECT_ATTEMPTS {
+        match client.post(url).json(body).send() {
+            Ok(mut response) => {
+                if response.status() == StatusCode::SERVICE_UNAVAILABLE {
+                    return Ok(None);
+                }
+                if !response.status().is_success() {
+                    return Err(TransportError::Rejected(format!(
+                        "{}: {}",
+                        url,
+                        response.status()
+                    )));
+                }
+                let text = response
+                    .text()
+                    .map_err(|e| TransportError::Malformed(e.to_string()))?;
+                return serde_json::from_str(&text)
+                    .map(Some)
+                    .map_err(|_| TransportError::Malformed(text));
+            }
+            Err(e) => error = e.to_string(),
+        }
+        if attempt < CONNECT_ATTEMPTS {
+            thread::sleep(delay);
+            delay = (delay * 2).min(backoff.max);
+        }
+    }
+    Err(TransportError::Connection(error))
+}
+
+impl Transport for HttpTransport {
+    fn party(&self) -> u16 {
+        self.party
+    }
+
+    fn broadcast(&mut self, session: &str, round: &str, body: &str) -> Result<(), TransportError> {
+        self.set(
+            session,
+            format!("{}-{}-{}", self.party, round, session),
+            body,
+        )
+    }
+
+    fn send(
+        &mut self,
+        session: &str,
+        round: &str,
+        receiver: u16,
+        body: &str,
+    ) -> Result<(), TransportError> {
+        self.set(
+            session,
+            format!("{}-{}-{}-{}", self.party, receiver, round, session),
+            body,
+        )
+    }
+
+    fn receive(
+        &mut self,
+        session: &str,
+        round: &str,
+        sender: u16,
+    ) -> Result<Envelope, TransportError> {
+        Ok(self.wait(session, round, &[sender])?.remove(0))
+    }
+
+    fn receive_all(
+        &mut self,
+        session: &str,
+        round: &str,
+        senders: &[u16],
+    ) -> Result<Vec<Envelope>, TransportError> {
+        self.wait(session, round, senders)
+    }
+}
@@ -0,0 +1,246 @@
+/*
+    Multi-party ECDSA
+
+    Copyright 2018 by Kzen Networks
+
+    This file is part of Multi-party ECDSA library
+    (https://github.com/KZen-networks/multi-party-ecdsa)
+
+    Multi-party ECDSA is free software: you can redistribute
+    it and/or modify it under the terms of the GNU General Public
+    License as published by the Free Software Foundation, either
+    version 3 of the License, or (at your option) any later version.
+
+    @license GPL-3.0+ <https://github.com/KZen-networks/multi-party-ecdsa/blob/master/LICENSE>
+*/
+
+/// Long-term party identities. Every party keeps one identity key pair across keygen, signing
+/// and refresh, the public keys of all parties (the roster) are exchanged at keygen and stored
+/// with the key share. Each message is signed with a Schnorr signature over the session id,
+/// round, sender and the SHA-256 of the payload, so nobody can post a message in another
+/// party's name.
+use std::collections::HashMap;
+use std::fmt;
+
+use crypto::digest::Digest;
+use crypto::sha2::Sha256;
+use curv::elliptic::curves::traits::{ECPoint, ECScalar};
+use curv::{BigInt, FE, GE};
+use serde::{Deserialize, Serialize};
+
+use super::{Envelope, Transport, TransportError};
+use crate::Error::{self, InvalidSig};
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct IdentityKey {
+    pub secret: FE,
+    pub public: GE,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct IdentitySignature {
+    pub r: GE,
+    pub s: FE,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SignedMessage {
+    pub payload: String,
+    pub signature: IdentitySignature,
+}
+
+impl IdentityKey {
+    pub fn new() -> Self {
+        let secret: FE = ECScalar::new_random();
+        let public = GE::generator() * secret;
+        IdentityKey { secret, public }
+    }
+
+    pub fn sign(
+        &self,
+        session: &str,
+        round: &str,
+        sender: u16,
+        payload: &[u8],
+    ) -> IdentitySignature {
+        let k: FE = ECScalar::new_random();
+        let r = GE::generator() * k;
+        let e = challenge(&r, &self.public, session, round, sender, payload);
+        IdentitySignature {
+            r,
+            s: k + e * self.secret,
+        }
+    }
+}
+
+impl Default for IdentityKey {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// the secret stays out of logs and panic messages
+impl fmt::Debug for IdentityKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("IdentityKey")
+            .field("public", &self.public)
+            .finish()
+    }
+}
+
+impl SignedMessage {
+    pub fn new(
+        identity: &IdentityKey,
+        session: &str,
+        round: &str,
+        sender: u16,
+        payload: &str,
+    ) -> Self {
+        SignedMessage {
+            payload: payload.to_string(),
+            signature: identity.sign(session, round, sender, payload.as_bytes()),
+        }
+    }
+
+    /// The payload, if sender signed it with public for round of session.
+    pub fn verify(
+        self,
+        public: &GE,
+        session: &str,
+        round: &str,
+        sender: u16,
+    ) -> Result<String, Error> {
+        self.signature
+            .verify(public, session, round, sender, self.payload.as_bytes())?;
+        Ok(self.payload)
+    }
+}
+
+impl IdentitySignature {
+    pub fn verify(
+        &self,
+        public: &GE,
+        session: &str,
+        round: &str,
+        sender: u16,
+        payload: &[u8],
+    ) -> Result<(), Error> {
+        let e = challenge(&self.r, public, session, round, sender, payload);
+        if GE::generator() * self.s == self.r + *public * e {
+            Ok(())
+        } else {
+            Err(InvalidSig)
+        }
+    }
+}
+
+/// Wraps a transport so that everything sent is signed with identity and everything received
+/// is checked against roster, the identity public key of every party by party number.
+pub struct SignedTransport<T> {
+    inner: T,
+    identity: IdentityKey,
+    roster: HashMap<u16, GE>,
+}
+
+impl<T: Transport> SignedTransport<T> {
+    pub fn new(inner: T, identity: IdentityKey, roster: HashMap<u16, GE>) -> Self {
+        SignedTransport {
+            inner,
+            identity,
+            roster,
+        }
+    }
+
+    fn sign(&self, session: &str, round: &str, body: &str) -> String {
+        let signed = SignedMessage::new(&self.identity, session, round, self.inner.party(), body);
+        serde_json::to_string(&signed).expect("unserializable message")
+    }
+
+    fn verify(&self, round: &str, mut envelope: Envelope) -> Result<Envelope, TransportError> {
+        let sender = envelope.sender;
+        let public = self
+            .roster
+            .get(&sender)
+            .ok_or(TransportError::UnknownParty(sender))?;
+        let signed: SignedMessage = serde_json::from_str(&envelope.body)
+            .map_err(|_| TransportError::Malformed(envelope.body.clone()))?;
+        envelope.body = signed
+            .verify(public, &envelope.session, round, sender)
+            .map_err(|_| TransportError::Unauthenticated {
+                round: round.to_string(),
+                sender,
+            })?;
+        Ok(envelope)
+    }
+}
+
+impl<T: Transport> Transport for SignedTransport<T> {
+    fn party(&self) -> u16 {
+        self.inner.party()
+    }
+
+    fn broadcast(&mut self, session: &str, round: &str, body: &str) -> Result<(), TransportError> {
+        let signed = self.sign(session, round, body);
+        self.inner.broadcast(session, round, &signed)
+    }
+
+    fn send(
+        &mut self,
+        session: &str,
+        round: &str,
+        receiver: u16,
+        body: &str,
+    ) -> Result<(), TransportError> {
+        let signed = self.sign(session, round, body);
+        self.inner.send(session, round, receiver, &signed)
+    }
+
+    fn receive(
+        &mut self,
+        session: &str,
+        round: &str,
+        sender: u16,
+    ) -> Result<Envelope, TransportError> {
+        let envelope = self.inner.receive(session, round, sender)?;
+        self.verify(round, envelope)
+    }
+
+    fn receive_all(
+        &mut self,
+        session: &str,
+        round: &str,
+        senders: &[u16],
+    ) -> Result<Vec<Envelope>, TransportError> {
+        let envelopes = self.inner.receive_all(session, round, senders)?;
+        envelopes
+            .into_iter()
+            .map(|envelope| self.verify(round, envelope))
+            .collect()
+    }
+
+    // aborts are read by the inner transport, so they go out unwrapped
+    fn abort(&mut self, session: &str, round: &str, reason: &str) -> Result<(), TransportError> {
+        self.inner.abort(session, round, reason)
+    }
+}
+
+// H(R, P, session, round, sender, H(payload)), variable length fields length prefixed
+fn challenge(r: &GE, public: &GE, session: &str, round: &str, sender: u16, payload: &[u8]) -> FE {
+    let mut payload_hash = [0u8; 32];
+    let mut hasher = Sha256::new();
+    hasher.input(payload);
+    hasher.result(&mut payload_hash);
+
+    let mut hasher = Sha256::new();
+    hasher.input(&r.pk_to_key_slice());
+    hasher.input(&public.pk_to_key_slice());
+    for field in [session.as_bytes(), round.as_bytes()].iter() {
+        hasher.input(&(field.len() as u32).to_be_bytes());
+        hasher.input(field);
+    }
+    hasher.input(&sender.to_be_bytes());
+    hasher.input(&payload_hash);
+    let mut e = [0u8; 32];
+    hasher.result(&mut e);
+    ECScalar::from(&BigInt::from(&e[..]))
+}
@@ -0,0 +1,141 @@
+/*
+    Multi-party ECDSA
+
+    Copyright 2018 by Kzen Networks
+
+    This file is part of Multi-party ECDSA library
+    (https://github.com/KZen-networks/multi-party-ecdsa)
+
+    Multi-party ECDSA is free software: you can redistribute
+    it and/or modify it under the terms of the GNU General Public
+    License as published by the Free Software Foundation, either
+    version 3 of the License, or (at your option) any later version.
+
+    @license GPL-3.0+ <https://github.com/KZen-networks/multi-party-ecdsa/blob/master/LICENSE>
+*/
+
+/// Parties in one process sharing a board keyed like the sm_manager relay. Receivers wait on
+/// a condition variable instead of polling.
+use std::collections::HashMap;
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::Instant;
+
+use super::{aborted, timeout, Backoff, Envelope, Transport, TransportError, ABORT_ROUND};
+
+// (session, round, sender, receiver), receiver None for broadcasts
+type Key = (String, String, u16, Option<u16>);
+
+#[derive(Default)]
+struct Board {
+    messages: Mutex<HashMap<Key, String>>,
+    posted: Condvar,
+}
+
+pub struct MemoryTransport {
+    party: u16,
+    board: Arc<Board>,
+    backoff: Backoff,
+}
+
+impl MemoryTransport {
+    /// Transports for parties 1..=parties, all on the same board. Only backoff.timeout and
+    /// backoff.cancel are used, receivers are woken up as soon as something is posted.
+    pub fn network(parties: u16, backoff: Backoff) -> Vec<MemoryTransport> {
+        let board = Arc::new(Board::default());
+        let waiting = board.clone();
+        backoff.on_cancel(move || {
+            // under the lock, so a receiver about to wait cannot miss it
+            let _messages = waiting.messages.lock().unwrap();
+            waiting.posted.notify_all();
+        });
+        (1..=parties)
+            .map(|party| MemoryTransport {
+                party,
+                board: board.clone(),
+                backoff: backoff.clone(),
+            })
+            .collect()
+    }
+
+    fn post(&self, key: Key, body: &str) {
+        let mut messages = self.board.messages.lock().unwrap();
+        messages.insert(key, body.to_string());
+        self.board.posted.notify_all();
+    }
+}
+
+impl Transport for MemoryTransport {
+    fn party(&self) -> u16 {
+        self.party
+    }
+
+    fn broadcast(&mut self, session: &str, round: &str, body: &str) -> Result<(), TransportError> {
+        self.post(
+            (session.to_string(), round.to_string(), self.party, None),
+            body,
+        );
+        Ok(())
+    }
+
+    fn send(
+        &mut self,
+        session: &str,
+        round: &str,
+        receiver: u16,
+        body: &str,
+    ) -> Result<(), TransportError> {
+        self.post(
+            (
+                session.to_string(),
+                round.to_string(),
+                self.party,
+                Some(receiver),
+            ),
+            body,
+        );
+        Ok(())
+    }
+
+    fn receive(
+        &mut self,
+        session: &str,
+        round: &str,
+        sender: u16,
+    ) -> Result<Envelope, TransportError> {
+        let start = Instant::now();
+        let mut messages = self.board.messages.lock().unwrap();
+        loop {
+            let abort = messages.iter().find(|((s, r, from, to), _)| {
+                s == session && r == ABORT_ROUND && *from != self.party && to.is_none()
+            });
+            if let Some(((_, _, from, _), body)) = abort {
+                return Err(aborted(&Envelope {
+                    session: session.to_string(),
+                    round: ABORT_ROUND.to_string(),
+                    sender: *from,
+                    receiver: None,
+                    body: body.clone(),
+                }));
+            }
+            for receiver in [Some(self.party), None].iter() {
+                let key = (session.to_string(), round.to_string(), sender, *receiver);
+                if let Some(body) = messages.get(&key) {
+                    return Ok(Envelope {
+                        session: session.to_string(),
+                        round: round.to_string(),
+                        sender,
+                        receiver: *receiver,
+                        body: body.clone(),
+                    });
+                }
+            }
+            messages = match self.backoff.slice(start)? {
+                None => self.board.posted.wait(messages).unwrap(),
+                Some(slice) if slice.as_nanos() > 0 => {
+                    self.board.posted.wait_timeout(messages, slice).unwrap().0
+                }
+                Some(_) => return Err(timeout(session, round, sender)),
+            };
+        }
+    }
+}
@@ -0,0 +1,306 @@
+/*
+    Multi-party ECDSA
+
+    Copyright 2018 by Kzen Networks
+
+    This file is part of Multi-party ECDSA library
+    (https://github.com/KZen-networks/multi-party-ecdsa)
+
+    Multi-party ECDSA is free software: you can redistribute
+    it and/or modify it under the terms of the GNU General Public
+    License as published by the Free Software Foundation, either
+    version 3 of the License, or (at your option) any later version.
+
+    @license GPL-3.0+ <https://github.com/KZen-networks/multi-party-ecdsa/blob/master/LICENSE>
+*/
+
+/// Moving protocol messages between parties. A Transport belongs to one party (numbered from 1,
+/// like the sm_manager signups) and carries opaque message bodies tagged with a session, a round
+/// and the sending party. Implementations talk to the sm_manager relay over http (feature
+/// "http"), directly to the other parties over tcp, or to each other in memory.
+use std::fmt;
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+pub mod echo;
+#[cfg(feature = "http")]
+pub mod http;
+pub mod identity;
+pub mod memory;
+pub mod secure;
+pub mod tcp;
+
+/// Round every party watches for aborts while waiting.
+pub const ABORT_ROUND: &str = "abort";
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Envelope {
+    pub session: String,
+    pub round: String,
+    pub sender: u16,
+    pub receiver: Option<u16>, // None for broadcasts
+    pub body: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TransportError {
+    // the relay or peer could not be reached
+    Connection(String),
+    // the relay answered but refused the request
+    Rejected(String),
+    // a reply or frame that does not parse
+    Malformed(String),
+    // nothing from sender in round before the backoff timeout
+    Timeout {
+        session: String,
+        round: String,
+        sender: u16,
+    },
+    // a message in round that sender did not make, or that was tampered with
+    Unauthenticated {
+        round: String,
+        sender: u16,
+    },
+    // this party and echoer got different broadcasts from sender in round: sender equivocated
+    // or echoer lied about what it got
+    Equivocation {
+        round: String,
+        sender: u16,
+        echoer: u16,
+    },
+    // party gave up in round and told everyone why
+    Aborted {
+        party: u16,
+        round: String,
+        reason: String,
+    },
+    UnknownParty(u16),
+    // the transport was shut down while waiting
+    Closed,
+    // the wait was stopped through its Cancel
+    Cancelled,
+}
+
+/// What a party broadcasts in ABORT_ROUND when it gives up.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Abort {
+    pub round: String,
+    pub reason: String,
+}
+
+/// Stops a session from outside: once cancelled, every wait of a Backoff holding a clone fails
+/// with TransportError::Cancelled. Waits blocked at that moment are woken up right away.
+#[derive(Clone, Default)]
+pub struct Cancel(Arc<CancelState>);
+
+#[derive(Default)]
+struct CancelState {
+    cancelled: Mutex<bool>,
+    cond: Condvar,
+    // how the transports holding a clone wake up their blocked waits
+    wakers: Mutex<Vec<Box<dyn Fn() + Send>>>,
+}
+
+impl Cancel {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        *self.0.cancelled.lock().unwrap() = true;
+        self.0.cond.notify_all();
+        for wake in self.0.wakers.lock().unwrap().iter() {
+            wake();
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        *self.0.cancelled.lock().unwrap()
+    }
+
+    // calls wake when cancelled, right away if that already happened
+    fn on_cancel<F: Fn() + Send + 'static>(&self, wake: F) {
+        let mut wakers = self.0.wakers.lock().unwrap();
+        if self.is_cancelled() {
+            wake();
+        }
+        wakers.push(Box::new(wake));
+    }
+
+    // sleeps for duration, less if cancelled meanwhile
+    fn sleep(&self, duration: Duration) {
+        let cancelled = self.0.cancelled.lock().unwrap();
+        if !*cancelled {
+            drop(self.0.cond.wait_timeout(cancelled, duration).unwrap());
+        }
+    }
+}
+
+impl fmt::Debug for Cancel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Cancel").field(&self.is_cancelled()).finish()
+    }
+}
+
+impl PartialEq for Cancel {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+pub trait Transport {
+    fn party(&self) -> u16;
+
+    fn broadcast(&mut self, session: &str, round: &str, body: &str) -> Result<(), TransportError>;
+
+    fn send(
+        &mut self,
+        session: &str,
+        round: &str,
+        receiver: u16,
+        body: &str,
+    ) -> Result<(), TransportError>;
+
+    /// Waits for the message sender sent to this party in round of session, broadcast or not.
+    fn receive(
+        &mut self,
+        session: &str,
+        round: &str,
+        sender: u16,
+    ) -> Result<Envelope, TransportError>;
+
+    /// Tells every other party that this one gives up in round, their waits in session fail with
+    /// TransportError::Aborted. Aborts are not signed: a forged one can only stop the session,
+    /// which any party can do by going quiet.
+    fn abort(&mut self, session: &str, round: &str, reason: &str) -> Result<(), TransportError> {
+        let abort = Abort {
+            round: round.to_string(),
+            reason: reason.to_string(),
+        };
+        let body = serde_json::to_string(&abort).expect("unserializable abort");
+        self.broadcast(session, ABORT_ROUND, &body)
+    }
+
+    /// Waits for the messages of all senders in round, in the order of senders.
+    fn receive_all(
+        &mut self,
+        session: &str,
+        round: &str,
+        senders: &[u16],
+    ) -> Result<Vec<Envelope>, TransportError> {
+        senders
+            .iter()
+            .map(|&sender| self.receive(session, round, sender))
+            .collect()
+    }
+}
+
+/// Exponential backoff between polls, starting at initial and doubling up to max.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Backoff {
+    pub initial: Duration,
+    pub max: Duration,
+    // give up after this long in total, None waits forever
+    pub timeout: Option<Duration>,
+    pub cancel: Option<Cancel>,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Backoff {
+            initial: Duration::from_millis(10),
+            max: Duration::from_secs(1),
+            timeout: None,
+            cancel: None,
+        }
+    }
+}
+
+impl Backoff {
+    /// Calls attempt until it returns Some or fails, sleeping in between. Ok(None) means the
+    /// timeout ran out.
+    pub fn poll<T, F>(&self, mut attempt: F) -> Result<Option<T>, TransportError>
+    where
+        F: FnMut() -> Result<Option<T>, TransportError>,
+    {
+        let start = Instant::now();
+        let mut delay = self.initial;
+        loop {
+            self.check_cancel()?;
+            if let Some(result) = attempt()? {
+                return Ok(Some(result));
+            }
+            if let Some(timeout) = self.timeout {
+                let elapsed = start.elapsed();
+                if elapsed >= timeout {
+                    return Ok(None);
+                }
+                // one last attempt right at the timeout
+                delay = delay.min(timeout - elapsed);
+            }
+            self.sleep(delay);
+            delay = (delay * 2).min(self.max);
+        }
+    }
+
+    // sleeps for delay, less if the wait is cancelled meanwhile
+    fn sleep(&self, delay: Duration) {
+        match &self.cancel {
+            Some(cancel) => cancel.sleep(delay),
+            None => thread::sleep(delay),
+        }
+    }
+
+    // calls wake when the wait is cancelled, transports use it to stop blocking
+    fn on_cancel<F: Fn() + Send + 'static>(&self, wake: F) {
+        if let Some(cancel) = &self.cancel {
+            cancel.on_cancel(wake);
+        }
+    }
+
+    // how much of the timeout is left after start, None if there is no timeout
+    fn remaining(&self, start: Instant) -> Option<Duration> {
+        self.timeout
+            .map(|timeout| timeout.checked_sub(start.elapsed()).unwrap_or_default())
+    }
+
+    // how long a wait that started at start may block, None for ever and zero once the timeout
+    // is over. A cancel wakes the wait through on_cancel rather than cutting it short.
+    fn slice(&self, start: Instant) -> Result<Option<Duration>, TransportError> {
+        self.check_cancel()?;
+        Ok(self.remaining(start))
+    }
+
+    fn check_cancel(&self) -> Result<(), TransportError> {
+        match &self.cancel {
+            Some(cancel) if cancel.is_cancelled() => Err(TransportError::Cancelled),
+            _ => Ok(()),
+        }
+    }
+}
+
+// the error for an envelope posted in ABORT_ROUND
+fn aborted(envelope: &Envelope) -> TransportError {
+    match serde_json::from_str::<Abort>(&envelope.body) {
+        Ok(abort) => TransportError::Aborted {
+            party: envelope.sender,
+            round: abort.round,
+            reason: abort.reason,
+        },
+        Err(_) => TransportError::Malformed(envelope.body.clone()),
+    }
+}
+
+fn timeout(session: &str, round: &str, sender: u16) -> TransportError {
+    TransportError::Timeout {
+        session: session.to_string(),
+        round: round.to_string(),
+        sender,
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,290 @@
+/*
+    Multi-party ECDSA
+
+    Copyright 2018 by Kzen Networks
+
+    This file is part of Multi-party ECDSA library
+    (https://github.com/KZen-networks/multi-party-ecdsa)
+
+    Multi-party ECDSA is free software: you can redistribute
+    it and/or modify it under the terms of the GNU General Public
+    License as published by the Free Software Foundation, either
+    version 3 of the License, or (at your option) any later version.
+
+    @license GPL-3.0+ <https://github.com/KZen-networks/multi-party-ecdsa/blob/master/LICENSE>
+*/
+
+/// Encrypted and authenticated p2p messages. The two ends of a channel run ECDH on their
+/// identity keys and derive with HKDF-SHA256 one AES-256-GCM key per session, run and
+/// direction. The run is a value fresh to this run of the session, made from nonces both ends
+/// announced, so a session id used twice never gets the same keys twice. The nonce is a
+/// per-key message counter, and session, sender, receiver, round and counter go into the AAD,
+/// so a ciphertext cannot be replayed or moved to another round, session, run or party.
+use std::collections::{HashMap, HashSet};
+
+use crypto::aead::{AeadDecryptor, AeadEncryptor};
+use crypto::aes::KeySize::KeySize256;
+use crypto::aes_gcm::AesGcm;
+use crypto::hkdf::{hkdf_expand, hkdf_extract};
+use crypto::sha2::Sha256;
+use curv::elliptic::curves::traits::ECPoint;
+use curv::{FE, GE};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroize;
+
+use super::{Envelope, Transport, TransportError};
+use crate::Error::{self, InvalidCiphertext};
+
+const TAG_SIZE: usize = 16;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Sealed {
+    pub counter: u64,
+    pub ciphertext: Vec<u8>,
+    pub tag: Vec<u8>,
+}
+
+/// One party's end of a channel to peer within session.
+pub struct SecureChannel {
+    session: String,
+    party: u16,
+    peer: u16,
+    send_key: [u8; 32],
+    receive_key: [u8; 32],
+    sent: u64,
+    opened: HashSet<u64>,
+}
+
+impl SecureChannel {
+    /// identity is the party's identity secret and peer_identity the peer's public key from
+    /// the roster, run comes from channel_run.
+    pub fn new(
+        session: &str,
+        run: &[u8],
+        party: u16,
+        identity: &FE,
+        peer: u16,
+        peer_identity: &GE,
+    ) -> Self {
+        let mut shared = (*peer_identity * identity).pk_to_key_slice();
+        let channel = SecureChannel {
+            session: session.to_string(),
+            party,
+            peer,
+            send_key: derive_key(&shared, session, run, party, peer),
+            receive_key: derive_key(&shared, session, run, peer, party),
+            sent: 0,
+            opened: HashSet::new(),
+        };
+        shared.zeroize();
+        channel
+    }
+
+    pub fn seal(&mut self, round: &str, plaintext: &[u8]) -> Sealed {
+        let counter = self.sent;
+        self.sent += 1;
+        let aad = aad(&self.session, self.party, self.peer, round, counter);
+        let mut gcm = AesGcm::new(KeySize256, &self.send_key, &nonce(counter), &aad);
+        let mut ciphertext = vec![0u8; plaintext.len()];
+        let mut tag = vec![0u8; TAG_SIZE];
+        gcm.encrypt(plaintext, &mut ciphertext, &mut tag);
+        Sealed {
+            counter,
+            ciphertext,
+            tag,
+        }
+    }
+
+    /// Fails if sealed was not made by peer for this party in round, or was opened before.
+    pub fn open(&mut self, round: &str, sealed: &Sealed) -> Result<Vec<u8>, Error> {
+        if self.opened.contains(&sealed.counter) || sealed.tag.len() != TAG_SIZE {
+            return Err(InvalidCiphertext);
+        }
+        let aad = aad(&self.session, self.peer, self.party, round, sealed.counter);
+        let mut gcm = AesGcm::new(KeySize256, &self.receive_key, &nonce(sealed.counter), &aad);
+        let mut plaintext = vec![0u8; sealed.ciphertext.len()];
+        if !gcm.decrypt(&sealed.ciphertext, &mut plaintext, &sealed.tag) {
+            return Err(InvalidCiphertext);
+        }
+        self.opened.insert(sealed.counter);
+        Ok(plaintext)
+    }
+}
+
+impl Drop for SecureChannel {
+    fn drop(&mut self) {
+        self.send_key.zeroize();
+        self.receive_key.zeroize();
+    }
+}
+
+/// Wraps a transport so that every p2p message is sealed for its receiver. Broadcasts pass
+/// through unchanged. roster holds the identity public key of every other party, and nonces
+/// the run nonce every party, this one included, announced for this run.
+pub struct SecureTransport<T> {
+    inner: T,
+    identity: FE,
+    roster: HashMap<u16, GE>,
+    nonces: HashMap<u16, Vec<u8>>,
+    channels: HashMap<(String, u16), SecureChannel>,
+}
+
+impl<T: Transport> SecureTransport<T> {
+    pub fn new(
+        inner: T,
+        identity: FE,
+        roster: HashMap<u16, GE>,
+        nonces: HashMap<u16, Vec<u8>>,
+    ) -> Self {
+        SecureTransport {
+            inner,
+            identity,
+            roster,
+            nonces,
+            channels: HashMap::new(),
+        }
+    }
+
+    fn channel(&mut self, session: &str, peer: u16) -> Result<&mut SecureChannel, TransportError> {
+        let party = self.inner.party();
+        let peer_identity = *self
+            .roster
+            .get(&peer)
+            .ok_or(TransportError::UnknownParty(peer))?;
+        let run = match (self.nonces.get(&party), self.nonces.get(&peer)) {
+            (Some(nonce), Some(peer_nonce)) => channel_run(party, nonce, peer, peer_nonce),
+            _ => return Err(TransportError::UnknownParty(peer)),
+        };
+        let identity = &self.identity;
+        Ok(self
+            .channels
+            .entry((session.to_string(), peer))
+            .or_insert_with(|| {
+                SecureChannel::new(session, &run, party, identity, peer, &peer_identity)
+            }))
+    }
+
+    fn open(&mut self, round: &str, mut envelope: Envelope) -> Result<Envelope, TransportError> {
+        if envelope.receiver.is_some() {
+            let sender = envelope.sender;
+            let rejected = || TransportError::Unauthenticated {
+                round: round.to_string(),
+                sender,
+            };
+            let sealed: Sealed = serde_json::from_str(&envelope.body)
+                .map_err(|_| TransportError::Malformed(envelope.body.clone()))?;
+            let plaintext = self
+                .channel(&envelope.session, sender)?
+                .open(round, &sealed)
+                .map_err(|_| rejected())?;
+            envelope.body = String::from_utf8(plaintext).map_err(|_| rejected())?;
+        }
+        Ok(envelope)
+    }
+}
+
+impl<T: Transport> Transport for SecureTransport<T> {
+    fn party(&self) -> u16 {
+        self.inner.party()
+    }
+
+    fn broadcast(&mut self, session: &str, round: &str, body: &str) -> Result<(), TransportError> {
+        self.inner.broadcast(session, round, body)
+    }
+
+    fn send(
+        &mut self,
+        session: &str,
+        round: &str,
+        receiver: u16,
+        body: &str,
+    ) -> Result<(), TransportError> {
+        let sealed = self
+            .channel(session, receiver)?
+            .seal(round, body.as_bytes());
+        let sealed = serde_json::to_string(&sealed).expect("unserializable message");
+        self.inner.send(session, round, receiver, &sealed)
+    }
+
+    fn receive(
+        &mut self,
+        session: &str,
+        round: &str,
+        sender: u16,
+    ) -> Result<Envelope, TransportError> {
+        let envelope = self.inner.receive(session, round, sender)?;
+        self.open(round, envelope)
+    }
+
+    fn receive_all(
+        &mut self,
+        session: &str,
+        round: &str,
+        senders: &[u16],
+    ) -> Result<Vec<Envelope>, TransportError> {
+        let envelopes = self.inner.receive_all(session, round, senders)?;
+        envelopes
+            .into_iter()
+            .map(|envelope| self.open(round, envelope))
+            .collect()
+    }
+
+    // aborts are read by the inner transport, so they go out unwrapped
+    fn abort(&mut self, session: &str, round: &str, reason: &str) -> Result<(), TransportError> {
+        self.inner.abort(session, round, reason)
+    }
+}
+
+/// A fresh nonce for a party to announce, signed, before its channels are set up.
+pub fn run_nonce() -> Vec<u8> {
+    let mut nonce = vec![0u8; 32];
+    OsRng.fill_bytes(&mut nonce);
+    nonce
+}
+
+/// The run of the channel between party and peer, the same at both ends.
+pub fn channel_run(party: u16, nonce: &[u8], peer: u16, peer_nonce: &[u8]) -> Vec<u8> {
+    let (first, second) = if party < peer {
+        (nonce, peer_nonce)
+    } else {
+        (peer_nonce, nonce)
+    };
+    let mut run = Vec::new();
+    for nonce in [first, second].iter() {
+        run.extend_from_slice(&(nonce.len() as u32).to_be_bytes());
+        run.extend_from_slice(nonce);
+    }
+    run
+}
+
+fn derive_key(shared: &[u8], session: &str, run: &[u8], sender: u16, receiver: u16) -> [u8; 32] {
+    let mut prk = [0u8; 32];
+    hkdf_extract(Sha256::new(), session.as_bytes(), shared, &mut prk);
+    let mut info = format!("multi-party-ecdsa p2p {} to {} ", sender, receiver).into_bytes();
+    info.extend_from_slice(run);
+    let mut key = [0u8; 32];
+    hkdf_expand(Sha256::new(), &prk, &info, &mut key);
+    prk.zeroize();
+    key
+}
+
+fn nonce(counter: u64) -> [u8; 12] {
+    let mut nonce = [0u8; 12];
+    nonce[4..].copy_from_slice(&counter.to_be_bytes());
+    nonce
+}
+
+// every field length prefixed, so no two different headers give the same bytes
+fn aad(session: &str, sender: u16, receiver: u16, round: &str, counter: u64) -> Vec<u8> {
+    let mut aad = Vec::new();
+    for field in [session.as_bytes(), round.as_bytes()].iter() {
+        aad.extend_from_slice(&(field.len() as u32).to_be_bytes());
+        aad.extend_from_slice(field);
+    }
+    aad.extend_from_slice(&sender.to_be_bytes());
+    aad.extend_from_slice(&receiver.to_be_bytes());
+    aad.extend_from_slice(&counter.to_be_bytes());
+    aad
+}
@@ -0,0 +1,243 @@
+/*
+    Multi-party ECDSA
+
+    Copyright 2018 by Kzen Networks
+
+    This file is part of Multi-party ECDSA library
+    (https://github.com/KZen-networks/multi-party-ecdsa)
+
+    Multi-party ECDSA is free software: you can redistribute
+    it and/or modify it under the terms of the GNU General Public
+    License as published by the Free Software Foundation, either
+    version 3 of the License, or (at your option) any later version.
+
+    @license GPL-3.0+ <https://github.com/KZen-networks/multi-party-ecdsa/blob/master/LICENSE>
+*/
+
+/// Direct connections between the parties, no relay. Every party listens on its own address
+/// and opens one connection to each peer it sends to. Envelopes go over the wire as json,
+/// each prefixed with its length as a big endian u32. A frame that does not parse, or an
+/// envelope addressed to another party, is dropped; an oversized frame closes only the
+/// connection it came on.
+use std::collections::HashMap;
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender};
+use std::sync::Arc;
+use std::thread;
+use std::time::Instant;
+
+use super::{aborted, timeout, Backoff, Envelope, Transport, TransportError, ABORT_ROUND};
+
+// larger frames are refused rather than allocated
+const MAX_FRAME: usize = 1 << 26;
+
+pub struct TcpTransport {
+    party: u16,
+    local: SocketAddr,
+    peers: HashMap<u16, SocketAddr>,
+    connections: HashMap<u16, TcpStream>,
+    // Err only to wake a blocked receive when the backoff is cancelled
+    incoming: Receiver<Result<Envelope, TransportError>>,
+    // messages that arrived before anyone asked for them
+    pending: Vec<Envelope>,
+    backoff: Backoff,
+    closed: Arc<AtomicBool>,
+}
+
+impl TcpTransport {
+    /// party accepts connections on listener and reaches the other parties at peers. Sends
+    /// retry with backoff until the peer is listening, receives wait for backoff.timeout.
+    pub fn new(
+        party: u16,
+        listener: TcpListener,
+        peers: HashMap<u16, SocketAddr>,
+        backoff: Backoff,
+    ) -> Result<Self, TransportError> {
+        let local = listener.local_addr().map_err(connection)?;
+        let closed = Arc::new(AtomicBool::new(false));
+        let (sender, incoming) = channel();
+        let wake = sender.clone();
+        backoff.on_cancel(move || {
+            let _ = wake.send(Err(TransportError::Cancelled));
+        });
+        let accepting = closed.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                if accepting.load(Ordering::SeqCst) {
+                    break;
+                }
+                if let Ok(stream) = stream {
+                    let sender = sender.clone();
+                    thread::spawn(move || read_frames(stream, party, sender));
+                }
+            }
+        });
+        Ok(TcpTransport {
+            party,
+            local,
+            peers,
+            connections: HashMap::new(),
+            incoming,
+            pending: Vec::new(),
+            backoff,
+            closed,
+        })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local
+    }
+
+    fn write(&mut self, receiver: u16, envelope: &Envelope) -> Result<(), TransportError> {
+        let body = serde_json::to_vec(envelope).expect("unserializable envelope");
+        let mut frame = (body.len() as u32).to_be_bytes().to_vec();
+        frame.extend(body);
+        let stream = self.connect(receiver)?;
+        if let Err(e) = stream.write_all(&frame) {
+            // reconnect on the next send
+            self.connections.remove(&receiver);
+            return Err(connection(e));
+        }
+        Ok(())
+    }
+
+    fn connect(&mut self, receiver: u16) -> Result<&mut TcpStream, TransportError> {
+        if !self.connections.contains_key(&receiver) {
+            let address = *self
+                .peers
+                .get(&receiver)
+                .ok_or(TransportError::UnknownParty(receiver))?;
+            let stream = self
+                .backoff
+                .poll(|| Ok(TcpStream::connect(address).ok()))?
+                .ok_or_else(|| {
+                    TransportError::Connection(format!("party {} at {}", receiver, address))
+                })?;
+            self.connections.insert(receiver, stream);
+        }
+        Ok(self.connections.get_mut(&receiver).unwrap())
+    }
+}
+
+impl Transport for TcpTransport {
+    fn party(&self) -> u16 {
+        self.party
+    }
+
+    fn broadcast(&mut self, session: &str, round: &str, body: &str) -> Result<(), TransportError> {
+        let envelope = Envelope {
+            session: session.to_string(),
+            round: round.to_string(),
+            sender: self.party,
+            receiver: None,
+            body: body.to_string(),
+        };
+        let mut receivers = self.peers.keys().cloned().collect::<Vec<u16>>();
+        receivers.sort();
+        for receiver in receivers {
+            self.write(receiver, &envelope)?;
+        }
+        Ok(())
+    }
+
+    fn send(
+        &mut self,
+        session: &str,
+        round: &str,
+        receiver: u16,
+        body: &str,
+    ) -> Result<(), TransportError> {
+        let envelope = Envelope {
+            session: session.to_string(),
+            round: round.to_string(),
+            sender: self.party,
+            receiver: Some(receiver),
+            body: body.to_string(),
+        };
+        self.write(receiver, &envelope)
+    }
+
+    fn receive(
+        &mut self,
+        session: &str,
+        round: &str,
+        sender: u16,
+    ) -> Result<Envelope, TransportError> {
+        let start = Instant::now();
+        loop {
+            let abort = self
+                .pending
+                .iter()
+                .find(|e| e.session == session && e.round == ABORT_ROUND);
+            if let Some(abort) = abort {
+                return Err(aborted(abort));
+            }
+            if let Some(pos) = self
+                .pending
+                .iter()
+                .position(|e| e.session == session && e.round == round && e.sender == sender)
+            {
+                return Ok(self.pending.remove(pos));
+            }
+            let envelope = match self.backoff.slice(start)? {
+                None => self.incoming.recv().map_err(|_| TransportError::Closed)?,
+                Some(slice) if slice.as_nanos() == 0 => {
+                    return Err(timeout(session, round, sender))
+                }
+                Some(slice) => match self.incoming.recv_timeout(slice) {
+                    Ok(envelope) => envelope,
+                    Err(RecvTimeoutError::Timeout) => continue,
+                    Err(RecvTimeoutError::Disconnected) => return Err(TransportError::Closed),
+                },
+            };
+            self.pending.push(envelope?);
+        }
+    }
+}
+
+impl Drop for TcpTransport {
+    fn drop(&mut self) {
+        // wake the accepting thread up so it sees the flag
+        self.closed.store(true, Ordering::SeqCst);
+        let _ = TcpStream::connect(self.local);
+    }
+}
+
+// what one peer sends never fails a receive, the other connections go on
+fn read_frames(
+    mut stream: TcpStream,
+    party: u16,
+    sender: Sender<Result<Envelope, TransportError>>,
+) {
+    loop {
+        let mut length = [0u8; 4];
+        if stream.read_exact(&mut length).is_err() {
+            // peer hung up
+            return;
+        }
+        let length = u32::from_be_bytes(length) as usize;
+        if length > MAX_FRAME {
+            return;
+        }
+        let mut body = vec![0u8; length];
+        if stream.read_exact(&mut body).is_err() {
+            return;
+        }
+        let envelope = match serde_json::from_slice::<Envelope>(&body) {
+            Ok(envelope) => envelope,
+            Err(_) => continue,
+        };
+        if envelope.receiver.is_some() && envelope.receiver != Some(party) {
+            continue;
+        }
+        if sender.send(Ok(envelope)).is_err() {
+            return;
+        }
+    }
+}
+
+fn connection(e: io::Error) -> TransportError {
+    TransportError::Connection(e.to_string())
+}
@@ -0,0 +1,466 @@
+use crate::transport::echo::echo;
+use crate::transport::identity::{IdentityKey, SignedTransport};
+use crate::transport::memory::MemoryTransport;
+use crate::transport::secure::{channel_run, run_nonce, SecureChannel, SecureTransport};
+use crate::transport::tcp::TcpTransport;
+use crate::transport::{Backoff, Cancel, Envelope, Transport, TransportError};
+use crate::Error::{InvalidCiphertext, InvalidSig};
+use curv::elliptic::curves::traits::{ECPoint, ECScalar};
+use curv::{FE, GE};
+use std::collections::HashMap;
+use std::io::Write;
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+use std::time::{Duration, Instant};
+
+fn backoff(timeout: u64) -> Backoff {
+    Backoff {
+        timeout: Some(Duration::from_millis(timeout)),
+        ..Backoff::default()
+    }
+}
+
+// every party broadcasts its number in round "bc" and sends it to the next party in round "p2p"
+fn exchange<T: Transport + Send + 'static>(transports: Vec<T>) {
+    let parties = transports.len() as u16;
+    let handles = transports
+        .into_iter()
+        .map(|mut transport| {
+            thread::spawn(move || {
+                let me = transport.party();
+                transport.broadcast("s", "bc", &me.to_string()).unwrap();
+                transport
+                    .send("s", "p2p", me % parties + 1, &(me * 10).to_string())
+                    .unwrap();
+                // p2p first, the broadcasts have to wait for it
+                let previous = (me + parties - 2) % parties + 1;
+                let p2p = transport.receive("s", "p2p", previous).unwrap();
+                assert_eq!(p2p.receiver, Some(me));
+                assert_eq!(p2p.body, (previous * 10).to_string());
+                for sender in (1..=parties).filter(|&i| i != me) {
+                    let bc = transport.receive("s", "bc", sender).unwrap();
+                    assert_eq!((bc.sender, bc.receiver), (sender, None));
+                    assert_eq!(bc.body, sender.to_string());
+                }
+                transport
+            })
+        })
+        .collect::<Vec<_>>();
+    // keep every transport alive until all are done
+    let _transports = handles
+        .into_iter()
+        .map(|h| h.join().unwrap())
+        .collect::<Vec<T>>();
+}
+
+#[test]
+fn test_backoff_gives_up_after_timeout() {
+    let mut attempts = 0;
+    let start = Instant::now();
+    let result: Option<()> = backoff(100)
+        .poll(|| {
+            attempts += 1;
+            Ok(None)
+        })
+        .unwrap();
+    assert_eq!(result, None);
+    assert!(start.elapsed() >= Duration::from_millis(100));
+    // waits of 10, 20, 40 and the rest of the 100ms at most
+    assert!((2..=5).contains(&attempts));
+
+    let mut attempts = 0;
+    let result = backoff(100).poll(|| {
+        attempts += 1;
+        Ok(if attempts == 3 { Some(attempts) } else { None })
+    });
+    assert_eq!(result, Ok(Some(3)));
+}
+
+#[test]
+fn test_memory_transport() {
+    exchange(MemoryTransport::network(4, Backoff::default()));
+
+    let mut transports = MemoryTransport::network(2, backoff(50));
+    transports[0].broadcast("s", "first", "x").unwrap();
+    // other sessions and rounds do not count
+    assert_eq!(
+        transports[1].receive("t", "first", 1),
+        Err(TransportError::Timeout {
+            session: "t".to_string(),
+            round: "first".to_string(),
+            sender: 1,
+        })
+    );
+    assert!(transports[1].receive("s", "second", 1).is_err());
+    assert_eq!(transports[1].receive("s", "first", 1).unwrap().body, "x");
+}
+
+#[test]
+fn test_tcp_transport() {
+    let listeners = (0..3)
+        .map(|_| TcpListener::bind("127.0.0.1:0").unwrap())
+        .collect::<Vec<_>>();
+    let addresses = listeners
+        .iter()
+        .map(|l| l.local_addr().unwrap())
+        .collect::<Vec<_>>();
+    let transports = listeners
+        .into_iter()
+        .enumerate()
+        .map(|(i, listener)| {
+            let party = i as u16 + 1;
+            let peers = addresses
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .map(|(j, address)| (j as u16 + 1, *address))
+                .collect::<HashMap<_, _>>();
+            TcpTransport::new(party, listener, peers, backoff(5000)).unwrap()
+        })
+        .collect::<Vec<_>>();
+    exchange(transports);
+}
+
+#[test]
+fn test_tcp_unknown_and_unreachable_party() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    // an address nobody listens on any more
+    let gone = TcpListener::bind("127.0.0.1:0").unwrap();
+    let gone_address = gone.local_addr().unwrap();
+    drop(gone);
+    let mut peers = HashMap::new();
+    peers.insert(2, gone_address);
+    let mut transport = TcpTransport::new(1, listener, peers, backoff(50)).unwrap();
+    assert_eq!(
+        transport.send("s", "r", 3, "x"),
+        Err(TransportError::UnknownParty(3))
+    );
+    match transport.send("s", "r", 2, "x") {
+        Err(TransportError::Connection(_)) => (),
+        e => panic!("unexpected {:?}", e),
+    }
+}
+
+#[test]
+fn test_tcp_drops_bad_frames() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let mut transport = TcpTransport::new(1, listener, HashMap::new(), backoff(500)).unwrap();
+    let frame = |body: &[u8]| {
+        let mut frame = (body.len() as u32).to_be_bytes().to_vec();
+        frame.extend(body);
+        frame
+    };
+    let envelope = |receiver: u16, body: &str| Envelope {
+        session: "s".to_string(),
+        round: "r".to_string(),
+        sender: 2,
+        receiver: Some(receiver),
+        body: body.to_string(),
+    };
+
+    // a peer whose connection ends with an oversized frame
+    let mut oversized = TcpStream::connect(transport.local_addr()).unwrap();
+    oversized
+        .write_all(&u32::max_value().to_be_bytes())
+        .unwrap();
+    let mut stream = TcpStream::connect(transport.local_addr()).unwrap();
+    stream.write_all(&frame(b"not json")).unwrap();
+    let misaddressed = serde_json::to_vec(&envelope(3, "for 3")).unwrap();
+    stream.write_all(&frame(&misaddressed)).unwrap();
+    let mine = serde_json::to_vec(&envelope(1, "for 1")).unwrap();
+    stream.write_all(&frame(&mine)).unwrap();
+
+    assert_eq!(transport.receive("s", "r", 2).unwrap().body, "for 1");
+    match transport.receive("s", "r", 2) {
+        Err(TransportError::Timeout { .. }) => (),
+        e => panic!("unexpected {:?}", e),
+    }
+}
+
+#[test]
+fn test_secure_channel() {
+    let a: FE = ECScalar::new_random();
+    let b: FE = ECScalar::new_random();
+    let g: GE = ECPoint::generator();
+    let (nonce_one, nonce_two) = (run_nonce(), run_nonce());
+    let run = channel_run(1, &nonce_one, 2, &nonce_two);
+    assert_eq!(run, channel_run(2, &nonce_two, 1, &nonce_one));
+    let mut one = SecureChannel::new("s", &run, 1, &a, 2, &(g * b));
+    let mut two = SecureChannel::new("s", &run, 2, &b, 1, &(g * a));
+
+    let first = one.seal("round3", b"share");
+    let second = one.seal("round3", b"share");
+    assert_ne!(first.ciphertext, second.ciphertext);
+    assert_eq!(two.open("round3", &first).unwrap(), b"share".to_vec());
+    // replayed, in another round, back to the sender, in another session or another run of
+    // the same session
+    assert_eq!(two.open("round3", &first), Err(InvalidCiphertext));
+    assert_eq!(two.open("round4", &second), Err(InvalidCiphertext));
+    assert_eq!(one.open("round3", &second), Err(InvalidCiphertext));
+    let mut other = SecureChannel::new("t", &run, 2, &b, 1, &(g * a));
+    assert_eq!(other.open("round3", &second), Err(InvalidCiphertext));
+    let rerun = channel_run(1, &nonce_one, 2, &run_nonce());
+    let mut other = SecureChannel::new("s", &rerun, 2, &b, 1, &(g * a));
+    assert_eq!(other.open("round3", &second), Err(InvalidCiphertext));
+    let mut tampered = second.clone();
+    tampered.ciphertext[0] ^= 1;
+    assert_eq!(two.open("round3", &tampered), Err(InvalidCiphertext));
+    assert_eq!(two.open("round3", &second).unwrap(), b"share".to_vec());
+}
+
+#[test]
+fn test_secure_transport() {
+    let g: GE = ECPoint::generator();
+    let identities = (0..3).map(|_| FE::new_random()).collect::<Vec<FE>>();
+    let roster = identities
+        .iter()
+        .enumerate()
+        .map(|(i, x)| (i as u16 + 1, g * x))
+        .collect::<HashMap<u16, GE>>();
+    let nonces = (1..=3)
+        .map(|i| (i, run_nonce()))
+        .collect::<HashMap<u16, Vec<u8>>>();
+    let transports = MemoryTransport::network(3, backoff(5000))
+        .into_iter()
+        .zip(identities)
+        .map(|(transport, identity)| {
+            SecureTransport::new(transport, identity, roster.clone(), nonces.clone())
+        })
+        .collect::<Vec<_>>();
+    exchange(transports);
+}
+
+#[test]
+fn test_identity_signature() {
+    let identity = IdentityKey::new();
+    let signature = identity.sign("s", "round1", 2, b"payload");
+    signature
+        .verify(&identity.public, "s", "round1", 2, b"payload")
+        .unwrap();
+    let other = IdentityKey::new();
+    for (public, session, round, sender, payload) in vec![
+        (&other.public, "s", "round1", 2, &b"payload"[..]),
+        (&identity.public, "t", "round1", 2, &b"payload"[..]),
+        (&identity.public, "s", "round2", 2, &b"payload"[..]),
+        (&identity.public, "s", "round1", 3, &b"payload"[..]),
+        (&identity.public, "s", "round1", 2, &b"payloae"[..]),
+    ] {
+        assert_eq!(
+            signature.verify(public, session, round, sender, payload),
+            Err(InvalidSig)
+        );
+    }
+    assert!(!format!("{:?}", identity).contains("secret"));
+}
+
+#[test]
+fn test_signed_transport_names_impersonator() {
+    let identities = (0..3).map(|_| IdentityKey::new()).collect::<Vec<_>>();
+    let roster = identities
+        .iter()
+        .enumerate()
+        .map(|(i, identity)| (i as u16 + 1, identity.public))
+        .collect::<HashMap<u16, GE>>();
+    let mut transports = MemoryTransport::network(3, backoff(5000))
+        .into_iter()
+        .zip(identities.clone())
+        .map(|(transport, identity)| SignedTransport::new(transport, identity, roster.clone()))
+        .collect::<Vec<_>>();
+    transports[0].broadcast("s", "round1", "hello").unwrap();
+    transports[0].send("s", "round2", 2, "for two").unwrap();
+    assert_eq!(
+        transports[1].receive("s", "round1", 1).unwrap().body,
+        "hello"
+    );
+    assert_eq!(
+        transports[2].receive("s", "round1", 1).unwrap().body,
+        "hello"
+    );
+    assert_eq!(
+        transports[1].receive("s", "round2", 1).unwrap().body,
+        "for two"
+    );
+
+    // party 3 posts as party 1, signing with its own key
+    let mut board = MemoryTransport::network(3, backoff(5000));
+    let mut impostor = SignedTransport::new(board.remove(0), identities[2].clone(), roster.clone());
+    let mut honest = SignedTransport::new(board.remove(0), identities[1].clone(), roster);
+    impostor.broadcast("s", "round1", "evil").unwrap();
+    assert_eq!(
+        honest.receive("s", "round1", 1),
+        Err(TransportError::Unauthenticated {
+            round: "round1".to_string(),
+            sender: 1,
+        })
+    );
+}
+
+// each party broadcasts bodies[party - 1] in round1, or sends it p2p when it is a list of one
+// body per receiver, then runs the echo round
+fn echo_with(bodies: Vec<Vec<&'static str>>) -> Vec<Result<(), TransportError>> {
+    let parties = bodies.len() as u16;
+    let handles = MemoryTransport::network(parties, backoff(5000))
+        .into_iter()
+        .zip(bodies)
+        .map(|(mut transport, bodies)| {
+            thread::spawn(move || {
+                let me = transport.party();
+                let own = if bodies.len() == 1 {
+                    bodies[0]
+                } else {
+                    bodies[me as usize - 1]
+                };
+                if bodies.len() == 1 {
+                    transport.broadcast("s", "round1", bodies[0]).unwrap();
+                } else {
+                    for (i, body) in bodies.iter().enumerate() {
+                        transport.send("s", "round1", i as u16 + 1, body).unwrap();
+                    }
+                }
+                let mut received = (1..=parties)
+                    .filter(|&i| i != me)
+                    .map(|i| transport.receive("s", "round1", i).unwrap())
+                    .collect::<Vec<_>>();
+                received.push(Envelope {
+                    session: "s".to_string(),
+                    round: "round1".to_string(),
+                    sender: me,
+                    receiver: None,
+                    body: own.to_string(),
+                });
+                echo(&mut transport, "s", "round1", &received)
+            })
+        })
+        .collect::<Vec<_>>();
+    handles.into_iter().map(|h| h.join().unwrap()).collect()
+}
+
+#[test]
+fn test_echo_names_sender_and_echoer() {
+    for result in echo_with(vec![vec!["a"], vec!["b"], vec!["c"]]) {
+        assert_eq!(result, Ok(()));
+    }
+
+    // party 1 tells 2 and 3 different things, they cannot tell whether 1 equivocated or lied
+    // in its echo
+    let results = echo_with(vec![vec!["x", "y", "z"], vec!["b"], vec!["c"]]);
+    for result in &results[1..] {
+        assert_eq!(
+            *result,
+            Err(TransportError::Equivocation {
+                round: "round1".to_string(),
+                sender: 1,
+                echoer: 1,
+            })
+        );
+    }
+}
+
+#[test]
+fn test_receive_all() {
+    let identities = (0..3).map(|_| IdentityKey::new()).collect::<Vec<_>>();
+    let roster = identities
+        .iter()
+        .enumerate()
+        .map(|(i, identity)| (i as u16 + 1, identity.public))
+        .collect::<HashMap<u16, GE>>();
+    let mut transports = MemoryTransport::network(3, backoff(5000))
+        .into_iter()
+        .zip(identities)
+        .map(|(transport, identity)| SignedTransport::new(transport, identity, roster.clone()))
+        .collect::<Vec<_>>();
+    transports[2].broadcast("s", "round1", "three").unwrap();
+    transports[1].send("s", "round1", 1, "two").unwrap();
+    let received = transports[0].receive_all("s", "round1", &[3, 2]).unwrap();
+    let received = received
+        .iter()
+        .map(|e| (e.sender, e.receiver, e.body.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(received, vec![(3, None, "three"), (2, Some(1), "two")]);
+
+    let mut transports = MemoryTransport::network(3, backoff(50));
+    transports[1].broadcast("s", "round1", "two").unwrap();
+    assert_eq!(
+        transports[0].receive_all("s", "round1", &[2, 3]),
+        Err(TransportError::Timeout {
+            session: "s".to_string(),
+            round: "round1".to_string(),
+            sender: 3,
+        })
+    );
+}
+
+#[test]
+fn test_abort_and_cancel() {
+    let identities = (0..3).map(|_| IdentityKey::new()).collect::<Vec<_>>();
+    let roster = identities
+        .iter()
+        .enumerate()
+        .map(|(i, identity)| (i as u16 + 1, identity.public))
+        .collect::<HashMap<u16, GE>>();
+    let mut transports = MemoryTransport::network(3, Backoff::default())
+        .into_iter()
+        .zip(identities)
+        .map(|(transport, identity)| SignedTransport::new(transport, identity, roster.clone()))
+        .collect::<Vec<_>>();
+    transports[1]
+        .abort("s", "round2", "no message from party 3")
+        .unwrap();
+    assert_eq!(
+        transports[0].receive("s", "round2", 3),
+        Err(TransportError::Aborted {
+            party: 2,
+            round: "round2".to_string(),
+            reason: "no message from party 3".to_string(),
+        })
+    );
+    // an abort only stops the session it was sent in
+    transports[1].broadcast("t", "round1", "two").unwrap();
+    assert_eq!(transports[0].receive("t", "round1", 2).unwrap().body, "two");
+
+    let cancel = Cancel::new();
+    let mut transports = MemoryTransport::network(
+        2,
+        Backoff {
+            cancel: Some(cancel.clone()),
+            ..Backoff::default()
+        },
+    );
+    let canceller = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        cancel.cancel();
+    });
+    assert_eq!(
+        transports[0].receive("s", "round1", 2),
+        Err(TransportError::Cancelled)
+    );
+    canceller.join().unwrap();
+
+    // a receive without timeout blocks on the connection until the cancel wakes it up
+    let cancel = Cancel::new();
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let mut transport = TcpTransport::new(
+        1,
+        listener,
+        HashMap::new(),
+        Backoff {
+            cancel: Some(cancel.clone()),
+            ..Backoff::default()
+        },
+    )
+    .unwrap();
+    let canceller = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        cancel.cancel();
+    });
+    assert_eq!(
+        transport.receive("s", "round1", 2),
+        Err(TransportError::Cancelled)
+    );
+    canceller.join().unwrap();
+    // and so does every later one
+    assert_eq!(
+        transport.receive("s", "round1", 2),
+        Err(TransportError::Cancelled)
+    );
+}
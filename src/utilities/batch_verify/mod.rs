@@ -0,0 +1,168 @@
+/*
+    Multi-party ECDSA
+
+    Copyright 2018 by Kzen Networks
+
+    This file is part of Multi-party ECDSA library
+    (https://github.com/KZen-networks/multi-party-ecdsa)
+
+    Multi-party ECDSA is free software: you can redistribute
+    it and/or modify it under the terms of the GNU General Public
+    License as published by the Free Software Foundation, either
+    version 3 of the License, or (at your option) any later version.
+
+    @license GPL-3.0+ <https://github.com/KZen-networks/multi-party-ecdsa/blob/master/LICENSE>
+*/
+
+/// Randomized batch verification of the checks that keygen and signing run once per party.
+/// Every verification equation is of the form sum_j c_j * P_j = 0, so n of them can be
+/// combined with random 128 bit coefficients into a single multi-scalar multiplication.
+/// A false positive happens with probability about 2^-128.
+/// When a batch fails we fall back to the individual checks and return the indices of the
+/// parties that failed, so the caller can assign blame.
+use curv::arithmetic::traits::*;
+use curv::cryptographic_primitives::hashing::hash_sha256::HSha256;
+use curv::cryptographic_primitives::hashing::traits::Hash;
+use curv::cryptographic_primitives::proofs::sigma_correct_homomorphic_elgamal_enc::*;
+use curv::cryptographic_primitives::proofs::sigma_dlog::{DLogProof, ProveDLog};
+use curv::cryptographic_primitives::secret_sharing::feldman_vss::VerifiableSS;
+use curv::elliptic::curves::traits::*;
+use curv::{BigInt, FE, GE};
+
+use crate::utilities::multiexp::multi_scalar_mul;
+use crate::utilities::parallel;
+
+const BATCH_RANDOMIZER_BITS: usize = 128;
+
+// checks g^{shares[j]} = prod_k vss_scheme_vec[j].commitments[k]^{index^k} for all dealers j
+pub fn validate_shares(
+    vss_scheme_vec: &[VerifiableSS],
+    shares: &[FE],
+    index: usize,
+) -> Result<(), Vec<usize>> {
+    assert_eq!(vss_scheme_vec.len(), shares.len());
+    let g: GE = ECPoint::generator();
+    let index_fe: FE = ECScalar::from(&BigInt::from(index as u32));
+
+    let mut scalars = Vec::new();
+    let mut points = Vec::new();
+    let mut g_scalar = FE::zero();
+    for (j, vss_scheme) in vss_scheme_vec.iter().enumerate() {
+        let a_j = randomizer(j);
+        g_scalar = g_scalar + a_j * shares[j];
+        // a_j * index^k
+        let mut coef = a_j;
+        for commitment in &vss_scheme.commitments {
+            scalars.push(negate(&coef));
+            points.push(*commitment);
+            coef = coef * index_fe;
+        }
+    }
+    scalars.push(g_scalar);
+    points.push(g);
+
+    batch_or_blame(&scalars, &points, vss_scheme_vec.len(), |j| {
+        vss_scheme_vec[j].validate_share(&shares[j], index).is_ok()
+    })
+}
+
+// checks g^{challenge_response} * pk^{challenge} = pk_t_rand_commitment for all proofs
+pub fn verify_dlog_proofs(proofs: &[DLogProof]) -> Result<(), Vec<usize>> {
+    let g: GE = ECPoint::generator();
+
+    let mut scalars = Vec::new();
+    let mut points = Vec::new();
+    let mut g_scalar = FE::zero();
+    for (i, proof) in proofs.iter().enumerate() {
+        let a_i = randomizer(i);
+        let challenge = HSha256::create_hash(&[
+            &proof.pk_t_rand_commitment.bytes_compressed_to_big_int(),
+            &g.bytes_compressed_to_big_int(),
+            &proof.pk.bytes_compressed_to_big_int(),
+        ]);
+        let challenge_fe: FE = ECScalar::from(&challenge);
+        g_scalar = g_scalar + a_i * proof.challenge_response;
+        scalars.push(a_i * challenge_fe);
+        points.push(proof.pk);
+        scalars.push(negate(&a_i));
+        points.push(proof.pk_t_rand_commitment);
+    }
+    scalars.push(g_scalar);
+    points.push(g);
+
+    batch_or_blame(&scalars, &points, proofs.len(), |i| {
+        DLogProof::verify(&proofs[i]).is_ok()
+    })
+}
+
+// checks H^{z1} * Y^{z2} = T * D^e and G^{z2} = A3 * E^e for all proofs
+pub fn verify_homo_elgamal_proofs(
+    proofs: &[HomoELGamalProof],
+    statements: &[HomoElGamalStatement],
+) -> Result<(), Vec<usize>> {
+    assert_eq!(proofs.len(), statements.len());
+
+    let mut scalars = Vec::new();
+    let mut points = Vec::new();
+    for (i, (proof, delta)) in proofs.iter().zip(statements.iter()).enumerate() {
+        // the two equations of every proof get independent coefficients
+        let a_i = randomizer(2 * i);
+        let b_i = randomizer(2 * i + 1);
+        let e = HSha256::create_hash_from_ge(&[
+            &proof.T, &proof.A3, &delta.G, &delta.H, &delta.Y, &delta.D, &delta.E,
+        ]);
+
+        scalars.push(a_i * proof.z1);
+        points.push(delta.H);
+        scalars.push(a_i * proof.z2);
+        points.push(delta.Y);
+        scalars.push(negate(&a_i));
+        points.push(proof.T);
+        scalars.push(negate(&(a_i * e)));
+        points.push(delta.D);
+
+        scalars.push(b_i * proof.z2);
+        points.push(delta.G);
+        scalars.push(negate(&b_i));
+        points.push(proof.A3);
+        scalars.push(negate(&(b_i * e)));
+        points.push(delta.E);
+    }
+
+    batch_or_blame(&scalars, &points, proofs.len(), |i| {
+        proofs[i].verify(&statements[i]).is_ok()
+    })
+}
+
+// the combined equation holds iff the multi-scalar multiplication is the point at infinity
+fn batch_or_blame<F>(scalars: &[FE], points: &[GE], len: usize, check: F) -> Result<(), Vec<usize>>
+where
+    F: Fn(usize) -> bool + Sync + Send,
+{
+    if len == 0 || multi_scalar_mul(scalars, points).is_none() {
+        return Ok(());
+    }
+    let valid = parallel::map_indices(len, check);
+    let invalid = (0..len).filter(|&i| !valid[i]).collect::<Vec<usize>>();
+    if invalid.is_empty() {
+        Ok(())
+    } else {
+        Err(invalid)
+    }
+}
+
+// the first coefficient can be fixed to one
+fn randomizer(i: usize) -> FE {
+    if i == 0 {
+        ECScalar::from(&BigInt::one())
+    } else {
+        ECScalar::from(&BigInt::sample(BATCH_RANDOMIZER_BITS))
+    }
+}
+
+fn negate(x: &FE) -> FE {
+    FE::zero().sub(&x.get_element())
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,71 @@
+#![allow(non_snake_case)]
+use crate::utilities::batch_verify::*;
+use curv::cryptographic_primitives::proofs::sigma_correct_homomorphic_elgamal_enc::*;
+use curv::cryptographic_primitives::proofs::sigma_dlog::{DLogProof, ProveDLog};
+use curv::cryptographic_primitives::secret_sharing::feldman_vss::VerifiableSS;
+use curv::elliptic::curves::traits::*;
+use curv::{FE, GE};
+
+#[test]
+fn test_validate_shares() {
+    let (t, n) = (2, 5);
+    let dealers = (0..n)
+        .map(|_| VerifiableSS::share(t, n, &ECScalar::new_random()))
+        .collect::<Vec<(VerifiableSS, Vec<FE>)>>();
+    let vss_scheme_vec = dealers.iter().map(|d| d.0.clone()).collect::<Vec<_>>();
+
+    for index in 1..=n {
+        let mut shares = dealers.iter().map(|d| d.1[index - 1]).collect::<Vec<FE>>();
+        validate_shares(&vss_scheme_vec, &shares, index).expect("invalid shares");
+
+        shares[1] = shares[1] + shares[0];
+        assert_eq!(
+            validate_shares(&vss_scheme_vec, &shares, index),
+            Err(vec![1])
+        );
+    }
+}
+
+#[test]
+fn test_verify_dlog_proofs() {
+    let mut proofs = (0..6)
+        .map(|_| DLogProof::prove(&ECScalar::new_random()))
+        .collect::<Vec<DLogProof>>();
+    verify_dlog_proofs(&proofs).expect("invalid proofs");
+
+    proofs[4].pk = proofs[3].pk;
+    assert_eq!(verify_dlog_proofs(&proofs), Err(vec![4]));
+}
+
+#[test]
+fn test_verify_homo_elgamal_proofs() {
+    let g: GE = ECPoint::generator();
+    let mut proofs = Vec::new();
+    let mut statements = Vec::new();
+    let h_scalar: FE = ECScalar::new_random();
+    let H = g * h_scalar;
+    for _ in 0..4 {
+        let witness = HomoElGamalWitness {
+            r: ECScalar::new_random(),
+            x: ECScalar::new_random(),
+        };
+        let a: FE = ECScalar::new_random();
+        let A = g * a;
+        let delta = HomoElGamalStatement {
+            G: A,
+            H,
+            Y: g,
+            D: H * witness.x + g * witness.r,
+            E: A * witness.r,
+        };
+        proofs.push(HomoELGamalProof::prove(&witness, &delta));
+        statements.push(delta);
+    }
+    verify_homo_elgamal_proofs(&proofs, &statements).expect("invalid proofs");
+
+    statements[2].E = statements[2].E + g;
+    assert_eq!(
+        verify_homo_elgamal_proofs(&proofs, &statements),
+        Err(vec![2])
+    );
+}
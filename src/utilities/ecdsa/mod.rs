@@ -0,0 +1,204 @@
+/*
+    Multi-party ECDSA
+
+    Copyright 2018 by Kzen Networks
+
+    This file is part of Multi-party ECDSA library
+    (https://github.com/KZen-networks/multi-party-ecdsa)
+
+    Multi-party ECDSA is free software: you can redistribute
+    it and/or modify it under the terms of the GNU General Public
+    License as published by the Free Software Foundation, either
+    version 3 of the License, or (at your option) any later version.
+
+    @license GPL-3.0+ <https://github.com/KZen-networks/multi-party-ecdsa/blob/master/LICENSE>
+*/
+
+/// ECDSA verification shared by all the protocols in the library.
+/// u1 * G + u2 * Y is computed with a single simultaneous multiplication and the
+/// x coordinate is compared to r in constant time.
+/// Batch verification recovers R from (r, recid) and checks a random linear combination
+/// sum_i a_i * (e_i * G + r_i * Y_i - s_i * R_i) = 0 with one multi-scalar multiplication.
+use curv::arithmetic::traits::*;
+use curv::elliptic::curves::traits::*;
+use curv::{BigInt, FE, GE};
+use serde::{Deserialize, Serialize};
+use subtle::ConstantTimeEq;
+
+use crate::utilities::multiexp::multi_scalar_mul;
+use crate::utilities::parallel;
+use crate::Error::{self, InvalidSig};
+
+// secp256k1 field prime
+const FIELD_PRIME: &str = "fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f";
+// size of the random coefficients of the linear combination
+const BATCH_RANDOMIZER_BITS: usize = 128;
+
+/// An ECDSA signature with the recovery id of R, as the threshold protocols output it.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SignatureRecid {
+    pub r: FE,
+    pub s: FE,
+    pub recid: u8,
+}
+
+#[derive(Copy, PartialEq, Eq, Clone, Debug)]
+pub enum Malleability {
+    // both (r, s) and (r, q - s) are accepted
+    Allow,
+    // only s <= q/2 is accepted (BIP-62 low s)
+    RequireLowS,
+}
+
+pub fn verify(
+    r: &BigInt,
+    s: &BigInt,
+    pubkey: &GE,
+    message: &BigInt,
+    malleability: Malleability,
+) -> Result<(), Error> {
+    let q = FE::q();
+    let zero = BigInt::zero();
+    if r <= &zero || r >= &q || s <= &zero || s >= &q {
+        return Err(InvalidSig);
+    }
+    if malleability == Malleability::RequireLowS && s > &(&q - s) {
+        return Err(InvalidSig);
+    }
+
+    let s_fe: FE = ECScalar::from(s);
+    let r_fe: FE = ECScalar::from(r);
+    let e_fe: FE = ECScalar::from(&message.mod_floor(&q));
+    let s_inv = s_fe.invert();
+    let u1 = e_fe * s_inv;
+    let u2 = r_fe * s_inv;
+
+    let g: GE = ECPoint::generator();
+    let point = match multi_scalar_mul(&[u1, u2], &[g, *pubkey]) {
+        Some(point) => point,
+        None => return Err(InvalidSig),
+    };
+    let rx = point.x_coor().unwrap().mod_floor(&q);
+
+    if to_bytes_32(r).ct_eq(&to_bytes_32(&rx)).unwrap_u8() == 1 {
+        Ok(())
+    } else {
+        Err(InvalidSig)
+    }
+}
+
+// Returns the indices of the invalid signatures if the batch does not verify.
+// sigs, pubkeys and messages are aligned: sigs[i] is expected to be a signature on messages[i]
+// under pubkeys[i].
+pub fn batch_verify(
+    sigs: &[SignatureRecid],
+    pubkeys: &[GE],
+    messages: &[BigInt],
+) -> Result<(), Vec<usize>> {
+    assert_eq!(sigs.len(), pubkeys.len());
+    assert_eq!(sigs.len(), messages.len());
+    if sigs.is_empty() {
+        return Ok(());
+    }
+    let q = FE::q();
+    let g: GE = ECPoint::generator();
+
+    let r_vec = sigs.iter().map(recover_r).collect::<Option<Vec<GE>>>();
+    let well_formed = sigs.iter().all(|sig| {
+        let s = sig.s.to_big_int();
+        !sig.r.to_big_int().is_zero() && !s.is_zero() && s <= &q - &s
+    });
+
+    let batch_ok = match r_vec {
+        Some(ref r_vec) if well_formed => {
+            let mut scalars = Vec::with_capacity(2 * sigs.len() + 1);
+            let mut points = Vec::with_capacity(2 * sigs.len() + 1);
+            let mut g_scalar = FE::zero();
+            for i in 0..sigs.len() {
+                // the first coefficient can be fixed to one
+                let a_i: FE = if i == 0 {
+                    ECScalar::from(&BigInt::one())
+                } else {
+                    ECScalar::from(&BigInt::sample(BATCH_RANDOMIZER_BITS))
+                };
+                let e_i: FE = ECScalar::from(&messages[i].mod_floor(&q));
+                g_scalar = g_scalar + a_i * e_i;
+                scalars.push(a_i * sigs[i].r);
+                points.push(pubkeys[i]);
+                let a_i_s_i = a_i * sigs[i].s;
+                scalars.push(FE::zero().sub(&a_i_s_i.get_element()));
+                points.push(r_vec[i]);
+            }
+            scalars.push(g_scalar);
+            points.push(g);
+            multi_scalar_mul(&scalars, &points).is_none()
+        }
+        _ => false,
+    };
+    if batch_ok {
+        return Ok(());
+    }
+
+    // the batch failed, locate the bad signatures one by one
+    let valid = parallel::map_indices(sigs.len(), |i| {
+        verify_with_recid(&sigs[i], &pubkeys[i], &messages[i])
+    });
+    let invalid = (0..sigs.len())
+        .filter(|&i| !valid[i])
+        .collect::<Vec<usize>>();
+    Err(invalid)
+}
+
+// the recovery id is part of what the batch checks, so a signature with a wrong
+// recid is reported as invalid as well
+fn verify_with_recid(sig: &SignatureRecid, pubkey: &GE, message: &BigInt) -> bool {
+    let r = sig.r.to_big_int();
+    let s = sig.s.to_big_int();
+    if verify(&r, &s, pubkey, message, Malleability::RequireLowS).is_err() {
+        return false;
+    }
+    let q = FE::q();
+    let e_fe: FE = ECScalar::from(&message.mod_floor(&q));
+    let s_inv = sig.s.invert();
+    let g: GE = ECPoint::generator();
+    let point = multi_scalar_mul(&[e_fe * s_inv, sig.r * s_inv], &[g, *pubkey]);
+    match (point, recover_r(sig)) {
+        (Some(point), Some(r_point)) => point == r_point,
+        _ => false,
+    }
+}
+
+// R = (r, y) where y is the square root of r^3 + 7 with the parity given by the recovery id
+fn recover_r(sig: &SignatureRecid) -> Option<GE> {
+    let p = BigInt::from_hex(FIELD_PRIME);
+    let mut x = sig.r.to_big_int();
+    if sig.recid & 2 != 0 {
+        x = x + FE::q();
+    }
+    if x >= p {
+        return None;
+    }
+    let x_cube = BigInt::mod_pow(&x, &BigInt::from(3), &p);
+    let y_square = BigInt::mod_add(&x_cube, &BigInt::from(7), &p);
+    // p = 3 mod 4
+    let exp = (&p + BigInt::one()).div_floor(&BigInt::from(4));
+    let mut y = BigInt::mod_pow(&y_square, &exp, &p);
+    if BigInt::mod_mul(&y, &y, &p) != y_square {
+        return None;
+    }
+    if y.tstbit(0) != (sig.recid & 1 == 1) {
+        y = &p - &y;
+    }
+    Some(GE::from_coor(&x, &y))
+}
+
+// big endian, left padded to the size of a scalar
+fn to_bytes_32(n: &BigInt) -> Vec<u8> {
+    let raw = BigInt::to_vec(n);
+    let mut bytes = vec![0u8; 32 - raw.len()];
+    bytes.extend(raw);
+    bytes
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,136 @@
+#![allow(non_snake_case)]
+
+use crate::utilities::ecdsa::*;
+use curv::arithmetic::traits::*;
+use curv::elliptic::curves::traits::*;
+use curv::{BigInt, FE, GE};
+
+// plain single party ECDSA, returns (r, s) with s in whatever half k happens to give
+fn sign(x: &FE, message: &BigInt) -> (BigInt, BigInt) {
+    let q = FE::q();
+    let k: FE = ECScalar::new_random();
+    let g: GE = ECPoint::generator();
+    let r = (g * k).x_coor().unwrap().mod_floor(&q);
+    let r_fe: FE = ECScalar::from(&r);
+    let m_fe: FE = ECScalar::from(&message.mod_floor(&q));
+    let s = k.invert() * (m_fe + r_fe * x);
+    (r, s.to_big_int())
+}
+
+fn low_s(s: &BigInt) -> BigInt {
+    let q = FE::q();
+    let s_neg = &q - s;
+    if s > &s_neg {
+        s_neg
+    } else {
+        s.clone()
+    }
+}
+
+#[test]
+fn test_verify() {
+    let x: FE = ECScalar::new_random();
+    let y = GE::generator() * x;
+    let message = BigInt::from(1234);
+    let (r, s) = sign(&x, &message);
+    let s = low_s(&s);
+
+    verify(&r, &s, &y, &message, Malleability::RequireLowS).expect("invalid signature");
+    verify(&r, &s, &y, &message, Malleability::Allow).expect("invalid signature");
+    assert!(verify(&r, &s, &y, &BigInt::from(1235), Malleability::Allow).is_err());
+    let other: FE = ECScalar::new_random();
+    let other_y = GE::generator() * other;
+    assert!(verify(&r, &s, &other_y, &message, Malleability::Allow).is_err());
+}
+
+#[test]
+fn test_verify_malleability() {
+    let x: FE = ECScalar::new_random();
+    let y = GE::generator() * x;
+    let message = BigInt::from(1234);
+    let (r, s) = sign(&x, &message);
+    let high_s = FE::q() - low_s(&s);
+
+    verify(&r, &high_s, &y, &message, Malleability::Allow).expect("invalid signature");
+    assert_eq!(
+        verify(&r, &high_s, &y, &message, Malleability::RequireLowS),
+        Err(crate::Error::InvalidSig)
+    );
+}
+
+#[test]
+fn test_verify_range() {
+    let x: FE = ECScalar::new_random();
+    let y = GE::generator() * x;
+    let message = BigInt::from(1234);
+    let (r, s) = sign(&x, &message);
+    let s = low_s(&s);
+    let q = FE::q();
+
+    for (r, s) in &[
+        (BigInt::zero(), s.clone()),
+        (r.clone(), BigInt::zero()),
+        (&r + &q, s.clone()),
+        (r.clone(), &s + &q),
+        (q.clone(), s.clone()),
+    ] {
+        assert!(verify(r, s, &y, &message, Malleability::Allow).is_err());
+    }
+}
+
+// signature in the same format output_signature() returns
+fn sign_recid(x: &FE, message: &BigInt) -> SignatureRecid {
+    let q = FE::q();
+    let k: FE = ECScalar::new_random();
+    let g: GE = ECPoint::generator();
+    let R = g * k;
+    let r: FE = ECScalar::from(&R.x_coor().unwrap().mod_floor(&q));
+    let m_fe: FE = ECScalar::from(&message.mod_floor(&q));
+    let s = k.invert() * (m_fe + r * x);
+    let mut recid = if R.y_coor().unwrap().tstbit(0) { 1 } else { 0 };
+    let s_bn = s.to_big_int();
+    let s = if s_bn > &q - &s_bn {
+        recid ^= 1;
+        ECScalar::from(&(&q - &s_bn))
+    } else {
+        s
+    };
+    SignatureRecid { r, s, recid }
+}
+
+fn batch(n: usize) -> (Vec<SignatureRecid>, Vec<GE>, Vec<BigInt>) {
+    let mut sigs = Vec::new();
+    let mut pubkeys = Vec::new();
+    let mut messages = Vec::new();
+    for i in 0..n {
+        let x: FE = ECScalar::new_random();
+        let message = BigInt::from(i as u32 + 1000);
+        sigs.push(sign_recid(&x, &message));
+        pubkeys.push(GE::generator() * x);
+        messages.push(message);
+    }
+    (sigs, pubkeys, messages)
+}
+
+#[test]
+fn test_batch_verify() {
+    let (sigs, pubkeys, messages) = batch(10);
+    batch_verify(&sigs, &pubkeys, &messages).expect("invalid batch");
+    batch_verify(&sigs[..1], &pubkeys[..1], &messages[..1]).expect("invalid batch");
+    batch_verify(&[], &[], &[]).expect("invalid batch");
+}
+
+#[test]
+fn test_batch_verify_locates_bad_signature() {
+    let (mut sigs, pubkeys, mut messages) = batch(10);
+    messages[3] = BigInt::from(1);
+    sigs[7].s = sigs[6].s;
+    assert_eq!(batch_verify(&sigs, &pubkeys, &messages), Err(vec![3, 7]));
+}
+
+#[test]
+fn test_batch_verify_wrong_recid() {
+    let (mut sigs, pubkeys, messages) = batch(5);
+    sigs[2].recid ^= 1;
+    assert_eq!(batch_verify(&sigs, &pubkeys, &messages), Err(vec![2]));
+}
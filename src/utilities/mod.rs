@@ -1,3 +1,10 @@
+pub mod batch_verify;
+pub mod ecdsa;
 pub mod mta;
+pub mod multiexp;
+pub mod parallel;
+pub mod sampling;
+#[cfg(test)]
+pub mod test_vectors;
 pub mod zk_pdl;
 pub mod zk_pdl_with_slack;
@@ -15,8 +15,8 @@
 */
 
 /// MtA is descrbied in https://eprint.iacr.org/2019/114.pdf section 3
-use curv::arithmetic::traits::Samplable;
 use curv::cryptographic_primitives::proofs::sigma_dlog::{DLogProof, ProveDLog};
+use curv::cryptographic_primitives::secret_sharing::feldman_vss::VerifiableSS;
 use curv::elliptic::curves::traits::*;
 use curv::BigInt;
 use curv::FE;
@@ -24,9 +24,13 @@ use curv::GE;
 use paillier::traits::EncryptWithChosenRandomness;
 use paillier::{Add, Decrypt, Mul};
 use paillier::{DecryptionKey, EncryptionKey, Paillier, Randomness, RawCiphertext, RawPlaintext};
+use rand::rngs::OsRng;
+use rand::{CryptoRng, RngCore};
 use serde::{Deserialize, Serialize};
 
-use crate::protocols::multi_party_ecdsa::gg_2018::party_i::PartyPrivate;
+use crate::protocols::multi_party_ecdsa::gg_2018::party_i::{PartyPrivate, SignKeys};
+use crate::utilities::parallel;
+use crate::utilities::sampling;
 use crate::Error::{self, InvalidKey};
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -43,7 +47,15 @@ pub struct MessageB {
 
 impl MessageA {
     pub fn a(a: &FE, alice_ek: &EncryptionKey) -> (Self, BigInt) {
-        let randomness = BigInt::sample_below(&alice_ek.n);
+        Self::a_with_rng(a, alice_ek, &mut OsRng)
+    }
+
+    pub fn a_with_rng<R: RngCore + CryptoRng>(
+        a: &FE,
+        alice_ek: &EncryptionKey,
+        rng: &mut R,
+    ) -> (Self, BigInt) {
+        let randomness = sampling::sample_below(rng, &alice_ek.n);
         let c_a = Paillier::encrypt_with_chosen_randomness(
             alice_ek,
             RawPlaintext::from(a.to_big_int()),
@@ -60,9 +72,18 @@ impl MessageA {
 
 impl MessageB {
     pub fn b(b: &FE, alice_ek: &EncryptionKey, c_a: MessageA) -> (Self, FE, BigInt) {
-        let beta_tag = BigInt::sample_below(&alice_ek.n);
+        Self::b_with_rng(b, alice_ek, c_a, &mut OsRng)
+    }
+
+    pub fn b_with_rng<R: RngCore + CryptoRng>(
+        b: &FE,
+        alice_ek: &EncryptionKey,
+        c_a: MessageA,
+        rng: &mut R,
+    ) -> (Self, FE, BigInt) {
+        let beta_tag = sampling::sample_below(rng, &alice_ek.n);
         let beta_tag_fe: FE = ECScalar::from(&beta_tag);
-        let randomness = BigInt::sample_below(&alice_ek.n);
+        let randomness = sampling::sample_below(rng, &alice_ek.n);
         let c_beta_tag = Paillier::encrypt_with_chosen_randomness(
             alice_ek,
             RawPlaintext::from(beta_tag),
@@ -77,8 +98,8 @@ impl MessageB {
         );
         let c_b = Paillier::add(alice_ek, b_c_a, c_beta_tag);
         let beta = FE::zero().sub(&beta_tag_fe.get_element());
-        let dlog_proof_b = DLogProof::prove(b);
-        let dlog_proof_beta_tag = DLogProof::prove(&beta_tag_fe);
+        let dlog_proof_b = sampling::prove_dlog(rng, b);
+        let dlog_proof_beta_tag = sampling::prove_dlog(rng, &beta_tag_fe);
 
         (
             Self {
@@ -91,6 +112,19 @@ impl MessageB {
         )
     }
 
+    // runs MessageB::b against the MessageA of every counterparty,
+    // alice_ek_vec[i] is the Paillier key c_a_vec[i] was encrypted under
+    pub fn b_vec(
+        b: &FE,
+        alice_ek_vec: &[EncryptionKey],
+        c_a_vec: &[MessageA],
+    ) -> Vec<(Self, FE, BigInt)> {
+        assert_eq!(alice_ek_vec.len(), c_a_vec.len());
+        parallel::map_indices(c_a_vec.len(), |i| {
+            Self::b(b, &alice_ek_vec[i], c_a_vec[i].clone())
+        })
+    }
+
     pub fn verify_proofs_get_alpha(&self, dk: &DecryptionKey, a: &FE) -> Result<FE, Error> {
         let alice_share = Paillier::decrypt(dk, &RawCiphertext::from(self.c.clone()));
         let g: GE = ECPoint::generator();
@@ -108,6 +142,16 @@ impl MessageB {
         }
     }
 
+    pub fn verify_proofs_get_alpha_vec(
+        m_b_vec: &[MessageB],
+        dk: &DecryptionKey,
+        a: &FE,
+    ) -> Result<Vec<FE>, Error> {
+        parallel::map_indices(m_b_vec.len(), |i| m_b_vec[i].verify_proofs_get_alpha(dk, a))
+            .into_iter()
+            .collect()
+    }
+
     //  another version, supporting PartyPrivate therefore binding mta to gg18.
     //  with the regular version mta can be used in general
     pub fn verify_proofs_get_alpha_gg18(
@@ -134,6 +178,39 @@ impl MessageB {
     pub fn verify_b_against_public(public_gb: &GE, mta_gb: &GE) -> bool {
         public_gb.get_element() == mta_gb.get_element()
     }
+
+    /// The gg18 MtAwc for b = w_j: verify_proofs_get_alpha, and the b the signer at index into
+    /// vss_scheme used must be its public share of the key among the signers s. Fails with
+    /// PublicShareMismatch naming index otherwise.
+    pub fn verify_proofs_get_alpha_wc(
+        &self,
+        dk: &DecryptionKey,
+        a: &FE,
+        vss_scheme: &VerifiableSS,
+        index: usize,
+        s: &[usize],
+    ) -> Result<FE, Error> {
+        let alpha = self.verify_proofs_get_alpha(dk, a)?;
+        SignKeys::verify_public_share(&self.b_proof.pk, vss_scheme, index, s)?;
+        Ok(alpha)
+    }
+
+    // m_b_vec[i] comes from the signer at indices[i] into vss_scheme
+    pub fn verify_proofs_get_alpha_wc_vec(
+        m_b_vec: &[MessageB],
+        dk: &DecryptionKey,
+        a: &FE,
+        vss_scheme: &VerifiableSS,
+        indices: &[usize],
+        s: &[usize],
+    ) -> Result<Vec<FE>, Error> {
+        assert_eq!(m_b_vec.len(), indices.len());
+        parallel::map_indices(m_b_vec.len(), |i| {
+            m_b_vec[i].verify_proofs_get_alpha_wc(dk, a, vss_scheme, indices[i], s)
+        })
+        .into_iter()
+        .collect()
+    }
 }
 
 #[cfg(test)]
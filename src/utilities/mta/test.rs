@@ -18,3 +18,72 @@ fn test_mta() {
     let right = alice_input * bob_input;
     assert_eq!(left.get_element(), right.get_element());
 }
+
+#[test]
+fn test_mta_vec() {
+    let bob_input: FE = ECScalar::new_random();
+    let alice_inputs = (0..3).map(|_| ECScalar::new_random()).collect::<Vec<FE>>();
+    let alice_keys = (0..3)
+        .map(|_| Paillier::keypair().keys())
+        .collect::<Vec<(EncryptionKey, DecryptionKey)>>();
+    let ek_vec = alice_keys
+        .iter()
+        .map(|(ek, _)| ek.clone())
+        .collect::<Vec<EncryptionKey>>();
+    let m_a_vec = (0..3)
+        .map(|i| MessageA::a(&alice_inputs[i], &ek_vec[i]).0)
+        .collect::<Vec<MessageA>>();
+
+    let m_b_vec = MessageB::b_vec(&bob_input, &ek_vec, &m_a_vec);
+    assert_eq!(m_b_vec.len(), 3);
+    for i in 0..3 {
+        let (m_b, beta, _) = &m_b_vec[i];
+        let alpha = MessageB::verify_proofs_get_alpha_vec(
+            &[m_b.clone()],
+            &alice_keys[i].1,
+            &alice_inputs[i],
+        )
+        .expect("wrong dlog or m_b")[0];
+        let left = alpha + beta;
+        let right = alice_inputs[i] * bob_input;
+        assert_eq!(left.get_element(), right.get_element());
+    }
+
+    // a message for another party does not verify under alice's key
+    assert!(MessageB::verify_proofs_get_alpha_vec(
+        &[m_b_vec[0].0.clone(), m_b_vec[1].0.clone()],
+        &alice_keys[0].1,
+        &alice_inputs[0],
+    )
+    .is_err());
+}
+
+#[test]
+fn test_vector_mta() {
+    use crate::utilities::sampling;
+    use crate::utilities::test_vectors;
+
+    test_vectors::check("mta", 2001, |rng, transcript| {
+        let (ek_alice, dk_alice) = sampling::paillier_keypair(rng);
+        let alice_input = sampling::sample_scalar(rng);
+        let bob_input = sampling::sample_scalar(rng);
+        let (m_a, randomness_a) = MessageA::a_with_rng(&alice_input, &ek_alice, rng);
+        let (m_b, beta, randomness_b) =
+            MessageB::b_with_rng(&bob_input, &ek_alice, m_a.clone(), rng);
+        let alpha = m_b
+            .verify_proofs_get_alpha(&dk_alice, &alice_input)
+            .expect("wrong dlog or m_b");
+        assert_eq!(alpha + beta, alice_input * bob_input);
+
+        transcript.record("ek", &ek_alice);
+        transcript.record("dk", &dk_alice);
+        transcript.record("a", &alice_input);
+        transcript.record("b", &bob_input);
+        transcript.record("m_a", &m_a);
+        transcript.record("randomness_a", &randomness_a);
+        transcript.record("m_b", &m_b);
+        transcript.record("beta", &beta);
+        transcript.record("randomness_b", &randomness_b);
+        transcript.record("alpha", &alpha);
+    });
+}
@@ -0,0 +1,111 @@
+/*
+    Multi-party ECDSA
+
+    Copyright 2018 by Kzen Networks
+
+    This file is part of Multi-party ECDSA library
+    (https://github.com/KZen-networks/multi-party-ecdsa)
+
+    Multi-party ECDSA is free software: you can redistribute
+    it and/or modify it under the terms of the GNU General Public
+    License as published by the Free Software Foundation, either
+    version 3 of the License, or (at your option) any later version.
+
+    @license GPL-3.0+ <https://github.com/KZen-networks/multi-party-ecdsa/blob/master/LICENSE>
+*/
+
+/// Simultaneous multi-scalar multiplication sum_i(a_i * P_i) using Straus' method
+/// (for two points this is the "Shamir trick"): all the scalars share a single chain
+/// of doublings and every point contributes one addition per non zero window.
+/// The point at infinity cannot be represented by GE and is returned as None.
+/// With the `parallel` feature long inputs are split into chunks that are multiplied
+/// on separate threads and summed at the end.
+use curv::elliptic::curves::traits::*;
+use curv::{BigInt, FE, GE};
+
+use crate::utilities::parallel;
+
+const WINDOW_BITS: usize = 4;
+const PARALLEL_CHUNK_SIZE: usize = 32;
+
+pub fn multi_scalar_mul(scalars: &[FE], points: &[GE]) -> Option<GE> {
+    assert_eq!(scalars.len(), points.len());
+    let chunks = (points.len() + PARALLEL_CHUNK_SIZE - 1) / PARALLEL_CHUNK_SIZE;
+    if !cfg!(feature = "parallel") || chunks <= 1 {
+        return straus(scalars, points);
+    }
+    let partial_sums = parallel::map_indices(chunks, |c| {
+        let start = c * PARALLEL_CHUNK_SIZE;
+        let end = std::cmp::min(start + PARALLEL_CHUNK_SIZE, points.len());
+        straus(&scalars[start..end], &points[start..end])
+    });
+    partial_sums
+        .iter()
+        .fold(None, |acc, partial| match partial {
+            Some(p) => add_point(acc, p),
+            None => acc,
+        })
+}
+
+fn straus(scalars: &[FE], points: &[GE]) -> Option<GE> {
+    let scalars_bn = scalars
+        .iter()
+        .map(|s| s.to_big_int())
+        .collect::<Vec<BigInt>>();
+    let max_bits = scalars_bn.iter().map(|s| s.bit_length()).max().unwrap_or(0);
+    let windows = (max_bits + WINDOW_BITS - 1) / WINDOW_BITS;
+
+    // table[i][d - 1] = d * points[i] for d in 1..2^WINDOW_BITS
+    let tables = points.iter().map(precompute).collect::<Vec<Vec<GE>>>();
+
+    let mut acc: Option<GE> = None;
+    for w in (0..windows).rev() {
+        for _ in 0..WINDOW_BITS {
+            acc = acc.map(|p| p + p);
+        }
+        for (s, table) in scalars_bn.iter().zip(tables.iter()) {
+            let digit = window_digit(s, w);
+            if digit != 0 {
+                acc = add_point(acc, &table[digit - 1]);
+            }
+        }
+    }
+    acc
+}
+
+// adds p to acc, taking care of the sum hitting the point at infinity
+pub fn add_point(acc: Option<GE>, p: &GE) -> Option<GE> {
+    match acc {
+        None => Some(*p),
+        Some(a) => {
+            if a.x_coor() == p.x_coor() && a.y_coor() != p.y_coor() {
+                None
+            } else {
+                Some(a + p)
+            }
+        }
+    }
+}
+
+fn precompute(p: &GE) -> Vec<GE> {
+    let mut table = Vec::with_capacity((1 << WINDOW_BITS) - 1);
+    table.push(*p);
+    for d in 1..(1 << WINDOW_BITS) - 1 {
+        let next = table[d - 1] + p;
+        table.push(next);
+    }
+    table
+}
+
+fn window_digit(s: &BigInt, w: usize) -> usize {
+    (0..WINDOW_BITS).fold(0, |acc, b| {
+        if s.tstbit(w * WINDOW_BITS + b) {
+            acc | (1 << b)
+        } else {
+            acc
+        }
+    })
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,62 @@
+use crate::utilities::multiexp::*;
+use curv::elliptic::curves::traits::*;
+use curv::{BigInt, FE, GE};
+
+#[test]
+fn test_multi_scalar_mul_matches_naive() {
+    let g: GE = ECPoint::generator();
+    for n in 1..6 {
+        let scalars = (0..n).map(|_| ECScalar::new_random()).collect::<Vec<FE>>();
+        let points = (0..n)
+            .map(|_| {
+                let x: FE = ECScalar::new_random();
+                g * x
+            })
+            .collect::<Vec<GE>>();
+        let expected = (1..n).fold(points[0] * scalars[0], |acc, i| {
+            acc + points[i] * scalars[i]
+        });
+        let result = multi_scalar_mul(&scalars, &points).expect("unexpected infinity");
+        assert_eq!(result, expected);
+    }
+}
+
+#[test]
+fn test_multi_scalar_mul_small_scalars() {
+    let g: GE = ECPoint::generator();
+    let x: FE = ECScalar::new_random();
+    let p = g * x;
+    let one: FE = ECScalar::from(&BigInt::one());
+    let two: FE = ECScalar::from(&BigInt::from(2));
+    assert_eq!(multi_scalar_mul(&[one], &[p]), Some(p));
+    let three: FE = ECScalar::from(&BigInt::from(3));
+    assert_eq!(multi_scalar_mul(&[two, one], &[g, g]), Some(g * three));
+}
+
+#[test]
+fn test_multi_scalar_mul_infinity() {
+    let g: GE = ECPoint::generator();
+    let a: FE = ECScalar::new_random();
+    let minus_a: FE = ECScalar::from(&(FE::q() - a.to_big_int()));
+    assert_eq!(multi_scalar_mul(&[a, minus_a], &[g, g]), None);
+    assert_eq!(multi_scalar_mul(&[FE::zero()], &[g]), None);
+}
+
+// long enough to be split into chunks when the `parallel` feature is enabled
+#[test]
+fn test_multi_scalar_mul_long_input() {
+    let g: GE = ECPoint::generator();
+    let n = 70;
+    let scalars = (0..n).map(|_| ECScalar::new_random()).collect::<Vec<FE>>();
+    let points = (0..n)
+        .map(|_| {
+            let x: FE = ECScalar::new_random();
+            g * x
+        })
+        .collect::<Vec<GE>>();
+    let expected = (1..n).fold(points[0] * scalars[0], |acc, i| {
+        acc + points[i] * scalars[i]
+    });
+    let result = multi_scalar_mul(&scalars, &points).expect("unexpected infinity");
+    assert_eq!(result, expected);
+}
@@ -0,0 +1,70 @@
+/*
+    Multi-party ECDSA
+
+    Copyright 2018 by Kzen Networks
+
+    This file is part of Multi-party ECDSA library
+    (https://github.com/KZen-networks/multi-party-ecdsa)
+
+    Multi-party ECDSA is free software: you can redistribute
+    it and/or modify it under the terms of the GNU General Public
+    License as published by the Free Software Foundation, either
+    version 3 of the License, or (at your option) any later version.
+
+    @license GPL-3.0+ <https://github.com/KZen-networks/multi-party-ecdsa/blob/master/LICENSE>
+*/
+
+/// Helpers for independent per-party work. With the `parallel` feature the closures run on
+/// the rayon thread pool, otherwise they run in order on the calling thread. The results are
+/// collected in index order in both cases.
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+pub fn map_indices<T, F>(len: usize, f: F) -> Vec<T>
+where
+    T: Send,
+    F: Fn(usize) -> T + Sync + Send,
+{
+    #[cfg(feature = "parallel")]
+    {
+        (0..len).into_par_iter().map(f).collect()
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        (0..len).map(f).collect()
+    }
+}
+
+pub fn all_indices<F>(len: usize, f: F) -> bool
+where
+    F: Fn(usize) -> bool + Sync + Send,
+{
+    #[cfg(feature = "parallel")]
+    {
+        (0..len).into_par_iter().all(f)
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        (0..len).all(f)
+    }
+}
+
+pub fn join<A, B, RA, RB>(a: A, b: B) -> (RA, RB)
+where
+    A: FnOnce() -> RA + Send,
+    B: FnOnce() -> RB + Send,
+    RA: Send,
+    RB: Send,
+{
+    #[cfg(feature = "parallel")]
+    {
+        rayon::join(a, b)
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        (a(), b())
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,19 @@
+use crate::utilities::parallel::*;
+
+#[test]
+fn test_map_indices_keeps_order() {
+    let squares = map_indices(100, |i| i * i);
+    assert_eq!(squares, (0..100).map(|i| i * i).collect::<Vec<usize>>());
+}
+
+#[test]
+fn test_all_indices() {
+    assert!(all_indices(50, |i| i < 50));
+    assert!(!all_indices(50, |i| i != 17));
+    assert!(all_indices(0, |_| false));
+}
+
+#[test]
+fn test_join() {
+    assert_eq!(join(|| 1 + 1, || "two"), (2, "two"));
+}
@@ -0,0 +1,233 @@
+#![allow(non_snake_case)]
+
+/*
+    Multi-party ECDSA
+
+    Copyright 2018 by Kzen Networks
+
+    This file is part of Multi-party ECDSA library
+    (https://github.com/KZen-networks/multi-party-ecdsa)
+
+    Multi-party ECDSA is free software: you can redistribute
+    it and/or modify it under the terms of the GNU General Public
+    License as published by the Free Software Foundation, either
+    version 3 of the License, or (at your option) any later version.
+
+    @license GPL-3.0+ <https://github.com/KZen-networks/multi-party-ecdsa/blob/master/LICENSE>
+*/
+
+/// Sampling driven by a caller supplied RNG. The protocol constructors have `_with_rng`
+/// variants built on these functions so a run can be reproduced from a seed, and the default
+/// constructors call them with OsRng. The two primes of a Paillier key are searched for on two
+/// threads with the `parallel` feature.
+/// The sigma protocols below produce the same proofs curv does, only with the
+/// commitment randomness taken from the given RNG, and verify with the curv verifiers.
+use curv::cryptographic_primitives::hashing::hash_sha256::HSha256;
+use curv::cryptographic_primitives::hashing::traits::Hash;
+use curv::cryptographic_primitives::proofs::sigma_correct_homomorphic_elgamal_enc::*;
+use curv::cryptographic_primitives::proofs::sigma_dlog::DLogProof;
+use curv::cryptographic_primitives::proofs::sigma_ec_ddh::*;
+use curv::cryptographic_primitives::secret_sharing::feldman_vss::{
+    ShamirSecretSharing, VerifiableSS,
+};
+use curv::elliptic::curves::traits::*;
+use curv::{BigInt, FE, GE};
+use paillier::{DecryptionKey, EncryptionKey, Keypair};
+use rand::{CryptoRng, RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+
+use crate::utilities::parallel;
+
+// same modulus size as Paillier::keypair()
+pub const PAILLIER_KEY_SIZE: usize = 2048;
+
+// uniform in [0, 2^bit_length)
+pub fn sample_bits<R: RngCore + CryptoRng>(rng: &mut R, bit_length: usize) -> BigInt {
+    if bit_length == 0 {
+        return BigInt::zero();
+    }
+    let byte_length = (bit_length + 7) / 8;
+    let mut bytes = vec![0u8; byte_length];
+    rng.fill_bytes(&mut bytes);
+    bytes[0] &= 0xff >> (8 * byte_length - bit_length);
+    BigInt::from(&bytes[..])
+}
+
+// uniform in [0, upper), by rejection
+pub fn sample_below<R: RngCore + CryptoRng>(rng: &mut R, upper: &BigInt) -> BigInt {
+    assert!(upper > &BigInt::zero());
+    let bit_length = upper.bit_length();
+    loop {
+        let candidate = sample_bits(rng, bit_length);
+        if &candidate < upper {
+            return candidate;
+        }
+    }
+}
+
+// uniform in [lower, upper)
+pub fn sample_range<R: RngCore + CryptoRng>(rng: &mut R, lower: &BigInt, upper: &BigInt) -> BigInt {
+    assert!(upper > lower);
+    lower + &sample_below(rng, &(upper - lower))
+}
+
+pub fn sample_scalar<R: RngCore + CryptoRng>(rng: &mut R) -> FE {
+    ECScalar::from(&sample_below(rng, &FE::q()))
+}
+
+// the two top bits are set so that the product of two such primes has exactly
+// 2 * bit_length bits
+fn prime_candidate<R: RngCore + CryptoRng>(rng: &mut R, bit_length: usize) -> BigInt {
+    let mut candidate = sample_bits(rng, bit_length);
+    candidate.setbit(bit_length - 1);
+    candidate.setbit(bit_length - 2);
+    candidate
+}
+
+pub fn sample_prime<R: RngCore + CryptoRng>(rng: &mut R, bit_length: usize) -> BigInt {
+    prime_candidate(rng, bit_length).nextprime()
+}
+
+// p = 2p' + 1 with p' prime
+pub fn sample_safe_prime<R: RngCore + CryptoRng>(rng: &mut R, bit_length: usize) -> BigInt {
+    loop {
+        let p_prime = sample_prime(rng, bit_length - 1);
+        let p = BigInt::from(2) * &p_prime + BigInt::one();
+        // p is prime iff it is the next prime after p - 1
+        if (&p - BigInt::one()).nextprime() == p {
+            return p;
+        }
+    }
+}
+
+fn seed<R: RngCore + CryptoRng>(rng: &mut R) -> [u8; 32] {
+    let mut seed = [0u8; 32];
+    rng.fill_bytes(&mut seed);
+    seed
+}
+
+// both starting points are drawn from rng before searching, so the keypair only depends on
+// the rng even when the two searches run concurrently with the `parallel` feature
+pub fn paillier_keypair<R: RngCore + CryptoRng>(rng: &mut R) -> (EncryptionKey, DecryptionKey) {
+    loop {
+        let p_start = prime_candidate(rng, PAILLIER_KEY_SIZE / 2);
+        let q_start = prime_candidate(rng, PAILLIER_KEY_SIZE / 2);
+        let (p, q) = parallel::join(|| p_start.nextprime(), || q_start.nextprime());
+        if p != q {
+            return Keypair { p, q }.keys();
+        }
+    }
+}
+
+// the two searches draw from their own streams seeded from rng, for the same reason
+pub fn paillier_keypair_safe_primes<R: RngCore + CryptoRng>(
+    rng: &mut R,
+) -> (EncryptionKey, DecryptionKey) {
+    loop {
+        let mut p_rng = ChaCha20Rng::from_seed(seed(rng));
+        let mut q_rng = ChaCha20Rng::from_seed(seed(rng));
+        let (p, q) = parallel::join(
+            || sample_safe_prime(&mut p_rng, PAILLIER_KEY_SIZE / 2),
+            || sample_safe_prime(&mut q_rng, PAILLIER_KEY_SIZE / 2),
+        );
+        if p != q {
+            return Keypair { p, q }.keys();
+        }
+    }
+}
+
+// Feldman VSS of secret with shares for the indices 1..=share_count, same as VerifiableSS::share
+pub fn share<R: RngCore + CryptoRng>(
+    rng: &mut R,
+    threshold: usize,
+    share_count: usize,
+    secret: &FE,
+) -> (VerifiableSS, Vec<FE>) {
+    let mut coefficients = vec![*secret];
+    for _ in 0..threshold {
+        coefficients.push(sample_scalar(rng));
+    }
+    let g: GE = ECPoint::generator();
+    let commitments = coefficients.iter().map(|c| g * c).collect::<Vec<GE>>();
+    let secret_shares = (1..=share_count)
+        .map(|index| {
+            let x: FE = ECScalar::from(&BigInt::from(index as u32));
+            // Horner's rule
+            coefficients
+                .iter()
+                .rev()
+                .fold(FE::zero(), |acc, coef| acc * x + *coef)
+        })
+        .collect::<Vec<FE>>();
+    (
+        VerifiableSS {
+            parameters: ShamirSecretSharing {
+                threshold,
+                share_count,
+            },
+            commitments,
+        },
+        secret_shares,
+    )
+}
+
+pub fn prove_dlog<R: RngCore + CryptoRng>(rng: &mut R, sk: &FE) -> DLogProof {
+    let g: GE = ECPoint::generator();
+    let sk_t_rand_commitment = sample_scalar(rng);
+    let pk_t_rand_commitment = g * sk_t_rand_commitment;
+    let pk = g * sk;
+    let challenge = HSha256::create_hash(&[
+        &pk_t_rand_commitment.bytes_compressed_to_big_int(),
+        &g.bytes_compressed_to_big_int(),
+        &pk.bytes_compressed_to_big_int(),
+    ]);
+    let challenge_fe: FE = ECScalar::from(&challenge);
+    let challenge_mul_sk = challenge_fe * *sk;
+    DLogProof {
+        pk,
+        pk_t_rand_commitment,
+        challenge_response: sk_t_rand_commitment.sub(&challenge_mul_sk.get_element()),
+    }
+}
+
+pub fn prove_homo_elgamal<R: RngCore + CryptoRng>(
+    rng: &mut R,
+    w: &HomoElGamalWitness,
+    delta: &HomoElGamalStatement,
+) -> HomoELGamalProof {
+    let s1 = sample_scalar(rng);
+    let s2 = sample_scalar(rng);
+    let A1 = delta.H * s1;
+    let A2 = delta.Y * s2;
+    let A3 = delta.G * s2;
+    let T = A1 + A2;
+    let e =
+        HSha256::create_hash_from_ge(&[&T, &A3, &delta.G, &delta.H, &delta.Y, &delta.D, &delta.E]);
+    let z1 = s1 + e * w.x;
+    let z2 = s2 + e * w.r;
+    HomoELGamalProof { T, A3, z1, z2 }
+}
+
+pub fn prove_ecddh<R: RngCore + CryptoRng>(
+    rng: &mut R,
+    w: &ECDDHWitness,
+    delta: &ECDDHStatement,
+) -> ECDDHProof {
+    let s = sample_scalar(rng);
+    let a1 = delta.g1 * s;
+    let a2 = delta.g2 * s;
+    let e = HSha256::create_hash(&[
+        &delta.g1.bytes_compressed_to_big_int(),
+        &delta.h1.bytes_compressed_to_big_int(),
+        &delta.g2.bytes_compressed_to_big_int(),
+        &delta.h2.bytes_compressed_to_big_int(),
+        &a1.bytes_compressed_to_big_int(),
+        &a2.bytes_compressed_to_big_int(),
+    ]);
+    let e: FE = ECScalar::from(&e);
+    let z = s + e * w.x;
+    ECDDHProof { a1, a2, z }
+}
+
+#[cfg(test)]
+mod test;
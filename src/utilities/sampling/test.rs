@@ -0,0 +1,100 @@
+use crate::utilities::sampling::*;
+use curv::cryptographic_primitives::proofs::sigma_dlog::ProveDLog;
+use paillier::{Decrypt, Encrypt, Paillier, RawCiphertext, RawPlaintext};
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+
+#[test]
+fn test_same_seed_same_samples() {
+    let mut rng_a = ChaCha20Rng::seed_from_u64(1);
+    let mut rng_b = ChaCha20Rng::seed_from_u64(1);
+    let upper = BigInt::from(1000);
+    for _ in 0..10 {
+        assert_eq!(sample_scalar(&mut rng_a), sample_scalar(&mut rng_b));
+        assert_eq!(
+            sample_below(&mut rng_a, &upper),
+            sample_below(&mut rng_b, &upper)
+        );
+    }
+    let mut rng_c = ChaCha20Rng::seed_from_u64(2);
+    assert_ne!(sample_scalar(&mut rng_a), sample_scalar(&mut rng_c));
+}
+
+#[test]
+fn test_sample_ranges() {
+    let mut rng = ChaCha20Rng::seed_from_u64(3);
+    let lower = BigInt::from(100);
+    let upper = BigInt::from(117);
+    for _ in 0..100 {
+        assert!(sample_bits(&mut rng, 13) < BigInt::from(1 << 13));
+        let x = sample_range(&mut rng, &lower, &upper);
+        assert!(x >= lower && x < upper);
+    }
+    assert_eq!(sample_bits(&mut rng, 0), BigInt::zero());
+}
+
+#[test]
+fn test_paillier_keypair() {
+    let (ek, dk) = paillier_keypair(&mut ChaCha20Rng::seed_from_u64(4));
+    assert_eq!(ek.n.bit_length(), PAILLIER_KEY_SIZE);
+    let m = BigInt::from(42);
+    let c = Paillier::encrypt(&ek, RawPlaintext::from(m.clone()));
+    let d = Paillier::decrypt(&dk, &RawCiphertext::from(c.0.into_owned()));
+    assert_eq!(d.0.into_owned(), m);
+
+    let (ek_again, _) = paillier_keypair(&mut ChaCha20Rng::seed_from_u64(4));
+    assert_eq!(ek.n, ek_again.n);
+}
+
+#[test]
+fn test_share() {
+    let mut rng = ChaCha20Rng::seed_from_u64(5);
+    let secret = sample_scalar(&mut rng);
+    let (vss_scheme, shares) = share(&mut rng, 2, 5, &secret);
+    assert_eq!(shares.len(), 5);
+    for (i, share) in shares.iter().enumerate() {
+        vss_scheme
+            .validate_share(share, i + 1)
+            .expect("invalid share");
+    }
+    let indices = vec![0, 2, 4];
+    let subset = indices.iter().map(|&i| shares[i]).collect::<Vec<FE>>();
+    assert_eq!(vss_scheme.reconstruct(&indices, &subset), secret);
+}
+
+#[test]
+fn test_proofs_verify() {
+    let mut rng = ChaCha20Rng::seed_from_u64(6);
+    let g: GE = ECPoint::generator();
+
+    let x = sample_scalar(&mut rng);
+    DLogProof::verify(&prove_dlog(&mut rng, &x)).expect("invalid dlog proof");
+
+    let h = GE::base_point2();
+    let ddh_witness = ECDDHWitness { x };
+    let ddh_statement = ECDDHStatement {
+        g1: g,
+        h1: g * x,
+        g2: h,
+        h2: h * x,
+    };
+    prove_ecddh(&mut rng, &ddh_witness, &ddh_statement)
+        .verify(&ddh_statement)
+        .expect("invalid ecddh proof");
+
+    let witness = HomoElGamalWitness {
+        r: sample_scalar(&mut rng),
+        x: sample_scalar(&mut rng),
+    };
+    let y = g * sample_scalar(&mut rng);
+    let statement = HomoElGamalStatement {
+        G: g,
+        H: h,
+        Y: y,
+        D: h * witness.x + y * witness.r,
+        E: g * witness.r,
+    };
+    prove_homo_elgamal(&mut rng, &witness, &statement)
+        .verify(&statement)
+        .expect("invalid homomorphic elgamal proof");
+}
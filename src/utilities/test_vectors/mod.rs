@@ -0,0 +1,116 @@
+/*
+    Multi-party ECDSA
+
+    Copyright 2018 by Kzen Networks
+
+    This file is part of Multi-party ECDSA library
+    (https://github.com/KZen-networks/multi-party-ecdsa)
+
+    Multi-party ECDSA is free software: you can redistribute
+    it and/or modify it under the terms of the GNU General Public
+    License as published by the Free Software Foundation, either
+    version 3 of the License, or (at your option) any later version.
+
+    @license GPL-3.0+ <https://github.com/KZen-networks/multi-party-ecdsa/blob/master/LICENSE>
+*/
+
+/// Known-answer test vectors. Every vector is a JSON file under test_vectors/v<VERSION>/
+/// holding the seed of a ChaCha20 RNG and the transcript (secrets, messages, proofs and
+/// outputs) of a run driven by that RNG. The runner replays the run from the stored seed
+/// and compares the transcript with the stored one.
+/// A missing or differing file fails the test. Set REGENERATE_TEST_VECTORS=1 to write the
+/// files after an intended change, and commit them.
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+// bump when the format of the vectors or of a serialized message changes on purpose
+pub const VERSION: u32 = 1;
+const REGENERATE_VAR: &str = "REGENERATE_TEST_VECTORS";
+
+#[derive(Serialize, Deserialize)]
+struct TestVector {
+    version: u32,
+    name: String,
+    seed: u64,
+    transcript: Value,
+}
+
+// everything a run produced, keyed by name
+#[derive(Default)]
+pub struct Transcript {
+    entries: Map<String, Value>,
+}
+
+impl Transcript {
+    pub fn record<T: Serialize>(&mut self, key: &str, value: &T) {
+        let value = serde_json::to_value(value).expect("unserializable transcript entry");
+        self.entries.insert(key.to_string(), value);
+    }
+
+    pub fn into_value(self) -> Value {
+        Value::Object(self.entries)
+    }
+}
+
+pub fn check<F>(name: &str, default_seed: u64, run: F)
+where
+    F: Fn(&mut ChaCha20Rng, &mut Transcript),
+{
+    let path = vector_path(name);
+    let stored = if env::var(REGENERATE_VAR).is_ok() {
+        None
+    } else {
+        let json = fs::read_to_string(&path).unwrap_or_else(|e| {
+            panic!(
+                "missing test vector {}: {}, generate it with {}=1",
+                path.display(),
+                e,
+                REGENERATE_VAR
+            )
+        });
+        let vector = serde_json::from_str::<TestVector>(&json)
+            .unwrap_or_else(|e| panic!("malformed test vector {}: {}", path.display(), e));
+        Some(vector)
+    };
+    let seed = stored.as_ref().map_or(default_seed, |vector| vector.seed);
+
+    let mut transcript = Transcript::default();
+    run(&mut ChaCha20Rng::seed_from_u64(seed), &mut transcript);
+    let transcript = transcript.into_value();
+
+    match stored {
+        Some(vector) => {
+            assert_eq!(vector.version, VERSION, "{}: wrong version", name);
+            assert_eq!(vector.name, name);
+            if vector.transcript != transcript {
+                panic!(
+                    "test vector {} does not match, rerun with {}=1 if the change is intended",
+                    name, REGENERATE_VAR
+                );
+            }
+        }
+        None => {
+            let vector = TestVector {
+                version: VERSION,
+                name: name.to_string(),
+                seed,
+                transcript,
+            };
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, serde_json::to_string_pretty(&vector).unwrap()).unwrap();
+        }
+    }
+}
+
+fn vector_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("test_vectors")
+        .join(format!("v{}", VERSION))
+        .join(format!("{}.json", name))
+}
@@ -19,20 +19,24 @@
 /// Statement: (c, pk, Q, G)
 /// witness (x, r, sk) such that Q = xG, c = Enc(pk, x, r) and Dec(sk, c) = x.
 /// note that because of the range proof, the proof is sound only for x < q/3
-use curv::arithmetic::traits::Samplable;
 use curv::cryptographic_primitives::commitments::hash_commitment::HashCommitment;
 use curv::cryptographic_primitives::commitments::traits::Commitment;
 use curv::elliptic::curves::traits::ECPoint;
 use curv::elliptic::curves::traits::ECScalar;
 use curv::{BigInt, FE, GE};
+use paillier::traits::EncryptWithChosenRandomness;
 use paillier::Paillier;
-use paillier::{Add, Decrypt, Encrypt, Mul};
-use paillier::{DecryptionKey, EncryptionKey, RawCiphertext, RawPlaintext};
+use paillier::{Add, Decrypt, Mul};
+use paillier::{DecryptionKey, EncryptionKey, Randomness, RawCiphertext, RawPlaintext};
+use rand::rngs::OsRng;
+use rand::{CryptoRng, RngCore};
 use serde::{Deserialize, Serialize};
 use std::ops::Shl;
 use zk_paillier::zkproofs::RangeProofError;
 use zk_paillier::zkproofs::RangeProofNi;
 
+use crate::utilities::sampling;
+
 #[derive(Clone)]
 pub struct PDLStatement {
     pub ciphertext: BigInt,
@@ -99,13 +103,24 @@ pub struct Verifier {}
 
 impl Verifier {
     pub fn message1(statement: &PDLStatement) -> (PDLVerifierFirstMessage, PDLVerifierState) {
-        let a_fe: FE = ECScalar::new_random();
+        Self::message1_with_rng(statement, &mut OsRng)
+    }
+
+    pub fn message1_with_rng<R: RngCore + CryptoRng>(
+        statement: &PDLStatement,
+        rng: &mut R,
+    ) -> (PDLVerifierFirstMessage, PDLVerifierState) {
+        let a_fe: FE = sampling::sample_scalar(rng);
         let a = a_fe.to_big_int();
         let q = FE::q();
         let q_sq = q.pow(2);
-        let b = BigInt::sample_below(&q_sq);
+        let b = sampling::sample_below(rng, &q_sq);
         let b_fe: FE = ECScalar::from(&b);
-        let b_enc = Paillier::encrypt(&statement.ek, RawPlaintext::from(b.clone()));
+        let b_enc = Paillier::encrypt_with_chosen_randomness(
+            &statement.ek,
+            RawPlaintext::from(b.clone()),
+            &Randomness::from(sampling::sample_below(rng, &statement.ek.n)),
+        );
         let ac = Paillier::mul(
             &statement.ek,
             RawCiphertext::from(statement.ciphertext.clone()),
@@ -113,7 +128,7 @@ impl Verifier {
         );
         let c_tag = Paillier::add(&statement.ek, ac, b_enc).0.into_owned();
         let ab_concat = a.clone() + b.clone().shl(a.bit_length());
-        let blindness = BigInt::sample_below(&q);
+        let blindness = sampling::sample_below(rng, &q);
         let c_tag_tag =
             HashCommitment::create_commitment_with_user_defined_randomness(&ab_concat, &blindness);
         let q_tag = &statement.Q * &a_fe + statement.G * b_fe;
@@ -183,12 +198,22 @@ impl Prover {
         witness: &PDLWitness,
         statement: &PDLStatement,
         verifier_first_message: &PDLVerifierFirstMessage,
+    ) -> (PDLProverFirstMessage, PDLProverState) {
+        Self::message1_with_rng(witness, statement, verifier_first_message, &mut OsRng)
+    }
+
+    // the range proof samples its own randomness, zk-paillier does not take an rng
+    pub fn message1_with_rng<R: RngCore + CryptoRng>(
+        witness: &PDLWitness,
+        statement: &PDLStatement,
+        verifier_first_message: &PDLVerifierFirstMessage,
+        rng: &mut R,
     ) -> (PDLProverFirstMessage, PDLProverState) {
         let c_tag = verifier_first_message.c_tag.clone();
         let alpha = Paillier::decrypt(&witness.dk, &RawCiphertext::from(c_tag.clone()));
         let alpha_fe: FE = ECScalar::from(&alpha.0);
         let q_hat = statement.G * alpha_fe;
-        let blindness = BigInt::sample_below(&FE::q());
+        let blindness = sampling::sample_below(rng, &FE::q());
         let c_hat = HashCommitment::create_commitment_with_user_defined_randomness(
             &q_hat.bytes_compressed_to_big_int(),
             &blindness,
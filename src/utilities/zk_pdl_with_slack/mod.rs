@@ -22,15 +22,19 @@ use curv::arithmetic::traits::Modulo;
 /// Statement: (c, pk, Q, G)
 /// witness (x, r, sk) such that Q = xG, c = Enc(pk, x, r) and Dec(sk, c) = x.
 /// note that because of the range proof, the proof has a slack in the range: x in [-q^3, q^3]
-use curv::arithmetic::traits::Samplable;
 use curv::cryptographic_primitives::hashing::hash_sha256::HSha256;
 use curv::cryptographic_primitives::hashing::traits::Hash;
 use curv::elliptic::curves::traits::ECPoint;
 use curv::elliptic::curves::traits::ECScalar;
 use curv::{BigInt, FE, GE};
 use paillier::{DecryptionKey, EncryptionKey};
+use rand::rngs::OsRng;
+use rand::{CryptoRng, RngCore};
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone)]
+use crate::utilities::sampling;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct PDLwSlackStatement {
     pub ciphertext: BigInt,
     pub ek: EncryptionKey,
@@ -47,7 +51,7 @@ pub struct PDLwSlackWitness {
     pub dk: DecryptionKey,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PDLwSlackProof {
     z: BigInt,
     u1: GE,
@@ -60,15 +64,23 @@ pub struct PDLwSlackProof {
 
 impl PDLwSlackProof {
     pub fn prove(witness: &PDLwSlackWitness, statement: &PDLwSlackStatement) -> Self {
+        Self::prove_with_rng(witness, statement, &mut OsRng)
+    }
+
+    pub fn prove_with_rng<R: RngCore + CryptoRng>(
+        witness: &PDLwSlackWitness,
+        statement: &PDLwSlackStatement,
+        rng: &mut R,
+    ) -> Self {
         let q3 = FE::q().pow(3);
         let q_N_tilde = FE::q() * &statement.N_tilde;
         let q3_N_tilde = &q3 * &statement.N_tilde;
 
-        let alpha = BigInt::sample_below(&q3);
+        let alpha = sampling::sample_below(rng, &q3);
         let one = BigInt::one();
-        let beta = BigInt::sample_range(&one, &(&statement.ek.n - &one));
-        let rho = BigInt::sample_below(&q_N_tilde);
-        let gamma = BigInt::sample_below(&q3_N_tilde);
+        let beta = sampling::sample_range(rng, &one, &(&statement.ek.n - &one));
+        let rho = sampling::sample_below(rng, &q_N_tilde);
+        let gamma = sampling::sample_below(rng, &q3_N_tilde);
 
         let z = commitment_unknown_order(
             &statement.h1,
@@ -1,5 +1,6 @@
 #![allow(non_snake_case)]
 use crate::utilities::zk_pdl_with_slack::*;
+use curv::arithmetic::traits::Samplable;
 use curv::{BigInt, FE, GE};
 use paillier::core::Randomness;
 use paillier::traits::{EncryptWithChosenRandomness, KeyGeneration};
@@ -132,3 +133,47 @@ fn test_zk_pdl_with_slack_soundness() {
     let result = proof.verify(&pdl_w_slack_statement);
     assert!(result.is_ok());
 }
+
+#[test]
+fn test_vector_zk_pdl_with_slack() {
+    use crate::utilities::sampling;
+    use crate::utilities::test_vectors;
+
+    test_vectors::check("pdl_with_slack", 3001, |rng, transcript| {
+        let (ek_tilde, dk_tilde) = sampling::paillier_keypair(rng);
+        let one = BigInt::one();
+        let phi = (&dk_tilde.p - &one) * (&dk_tilde.q - &one);
+        let h1 = sampling::sample_below(rng, &phi);
+        let xhi = sampling::sample_bits(rng, 256);
+        let h2 = BigInt::mod_pow(&h1, &(-&xhi), &ek_tilde.n);
+
+        let (ek, dk) = sampling::paillier_keypair(rng);
+        let r = sampling::sample_below(rng, &ek.n);
+        let x = sampling::sample_scalar(rng);
+        let c = Paillier::encrypt_with_chosen_randomness(
+            &ek,
+            RawPlaintext::from(x.to_big_int()),
+            &Randomness::from(r.clone()),
+        )
+        .0
+        .into_owned();
+
+        let statement = PDLwSlackStatement {
+            ciphertext: c,
+            ek,
+            Q: GE::generator() * &x,
+            G: GE::generator(),
+            h1,
+            h2,
+            N_tilde: ek_tilde.n,
+        };
+        let witness = PDLwSlackWitness { x, r, dk };
+        let proof = PDLwSlackProof::prove_with_rng(&witness, &statement, rng);
+        proof.verify(&statement).expect("invalid proof");
+
+        transcript.record("statement", &statement);
+        transcript.record("x", &witness.x);
+        transcript.record("r", &witness.r);
+        transcript.record("proof", &proof);
+    });
+}